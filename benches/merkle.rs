@@ -0,0 +1,173 @@
+//! Times Merkle construction separately from encoding, to see how much of `commit` is Keccak, the
+//! query-phase Merkle path reads, and a hash-dominated `verify` against its estimated time.
+//!
+//! Run with `cargo bench --bench merkle`.
+use std::time::{Duration, Instant};
+
+use binius_field::{BinaryField32b, BinaryField64b, BinaryField128b, Field};
+use binius_ntt::SingleThreadedNTT;
+use fri_binius::{
+    prover::{commit, prove},
+    utils::{
+        channel::{Channel, DEFAULT_NUM_QUERIES},
+        code::{LOG_RATE, RATE},
+        merkle::{Hash, Keccak256Hasher, compute_leaf_hashes, hash, merklize},
+        mle::{LagrangeBases, PackedMLE},
+    },
+    verifier::{estimate_verify_time, verify},
+};
+use rand::{Rng, thread_rng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+/// `count` distinct leaf hashes, cheaper to make than hashes of random symbols.
+fn leaf_hashes(count: usize) -> Vec<Hash> {
+    (0..count)
+        .into_par_iter()
+        .map(|i: usize| hash(&i.to_le_bytes()))
+        .collect()
+}
+
+fn random_symbols(count: usize) -> Vec<BinaryField128b> {
+    (0..count)
+        .into_par_iter()
+        .map(|_| BinaryField128b::random(thread_rng()))
+        .collect()
+}
+
+/// Times [`merklize`] alone over `leaf_count` leaf hashes (a power of two).
+fn merklize_time(leaf_count: usize) -> Duration {
+    let hashes = leaf_hashes(leaf_count);
+    let start = Instant::now();
+    let _tree = merklize::<Keccak256Hasher>(hashes);
+    start.elapsed()
+}
+
+/// Times [`compute_leaf_hashes`] alone over `symbol_count` random symbols (an even number).
+fn leaf_hashes_time(symbol_count: usize) -> Duration {
+    let symbols = random_symbols(symbol_count);
+    let start = Instant::now();
+    let _leaf_hashes = compute_leaf_hashes::<Keccak256Hasher>(&symbols);
+    start.elapsed()
+}
+
+/// Times `num_queries` random openings of a tree over `leaf_count` leaves (a power of two), path
+/// by path and then batched.
+fn merkle_paths_time(leaf_count: usize, num_queries: usize) -> (Duration, Duration) {
+    let tree = merklize::<Keccak256Hasher>(leaf_hashes(leaf_count));
+    let indices: Vec<usize> = (0..num_queries)
+        .map(|_| thread_rng().gen_range(0..leaf_count))
+        .collect();
+
+    let start = Instant::now();
+    let _single: Vec<Vec<Hash>> = indices.iter().map(|i| tree.get_merkle_path(*i)).collect();
+    let single_time = start.elapsed();
+
+    let start = Instant::now();
+    let _batched = tree.get_merkle_paths(&indices);
+    (single_time, start.elapsed())
+}
+
+/// Times `num_queries` random openings of a random codeword of `2^log_len` symbols, batched per
+/// query and then deduplicated as the prover's query phase gathers them: each distinct leaf's
+/// path is read once and copied to the queries repeating it.
+fn round_queries_time(log_len: usize, num_queries: usize) -> (Duration, Duration) {
+    let tree = merklize(compute_leaf_hashes::<Keccak256Hasher>(&random_symbols(
+        1 << log_len,
+    )));
+    let queries: Vec<usize> = (0..num_queries)
+        .map(|_| thread_rng().gen_range(0..1 << (log_len - 1)))
+        .collect();
+
+    let start = Instant::now();
+    let _per_query = tree.get_merkle_paths(&queries);
+    let per_query_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut unique = queries.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    let unique_paths = tree.get_merkle_paths(&unique);
+    let _deduplicated: Vec<Vec<Hash>> = queries
+        .iter()
+        .map(|query| unique_paths[unique.binary_search(query).unwrap()].clone())
+        .collect();
+    (per_query_time, start.elapsed())
+}
+
+/// Proves an opening of a random polynomial of `2^log_len` coefficients, then returns
+/// [`estimate_verify_time`]'s prediction for its proof together with the fastest of `runs` timed
+/// [`verify`] calls, so that one scheduling hiccup does not decide the comparison.
+fn verify_time(log_len: usize, runs: usize) -> (Duration, Duration) {
+    let coeffs: Vec<BinaryField64b> = (0..1 << log_len)
+        .map(|_| BinaryField64b::random(thread_rng()))
+        .collect();
+    let mle = PackedMLE::new(coeffs, true);
+    let ntt = SingleThreadedNTT::<BinaryField32b>::new(log_len + LOG_RATE)
+        .expect("failed to build the NTT")
+        .multithreaded();
+    let (commitment, code, merkle_tree) = commit(&mle, &ntt).expect("failed to commit");
+    let point: Vec<BinaryField128b> = (0..mle.variables)
+        .map(|_| BinaryField128b::random(thread_rng()))
+        .collect();
+    let eval = mle.get_bound_elem(0, &LagrangeBases::gen_from_point(&point));
+    let proof = prove(
+        &mle,
+        &point,
+        eval,
+        &code,
+        &commitment,
+        &merkle_tree,
+        &ntt,
+        &mut Channel::new(),
+    )
+    .expect("failed to prove");
+
+    let model = estimate_verify_time(
+        proof.fri_queried_symbols[0].len(),
+        proof.fri_queried_symbols.len(),
+        commitment.vector_commitment.depth,
+    );
+    let actual = (0..runs)
+        .map(|_| {
+            let proof = proof.clone();
+            let start = Instant::now();
+            verify(&commitment, &point, eval, proof, &ntt, &mut Channel::new())
+                .expect("honest proof failed to verify");
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default();
+    (model.estimate(), actual)
+}
+
+fn main() {
+    for log_len in [16, 18, 20, 22] {
+        // A committed codeword of 2^log_len symbols hashes into 2^(log_len - 1) leaves.
+        let leaf_time = leaf_hashes_time(1 << log_len);
+        let tree_time = merklize_time(1 << (log_len - 1));
+
+        println!(
+            "codeword 2^{log_len} (message 2^{} at rate {RATE}): leaf hashes {leaf_time:?}, merklize {tree_time:?}",
+            log_len - RATE.trailing_zeros() as usize
+        );
+    }
+
+    let (single_time, batched_time) = merkle_paths_time(1 << 22, DEFAULT_NUM_QUERIES);
+    println!("tree 2^22: per-query paths {single_time:?}, batched paths {batched_time:?}");
+
+    // Small codewords stand in for the late rounds, where queries collide.
+    for log_len in [4, 12, 22] {
+        let (per_query_time, deduplicated_time) = round_queries_time(log_len, DEFAULT_NUM_QUERIES);
+        println!(
+            "codeword 2^{log_len}: per-query paths {per_query_time:?}, deduplicated {deduplicated_time:?}"
+        );
+    }
+
+    for log_len in [16, 20] {
+        let (estimate, actual) = verify_time(log_len, 5);
+        println!(
+            "verify over 2^{log_len} coefficients: estimated {estimate:?}, measured {actual:?} ({:.2}x)",
+            actual.as_secs_f64() / estimate.as_secs_f64()
+        );
+    }
+}
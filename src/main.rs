@@ -17,24 +17,27 @@ use fri_binius::{
 
 fn main() -> Result<()> {
     init_tracing().expect("failed to initialize tracing");
-    for l in 10..40 {
+    // A BinaryField32b NTT spans at most 2^32 points.
+    const MAX_LOG_LEN: usize = 32 - LOG_RATE;
+    // One NTT sized for the largest polynomial commits to, opens and verifies every smaller one.
+    let ntt = SingleThreadedNTT::<BinaryField32b>::new(MAX_LOG_LEN + LOG_RATE)
+        .unwrap()
+        .multithreaded();
+
+    for l in 10..=MAX_LOG_LEN {
         println!(
             "--------------|| length 2^{:?} ||-------------- \n\n",
             l + 6
         );
-        let poly: Vec<BinaryField64b> = (0..1 << l)
-            .into_par_iter()
-            .map(|_| BinaryField64b::random(thread_rng()))
-            .collect();
-
-        let poly = PackedMLE::new(poly, true);
-
-        let ntt = SingleThreadedNTT::<BinaryField32b>::new(l + LOG_RATE)
-            .unwrap()
-            .multithreaded();
+        let poly = PackedMLE::from_par_iter(
+            (0..1 << l)
+                .into_par_iter()
+                .map(|_| BinaryField64b::random(thread_rng())),
+            true,
+        );
 
         let (commitment, encoded_poly, merkle_tree) =
-            commit::<BinaryField64b, BinaryField32b>(&poly, &ntt);
+            commit::<BinaryField64b, BinaryField32b>(&poly, &ntt)?;
 
         let point: Vec<BinaryField128b> = (0..l + 6)
             .into_iter()
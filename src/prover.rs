@@ -1,52 +1,611 @@
+use std::collections::HashMap;
+
 use crate::{
     Result,
     utils::{
         TAU,
-        channel::Channel,
-        code::{Code, LOG_RATE},
-        merkle::{Hash, MerkleTree, VectorCommitment, compute_leaf_hashes, merklize},
-        mle::{LagrangeBases, PackedMLE, compute_row_batch},
+        bytes::{ByteReader, ProofEncoding, put_field, put_hash, put_u64},
+        channel::{Channel, oracle_digest},
+        code::{Code, LOG_RATE, RATE, twiddle_fingerprint},
+        merkle::{
+            DEFAULT_SYMBOLS_PER_LEAF, Hash, Keccak256Hasher, MerkleHasher, MerkleMultiProof,
+            MerkleTree, VectorCommitment, codeword_index_to_leaf_index,
+            leaf_index_to_codeword_index,
+        },
+        mle::{
+            AffineDomain, ColumnView, LagrangeBases, PackedMLE, compute_dot_product, compute_eq,
+            compute_row_batch,
+        },
+        parallel::parallelism_available,
     },
 };
+use anyhow::{Context, ensure};
 use binius_field::{
-    BinaryField, BinaryField128b, ExtensionField, Field, PackedExtension, PackedField, TowerField,
+    BinaryField, BinaryField1b, BinaryField32b, BinaryField64b, BinaryField128b, ExtensionField,
+    Field, PackedExtension, PackedField, TowerField,
+};
+use binius_ntt::{AdditiveNTT, MultithreadedNTT};
+use rayon::{
+    ThreadPool,
+    iter::{IntoParallelIterator, ParallelIterator},
 };
-use binius_ntt::MultithreadedNTT;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use tracing::instrument;
+use sha3::{Digest, Keccak256};
+use tracing::{instrument, warn};
+
+/// Checks that the NTT, the message length, `RATE` and the subfield `P` agree before encoding.
+///
+/// The NTT may be larger than the message needs: a transform and the fold twiddles only read the
+/// subspace evaluations of their own rounds, which are the same in every NTT built over a prefix
+/// of the same domain basis. One NTT sized for the largest polynomial can therefore commit to and
+/// open every smaller one, and the verifier may use that NTT or one sized to the codeword.
+pub fn validate_params<F, P>(mle: &PackedMLE<F>, ntt: &MultithreadedNTT<P>) -> Result<()>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    let message_len = mle.coeffs.len();
+    ensure!(
+        message_len.is_power_of_two(),
+        "message length {message_len} is not a power of two"
+    );
+
+    let log_message_len = message_len.trailing_zeros() as usize;
+    ensure!(
+        ntt.log_domain_size() >= log_message_len + LOG_RATE,
+        "NTT log domain size {} is below message log length {log_message_len} + LOG_RATE {LOG_RATE}",
+        ntt.log_domain_size()
+    );
 
+    ensure!(
+        F::N_BITS >= P::N_BITS && F::N_BITS % P::N_BITS == 0,
+        "NTT field of {} bits is not a subfield of the message field of {} bits",
+        P::N_BITS,
+        F::N_BITS
+    );
+
+    let degree = <BinaryField128b as ExtensionField<F>>::DEGREE;
+    ensure!(
+        message_len >= degree,
+        "message length {message_len} is smaller than the repacking degree {degree}"
+    );
+
+    let codeword_len = (message_len / degree)
+        .checked_mul(RATE)
+        .context("codeword length overflows usize")?;
+    ensure!(
+        codeword_len.is_power_of_two(),
+        "codeword length {codeword_len} is not a power of two"
+    );
+
+    Ok(())
+}
+
+/// Encodes `mle` under `ntt` and Merklizes the codeword, returning the commitment with the
+/// codeword and tree the prover opens.
+///
+/// A [`PackedMLE`] already stores hypercube evaluations — `get_bound_elem` contracts them against
+/// the Lagrange basis — so no basis conversion is needed before encoding.
 #[instrument(skip_all, name = "commit", level = "debug")]
 pub fn commit<F, P>(
     mle: &PackedMLE<F>,
     ntt: &MultithreadedNTT<P>,
-) -> (FriCommitment, Code<BinaryField128b>, MerkleTree)
+) -> Result<(FriCommitment, Code<BinaryField128b>, MerkleTree)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    commit_with_hasher(mle, ntt)
+}
+
+/// [`commit`] with the Merkle tree built by `H`, e.g. [`Sha3_512Hasher`] for 256-bit
+/// second-preimage resistance. [`prove`] and [`verify`](crate::verifier::verify) take the hasher
+/// from the commitment.
+///
+/// [`Sha3_512Hasher`]: crate::utils::merkle::Sha3_512Hasher
+pub fn commit_with_hasher<F, P, H>(
+    mle: &PackedMLE<F>,
+    ntt: &MultithreadedNTT<P>,
+) -> Result<(FriCommitment<H>, Code<BinaryField128b>, MerkleTree<H>)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+    H: MerkleHasher,
+{
+    commit_leaves(mle, ntt, DEFAULT_SYMBOLS_PER_LEAF, 0, TAU)
+}
+
+/// [`commit`] to `mle` with the highest variables it is constant in dropped, keeping more than
+/// `TAU`. The returned truncation opens `mle` at any point through the point's leading
+/// coordinates, with fewer sum-check and FRI rounds; see [`verify_effective`].
+///
+/// [`verify_effective`]: crate::verifier::verify_effective
+pub fn commit_effective<F, P>(
+    mle: &PackedMLE<F>,
+    ntt: &MultithreadedNTT<P>,
+) -> Result<(
+    PackedMLE<F>,
+    FriCommitment,
+    Code<BinaryField128b>,
+    MerkleTree,
+)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    let variables = mle.effective_variables().max(TAU + 1).min(mle.variables);
+    let truncated = mle.truncated(variables);
+    let (commitment, code, merkle_tree) = commit(&truncated, ntt)?;
+    Ok((truncated, commitment, code, merkle_tree))
+}
+
+/// Commits to several polynomials of the same shape under one root, as the one polynomial that
+/// stacks them along new top variables: `mles[i]` fills the block whose top variables spell `i`,
+/// and blocks past `mles.len()` are zero up to a power of two. Returns the stacked polynomial to
+/// open with [`prove_batch`] alongside the usual [`commit`] output.
+pub fn commit_batch<F, P>(
+    mles: &[PackedMLE<F>],
+    ntt: &MultithreadedNTT<P>,
+) -> Result<(
+    PackedMLE<F>,
+    FriCommitment,
+    Code<BinaryField128b>,
+    MerkleTree,
+)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    let first = mles
+        .first()
+        .context("a batch needs at least one polynomial")?;
+    ensure!(
+        mles.iter()
+            .all(|mle| mle.variables == first.variables
+                && mle.packing_factor == first.packing_factor),
+        "batched polynomials must share their variable count and packing"
+    );
+
+    let mut coeffs: Vec<F> = mles
+        .iter()
+        .flat_map(|mle| mle.coeffs.iter().copied())
+        .collect();
+    coeffs.resize(first.coeffs.len() * mles.len().next_power_of_two(), F::ZERO);
+    let stacked = PackedMLE::new(coeffs, first.packing_factor != 0);
+    let (commitment, code, merkle_tree) = commit(&stacked, ntt)?;
+    Ok((stacked, commitment, code, merkle_tree))
+}
+
+/// [`commit`] to `len` coefficients produced lazily by `iter`, returning the [`PackedMLE`] they
+/// form alongside the usual output. The NTT needs the whole message, so the coefficients are
+/// buffered once, straight into the message the encoder reads; `packed` is as in
+/// [`PackedMLE::new`].
+pub fn commit_from_iter<F, P, I>(
+    iter: I,
+    len: usize,
+    packed: bool,
+    ntt: &MultithreadedNTT<P>,
+) -> Result<(
+    PackedMLE<F>,
+    FriCommitment,
+    Code<BinaryField128b>,
+    MerkleTree,
+)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+    I: IntoIterator<Item = F>,
+{
+    let mut iter = iter.into_iter();
+    let mut coeffs = Vec::with_capacity(len);
+    coeffs.extend(iter.by_ref().take(len));
+    ensure!(
+        coeffs.len() == len,
+        "iterator yielded {} coefficients, expected {len}",
+        coeffs.len()
+    );
+    ensure!(
+        iter.next().is_none(),
+        "iterator yielded more than {len} coefficients"
+    );
+
+    let mle = PackedMLE::try_new(coeffs, packed)?;
+    let (commitment, code, merkle_tree) = commit(&mle, ntt)?;
+    Ok((mle, commitment, code, merkle_tree))
+}
+
+/// [`commit_with_hasher`]'s [`FriCommitment`] alone, for when the polynomial will not be
+/// opened: the codeword and Merkle tree are dropped once the root is known.
+pub fn commit_root_only<F, P, H>(
+    mle: &PackedMLE<F>,
+    ntt: &MultithreadedNTT<P>,
+) -> Result<FriCommitment<H>>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+    H: MerkleHasher,
+{
+    let (commitment, _, _) = commit_leaves(mle, ntt, DEFAULT_SYMBOLS_PER_LEAF, 0, TAU)?;
+    Ok(commitment)
+}
+
+/// Merkle root [`commit_with_hasher`] would produce under `H` for the packed coefficients
+/// `message`, for checking a root claimed by an external committer. Name `H` with a turbofish,
+/// as for [`merklize`](crate::utils::merkle::merklize).
+pub fn expected_root<F, P, H>(message: &[F], ntt: &MultithreadedNTT<P>) -> Result<H::Digest>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+    H: MerkleHasher,
+{
+    let mle = PackedMLE::try_new(message.to_vec(), true)?;
+    let (commitment, _, _) = commit_leaves::<F, P, H>(&mle, ntt, DEFAULT_SYMBOLS_PER_LEAF, 0, TAU)?;
+    Ok(commitment.vector_commitment.root)
+}
+
+/// Commits to a codeword encoded elsewhere (e.g. on a GPU), Merklizing it with `H` without
+/// running the NTT. `packing_factor` is the tower level of the message field, as recorded by
+/// [`commit`].
+///
+/// Only the shape is checked: the caller vouches that `encoding` is the rate-`RATE` [`Code`] of
+/// the intended message under the verifier's NTT, in the default coset layout.
+pub fn commit_from_encoding<H: MerkleHasher>(
+    encoding: Vec<BinaryField128b>,
+    packing_factor: usize,
+) -> Result<(FriCommitment<H>, Code<BinaryField128b>, MerkleTree<H>)> {
+    ensure!(
+        encoding.len().is_power_of_two(),
+        "encoding length {} is not a power of two",
+        encoding.len()
+    );
+    ensure!(
+        packing_factor <= BinaryField128b::TOWER_LEVEL,
+        "packing factor {packing_factor} exceeds the tower level of BinaryField128b"
+    );
+    // Each repacked message symbol carries 128 bits, i.e. 2^7 hypercube points.
+    let log_message_len = (encoding.len().trailing_zeros() as usize)
+        .checked_sub(LOG_RATE)
+        .context("encoding is shorter than RATE")?;
+    let variables = log_message_len + BinaryField128b::N_BITS.trailing_zeros() as usize;
+    ensure!(
+        variables > TAU,
+        "encoded polynomial has {variables} variables, openings need more than TAU = {TAU}"
+    );
+
+    let code = Code {
+        encoding,
+        coset_offset: 0,
+    };
+    commit_codeword(
+        code,
+        DEFAULT_SYMBOLS_PER_LEAF,
+        packing_factor,
+        variables,
+        TAU,
+    )
+}
+
+/// Field a bit-vector witness is packed into by [`commit_bits`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FieldChoice {
+    B32,
+    #[default]
+    B64,
+    B128,
+}
+
+impl FieldChoice {
+    pub fn bits(self) -> usize {
+        match self {
+            FieldChoice::B32 => 32,
+            FieldChoice::B64 => 64,
+            FieldChoice::B128 => 128,
+        }
+    }
+
+    /// Number of coefficients `bit_len` bits pack into, padded to a power of two and to at least
+    /// one `BinaryField128b` symbol. The NTT passed to [`commit_bits`] needs a log domain size of
+    /// at least `log2(packed_len) + LOG_RATE`.
+    pub fn packed_len(self, bit_len: usize) -> usize {
+        bit_len
+            .div_ceil(self.bits())
+            .next_power_of_two()
+            .max(128 / self.bits())
+    }
+}
+
+/// [`PackedMLE`] built by [`commit_bits`], typed by the [`FieldChoice`] it was packed into.
+#[derive(Clone, Debug)]
+pub enum PackedBits {
+    B32(PackedMLE<BinaryField32b>),
+    B64(PackedMLE<BinaryField64b>),
+    B128(PackedMLE<BinaryField128b>),
+}
+
+/// Prover-side data behind a [`commit_bits`] commitment. `bit_len` is the true length of the
+/// bit-vector; the hypercube values past it are zero padding.
+#[derive(Clone, Debug)]
+pub struct BitWitness {
+    pub mle: PackedBits,
+    pub bit_len: usize,
+    pub code: Code<BinaryField128b>,
+    pub merkle_tree: MerkleTree,
+}
+
+impl BitWitness {
+    /// The committed multilinear extension of the padded bits evaluated at `point`.
+    pub fn evaluate(&self, point: &[BinaryField128b]) -> BinaryField128b {
+        let eq = LagrangeBases::gen_from_point(point);
+        match &self.mle {
+            PackedBits::B32(mle) => mle.get_bound_elem(0, &eq),
+            PackedBits::B64(mle) => mle.get_bound_elem(0, &eq),
+            PackedBits::B128(mle) => mle.get_bound_elem(0, &eq),
+        }
+    }
+
+    /// [`prove`] an evaluation of the committed bits.
+    pub fn prove<P>(
+        &self,
+        eval_point: &[BinaryField128b],
+        eval: BinaryField128b,
+        commitment: &FriCommitment,
+        ntt: &MultithreadedNTT<P>,
+        channel: &mut Channel,
+    ) -> Result<EvalProof>
+    where
+        BinaryField128b: ExtensionField<P> + PackedExtension<P>,
+        P: BinaryField,
+    {
+        let (code, merkle_tree) = (&self.code, &self.merkle_tree);
+        match &self.mle {
+            PackedBits::B32(mle) => prove(
+                mle,
+                eval_point,
+                eval,
+                code,
+                commitment,
+                merkle_tree,
+                ntt,
+                channel,
+            ),
+            PackedBits::B64(mle) => prove(
+                mle,
+                eval_point,
+                eval,
+                code,
+                commitment,
+                merkle_tree,
+                ntt,
+                channel,
+            ),
+            PackedBits::B128(mle) => prove(
+                mle,
+                eval_point,
+                eval,
+                code,
+                commitment,
+                merkle_tree,
+                ntt,
+                channel,
+            ),
+        }
+    }
+}
+
+fn commit_packed_bits<F, P>(
+    bits: &[BinaryField1b],
+    packed_len: usize,
+    ntt: &MultithreadedNTT<P>,
+) -> Result<(
+    PackedMLE<F>,
+    FriCommitment,
+    Code<BinaryField128b>,
+    MerkleTree,
+)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    let mut mle = PackedMLE::<F>::from_bits(bits);
+    if mle.coeffs.len() < packed_len {
+        let mut coeffs = mle.coeffs;
+        coeffs.resize(packed_len, F::ZERO);
+        mle = PackedMLE::new(coeffs, true);
+    }
+
+    let (commitment, code, merkle_tree) = commit(&mle, ntt)?;
+    Ok((mle, commitment, code, merkle_tree))
+}
+
+/// Packs a bit-vector witness into `pack_into` coefficients, zero-padding the tail (see
+/// [`FieldChoice::packed_len`]), and commits to it.
+pub fn commit_bits<P>(
+    bits: &[BinaryField1b],
+    pack_into: FieldChoice,
+    ntt: &MultithreadedNTT<P>,
+) -> Result<(FriCommitment, BitWitness)>
+where
+    BinaryField128b: ExtensionField<P> + PackedExtension<P>,
+    BinaryField64b: ExtensionField<P>,
+    BinaryField32b: ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    let packed_len = pack_into.packed_len(bits.len());
+    let (mle, commitment, code, merkle_tree) = match pack_into {
+        FieldChoice::B32 => {
+            let (mle, commitment, code, merkle_tree) =
+                commit_packed_bits::<BinaryField32b, P>(bits, packed_len, ntt)?;
+            (PackedBits::B32(mle), commitment, code, merkle_tree)
+        }
+        FieldChoice::B64 => {
+            let (mle, commitment, code, merkle_tree) =
+                commit_packed_bits::<BinaryField64b, P>(bits, packed_len, ntt)?;
+            (PackedBits::B64(mle), commitment, code, merkle_tree)
+        }
+        FieldChoice::B128 => {
+            let (mle, commitment, code, merkle_tree) =
+                commit_packed_bits::<BinaryField128b, P>(bits, packed_len, ntt)?;
+            (PackedBits::B128(mle), commitment, code, merkle_tree)
+        }
+    };
+
+    Ok((
+        commitment,
+        BitWitness {
+            mle,
+            bit_len: bits.len(),
+            code,
+            merkle_tree,
+        },
+    ))
+}
+
+/// [`commit`] hashing `symbols_per_leaf` codeword symbols into each Merkle leaf.
+///
+/// `prove`/`verify` open symbol pairs and so require the default of 2; a single symbol per leaf
+/// suits plain per-symbol openings at the cost of a tree one level deeper.
+pub fn commit_with_symbols_per_leaf<F, P>(
+    mle: &PackedMLE<F>,
+    ntt: &MultithreadedNTT<P>,
+    symbols_per_leaf: usize,
+) -> Result<(FriCommitment, Code<BinaryField128b>, MerkleTree)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    commit_leaves(mle, ntt, symbols_per_leaf, 0, TAU)
+}
+
+/// [`commit`] with the coset-0 block of the codeword placed at block `coset_offset`,
+/// to match an external verifier's layout. The offset is recorded in the commitment, and
+/// [`prove`] and [`verify`](crate::verifier::verify) fold in that layout.
+pub fn commit_with_coset_offset<F, P>(
+    mle: &PackedMLE<F>,
+    ntt: &MultithreadedNTT<P>,
+    coset_offset: usize,
+) -> Result<(FriCommitment, Code<BinaryField128b>, MerkleTree)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    commit_leaves(mle, ntt, DEFAULT_SYMBOLS_PER_LEAF, coset_offset, TAU)
+}
+
+/// Shared body of the `commit` variants, hashing `symbols_per_leaf` symbols per leaf with `H`,
+/// placing the coset-0 block at `coset_offset` and packing `2^tau` hypercube bits per symbol.
+fn commit_leaves<F, P, H>(
+    mle: &PackedMLE<F>,
+    ntt: &MultithreadedNTT<P>,
+    symbols_per_leaf: usize,
+    coset_offset: usize,
+    tau: usize,
+) -> Result<(FriCommitment<H>, Code<BinaryField128b>, MerkleTree<H>)>
 where
     BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
     F: BinaryField + TowerField + ExtensionField<P>,
     P: BinaryField + PackedField,
+    H: MerkleHasher,
 {
-    let code = Code::new_ext(&mle.coeffs, ntt);
+    ensure!(
+        tau <= TAU,
+        "tau = {tau} packs more bits than a BinaryField128b symbol holds (TAU = {TAU})"
+    );
+    // Ring switching binds the first `tau` variables; the sum-check and FRI fold the rest, so a
+    // polynomial without variables beyond `tau` has nothing to fold and cannot be opened.
+    ensure!(
+        mle.variables > tau,
+        "polynomial has {} variables, openings need more than tau = {tau}",
+        mle.variables
+    );
+
+    ensure!(
+        coset_offset < RATE,
+        "coset offset {coset_offset} must be below RATE = {RATE}"
+    );
+    let code = if tau == TAU {
+        validate_params(mle, ntt)?;
+        Code::new_ext_with_offset(&mle.coeffs, ntt, coset_offset)
+    } else {
+        let message = mle.repack_for_tau(tau);
+        validate_params(&message, ntt)?;
+        Code::new_ext_with_offset(&message.coeffs, ntt, coset_offset)
+    };
+
+    commit_codeword(
+        code,
+        symbols_per_leaf,
+        <F as TowerField>::TOWER_LEVEL,
+        mle.variables,
+        tau,
+    )
+}
+
+/// Merklizes `code` with `H`, `symbols_per_leaf` symbols per leaf, into the commitment to a
+/// `variables`-variable polynomial over the field of tower level `packing_factor`, split at `tau`.
+fn commit_codeword<H: MerkleHasher>(
+    code: Code<BinaryField128b>,
+    symbols_per_leaf: usize,
+    packing_factor: usize,
+    variables: usize,
+    tau: usize,
+) -> Result<(FriCommitment<H>, Code<BinaryField128b>, MerkleTree<H>)> {
+    ensure!(
+        symbols_per_leaf.is_power_of_two() && symbols_per_leaf <= code.encoding.len(),
+        "symbols per leaf {symbols_per_leaf} must be a power of two no larger than the codeword"
+    );
 
-    let leaf_hashes: Vec<Hash> = compute_leaf_hashes(&code.encoding);
-    let merkle_tree = merklize(leaf_hashes);
+    let merkle_tree = MerkleTree::<H>::from_symbols(&code.encoding, symbols_per_leaf);
 
     let vector_commitment = VectorCommitment {
         root: merkle_tree.get_root(),
-        depth: (code.encoding.len().trailing_zeros() - 1) as usize,
+        depth: (code.encoding.len().trailing_zeros() - symbols_per_leaf.trailing_zeros()) as usize,
     };
 
     let fri_commitment = FriCommitment {
         vector_commitment,
-        packing_factor: <F as TowerField>::TOWER_LEVEL,
+        packing_factor,
+        variables,
+        symbols_per_leaf,
+        tau,
+        coset_offset: code.coset_offset,
     };
 
-    (fri_commitment, code, merkle_tree)
+    Ok((fri_commitment, code, merkle_tree))
+}
+
+/// [`commit`] with the ring-switching split at `tau <= TAU` variables: each codeword symbol
+/// packs `2^tau` hypercube bits instead of 128, trading a longer codeword for fewer partial
+/// evaluations. [`prove`] and [`verify`](crate::verifier::verify) read the split from the
+/// commitment.
+pub fn commit_with_tau<F, P, H>(
+    mle: &PackedMLE<F>,
+    ntt: &MultithreadedNTT<P>,
+    tau: usize,
+) -> Result<(FriCommitment<H>, Code<BinaryField128b>, MerkleTree<H>)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+    H: MerkleHasher,
+{
+    commit_leaves(mle, ntt, DEFAULT_SYMBOLS_PER_LEAF, 0, tau)
 }
 
 #[instrument(skip_all, name = "commit_fri_oracle", level = "debug")]
-pub fn commit_oracle(code: &Code<BinaryField128b>) -> (VectorCommitment, MerkleTree) {
-    let leaf_hashes: Vec<Hash> = compute_leaf_hashes(&code.encoding);
-    let merkle_tree = merklize(leaf_hashes);
+pub fn commit_oracle<H: MerkleHasher>(
+    code: &Code<BinaryField128b>,
+) -> (VectorCommitment<H>, MerkleTree<H>) {
+    let merkle_tree = MerkleTree::<H>::from_symbols(&code.encoding, DEFAULT_SYMBOLS_PER_LEAF);
 
     let vector_commitment = VectorCommitment {
         root: merkle_tree.get_root(),
@@ -56,137 +615,2452 @@ pub fn commit_oracle(code: &Code<BinaryField128b>) -> (VectorCommitment, MerkleT
     (vector_commitment, merkle_tree)
 }
 
+/// Commitment to polynomials of differing sizes as one [`commit_batch`] oracle: each polynomial
+/// is zero-padded to the largest size, and the padded polynomials are stacked along selector
+/// variables, so `stacked(x, 0, ..., 0, i) = mles[i](x)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeterogeneousCommitment {
+    pub commitment: FriCommitment,
+    pub variables: Vec<usize>,
+}
+
+impl HeterogeneousCommitment {
+    /// Maps a point on the `variables[index]`-variable polynomial to the stacked polynomial: zeros
+    /// in the padded (high) coordinates, where the padded polynomial agrees with the original,
+    /// followed by the bits of `index` in the selector coordinates.
+    pub fn padded_point(
+        &self,
+        index: usize,
+        point: &[BinaryField128b],
+    ) -> Result<Vec<BinaryField128b>> {
+        let variables = *self
+            .variables
+            .get(index)
+            .with_context(|| format!("no polynomial at index {index}"))?;
+        ensure!(
+            point.len() == variables,
+            "point for polynomial {index} has {} coordinates, polynomial has {variables} variables",
+            point.len()
+        );
+
+        let selector_variables = self.variables.len().next_power_of_two().trailing_zeros() as usize;
+        let padded_variables = self
+            .commitment
+            .variables()
+            .checked_sub(selector_variables)
+            .context("commitment has fewer variables than its selector")?;
+        let mut padded = point.to_vec();
+        padded.resize(padded_variables, BinaryField128b::ZERO);
+        padded.extend((0..selector_variables).map(|bit| {
+            if (index >> bit) & 1 == 1 {
+                BinaryField128b::ONE
+            } else {
+                BinaryField128b::ZERO
+            }
+        }));
+        Ok(padded)
+    }
+}
+
+/// Prover-side data behind a [`HeterogeneousCommitment`]: the stacked polynomial with its
+/// encoding and Merkle tree.
+#[derive(Clone, Debug)]
+pub struct HeterogeneousWitness<F>
+where
+    F: BinaryField + TowerField,
+    BinaryField128b: ExtensionField<F>,
+{
+    pub stacked: PackedMLE<F>,
+    pub code: Code<BinaryField128b>,
+    pub merkle_tree: MerkleTree,
+}
+
+/// Commits to `mles` of differing sizes under one root by padding each to the largest size with
+/// zeros in its high indices, so `p_padded(x, 0, ..., 0) = p(x)`, and stacking the padded
+/// polynomials with [`commit_batch`].
+pub fn commit_heterogeneous<F, P>(
+    mles: &[PackedMLE<F>],
+    ntt: &MultithreadedNTT<P>,
+) -> Result<(HeterogeneousCommitment, HeterogeneousWitness<F>)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    let max_len = mles
+        .iter()
+        .map(|mle| mle.coeffs.len())
+        .max()
+        .context("no polynomials to commit")?;
+
+    let padded: Vec<PackedMLE<F>> = mles
+        .iter()
+        .map(|mle| {
+            let mut coeffs = mle.coeffs.clone();
+            coeffs.resize(max_len, F::ZERO);
+            PackedMLE::new(coeffs, mle.packing_factor > 0)
+        })
+        .collect();
+    let (stacked, commitment, code, merkle_tree) = commit_batch(&padded, ntt)?;
+
+    Ok((
+        HeterogeneousCommitment {
+            commitment,
+            variables: mles.iter().map(|mle| mle.variables).collect(),
+        },
+        HeterogeneousWitness {
+            stacked,
+            code,
+            merkle_tree,
+        },
+    ))
+}
+
 ///We assume that each coefficient of mle actually represents a packed vector of F_2 elements equal to number of bits required to represent F or
 ///F's dimension as a vector space over F_2
 
 #[instrument(skip_all, name = "prove", level = "debug")]
-pub fn prove<F, P>(
+pub fn prove<F, P, H>(
     mle: &PackedMLE<F>,
     eval_point: &[BinaryField128b],
     eval: BinaryField128b,
     encoding: &Code<BinaryField128b>,
-    commitment: &FriCommitment,
-    merkle_tree: &MerkleTree,
+    commitment: &FriCommitment<H>,
+    merkle_tree: &MerkleTree<H>,
     ntt: &MultithreadedNTT<P>,
     channel: &mut Channel,
-) -> Result<EvalProof>
+) -> Result<EvalProof<H>>
 where
     BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
     F: BinaryField + TowerField,
     P: BinaryField,
+    H: MerkleHasher,
 {
-    //The statement should be observed
-    channel.observe_fri_commitment(commitment);
-    channel.observe_field_elems(eval_point)?;
-    channel.observe_field_elem(eval)?;
-
-    let (_, right) = eval_point.split_at(TAU);
-    let mut right_eq = LagrangeBases::gen_from_point(right);
-
-    // Get partial evaluations for the binding of the latter variables.
-    let upper_partial_evals = get_partial_evals(mle, &right_eq);
-    let tensor_batching_point = channel.get_random_points(TAU)?;
-
-    let batching_eq = LagrangeBases::gen_from_point(&tensor_batching_point);
-
-    // After appropriately row-wise batching the ring-switch claims, reinterpret the received MLE as having coefficients in the 128 degree extension.
-    let mut repacked_mle = mle.clone().repack_for_fri();
+    prove_bound(
+        mle,
+        eval_point,
+        None,
+        None,
+        eval,
+        encoding,
+        commitment,
+        merkle_tree,
+        ntt,
+        channel,
+    )
+}
 
-    let mut sum_check_claim = compute_row_batch(&batching_eq.vals, &upper_partial_evals);
+/// Hash commitment to an evaluation point, letting the transcript bind to a large point through
+/// 32 bytes; the point itself is its opening.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PointCommitment(pub Hash);
 
-    let mut tensored_eq = right_eq.row_batch(&batching_eq);
+impl PointCommitment {
+    pub fn new(point: &[BinaryField128b]) -> PointCommitment {
+        let mut hasher = Keccak256::new();
+        hasher.update((point.len() as u64).to_le_bytes());
+        for coord in point {
+            hasher.update(coord.val().to_le_bytes());
+        }
+        PointCommitment(Hash(hasher.finalize()))
+    }
 
-    let rounds = right.len();
+    /// Checks that `point` opens this commitment.
+    pub fn check_opening(&self, point: &[BinaryField128b]) -> Result<()> {
+        ensure!(
+            PointCommitment::new(point) == *self,
+            "Evaluation point does not open the point commitment"
+        );
+        Ok(())
+    }
+}
 
-    let mut proof_state = ProofState::default();
+impl Channel {
+    pub fn observe_point_commitment(&mut self, commitment: &PointCommitment) {
+        self.absorb_bytes(&commitment.0.0);
+    }
+}
 
-    let final_code_folded_value = commit_phase(
-        rounds,
+/// [`prove`] with the transcript bound to `point_commitment` instead of the full `eval_point`,
+/// which must open it. Verify with
+/// [`verify_with_point_commitment`](crate::verifier::verify_with_point_commitment).
+pub fn prove_with_point_commitment<F, P>(
+    mle: &PackedMLE<F>,
+    eval_point: &[BinaryField128b],
+    point_commitment: &PointCommitment,
+    eval: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<EvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    point_commitment.check_opening(eval_point)?;
+    prove_bound(
+        mle,
+        eval_point,
+        Some(point_commitment),
+        None,
+        eval,
         encoding,
+        commitment,
+        merkle_tree,
         ntt,
         channel,
-        &mut proof_state,
-        &mut repacked_mle,
-        &mut tensored_eq,
-        &mut sum_check_claim,
-    )?;
+    )
+}
 
-    channel.observe_field_elem(final_code_folded_value)?;
+/// Equality tables of a fixed evaluation point, built once and reused by every
+/// [`prove_with_eq_tables`] and
+/// [`verify_with_eq_tables`](crate::verifier::verify_with_eq_tables) opening at that point.
+pub struct PointEqTables {
+    point: Vec<BinaryField128b>,
+    /// Eq table of the first `TAU` coordinates, against which the verifier binds the evaluation.
+    pub left_eq: Vec<BinaryField128b>,
+    /// Eq table of the remaining coordinates, against which the prover takes partial evaluations.
+    pub right_eq: LagrangeBases,
+}
 
-    let (round_queried_symbols, round_merkle_paths) =
-        query_phase(rounds, encoding, merkle_tree, channel, &proof_state)?;
+impl PointEqTables {
+    pub fn new(point: &[BinaryField128b]) -> Result<PointEqTables> {
+        validate_eval_point(point)?;
+        let (left, right) = point.split_at(TAU);
+        Ok(PointEqTables {
+            point: point.to_vec(),
+            left_eq: compute_eq(left),
+            right_eq: LagrangeBases::gen_from_point(right),
+        })
+    }
 
-    Ok(EvalProof::new(
-        upper_partial_evals,
-        proof_state,
-        final_code_folded_value,
-        round_queried_symbols,
-        round_merkle_paths,
-    ))
+    pub fn point(&self) -> &[BinaryField128b] {
+        &self.point
+    }
 }
 
-/// Ordered pair of sibling code symbols opened at a query index.
-pub type FriSymbolPair = (BinaryField128b, BinaryField128b);
-/// All queried symbol pairs within a single FRI round.
-pub type FriRoundSymbols = Vec<FriSymbolPair>;
+/// [`prove`] at `eq_tables.point()`, reusing its precomputed eq table.
+pub fn prove_with_eq_tables<F, P>(
+    mle: &PackedMLE<F>,
+    eq_tables: &PointEqTables,
+    eval: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<EvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    prove_bound(
+        mle,
+        eq_tables.point(),
+        None,
+        Some(eq_tables),
+        eval,
+        encoding,
+        commitment,
+        merkle_tree,
+        ntt,
+        channel,
+    )
+}
+
+/// Shared body of the `prove` variants: the statement observes `point_commitment` when given,
+/// the full point otherwise, and `eq_tables` stands in for the point's eq table when given.
+fn prove_bound<F, P, H>(
+    mle: &PackedMLE<F>,
+    eval_point: &[BinaryField128b],
+    point_commitment: Option<&PointCommitment>,
+    eq_tables: Option<&PointEqTables>,
+    eval: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment<H>,
+    merkle_tree: &MerkleTree<H>,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<EvalProof<H>>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+    H: MerkleHasher,
+{
+    let (proof, _) = prove_bound_with_queries(
+        mle,
+        eval_point,
+        point_commitment,
+        eq_tables,
+        eval,
+        encoding,
+        commitment,
+        merkle_tree,
+        ntt,
+        channel,
+    )?;
+    Ok(proof)
+}
+
+/// [`prove_bound`] also returning the round-0 leaf indices of the query phase.
+fn prove_bound_with_queries<F, P, H>(
+    mle: &PackedMLE<F>,
+    eval_point: &[BinaryField128b],
+    point_commitment: Option<&PointCommitment>,
+    eq_tables: Option<&PointEqTables>,
+    eval: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment<H>,
+    merkle_tree: &MerkleTree<H>,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<(EvalProof<H>, Vec<usize>)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+    H: MerkleHasher,
+{
+    let tau = check_opening_inputs(mle, [eval_point], encoding, commitment)?;
+
+    //The statement should be observed
+    channel.observe_fri_commitment(commitment);
+    match point_commitment {
+        Some(point_commitment) => channel.observe_point_commitment(point_commitment),
+        None => channel.observe_field_elems(eval_point)?,
+    }
+    channel.observe_field_elem(eval)?;
+
+    let (_, right) = eval_point.split_at(tau);
+    let owned_right_eq;
+    let right_eq = match eq_tables {
+        Some(tables) => {
+            ensure!(
+                tables.point == eval_point,
+                "Eq tables were built for a different evaluation point"
+            );
+            ensure!(
+                tau == TAU,
+                "Eq tables split the point at TAU = {TAU}, commitment uses tau = {tau}"
+            );
+            &tables.right_eq
+        }
+        None => {
+            owned_right_eq = LagrangeBases::gen_from_point(right);
+            &owned_right_eq
+        }
+    };
+
+    // Get partial evaluations for the binding of the latter variables.
+    let upper_partial_evals = get_partial_evals_at(mle, right_eq, tau)?;
+    // The batching point must not be known before the partial evaluations are committed to.
+    channel.observe_field_elems(&upper_partial_evals)?;
+    // Row batching spans the 2^TAU bits of a BinaryField128b whatever the commitment's split.
+    let tensor_batching_point = channel.get_random_points(TAU)?;
+
+    let batching_eq = LagrangeBases::gen_from_point(&tensor_batching_point);
+
+    // After appropriately row-wise batching the ring-switch claims, reinterpret the received MLE as having coefficients in the 128 degree extension.
+    let repacked_mle = repack_at(mle, tau);
+
+    let sum_check_claim = compute_row_batch(
+        &batching_eq.vals,
+        &ColumnView::from_partial_evals(&upper_partial_evals)?,
+    );
+
+    let tensored_eq = right_eq.row_batch(&batching_eq);
+
+    prove_fri_phases(
+        right.len(),
+        upper_partial_evals,
+        repacked_mle,
+        tensored_eq,
+        sum_check_claim,
+        encoding,
+        merkle_tree,
+        ntt,
+        channel,
+    )
+}
+
+/// Checks shared by the provers opening `commitment` to `mle` at `points`: every point must be a
+/// valid evaluation point, `encoding` the codeword the commitment's FRI queries open, and `mle`
+/// addressable by [`get_partial_evals_at`]. Returns the commitment's ring-switch split.
+fn check_opening_inputs<'a, F, H>(
+    mle: &PackedMLE<F>,
+    points: impl IntoIterator<Item = &'a [BinaryField128b]>,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment<H>,
+) -> Result<usize>
+where
+    F: BinaryField,
+    H: MerkleHasher,
+{
+    for point in points {
+        validate_eval_point(point)?;
+    }
+    ensure!(
+        commitment.symbols_per_leaf == DEFAULT_SYMBOLS_PER_LEAF,
+        "FRI queries open symbol pairs; commitment uses {} symbols per leaf",
+        commitment.symbols_per_leaf
+    );
+    ensure!(
+        encoding.coset_offset == commitment.coset_offset,
+        "encoding uses coset offset {}, commitment records {}",
+        encoding.coset_offset,
+        commitment.coset_offset
+    );
+
+    let tau = commitment.tau();
+    ensure!(
+        tau <= TAU,
+        "Commitment uses tau = {tau}, ring switching into BinaryField128b allows at most {TAU}"
+    );
+    ensure!(
+        (tau..=MAX_PARTIAL_EVAL_VARIABLES).contains(&mle.variables),
+        "{} variables outside the addressable range {tau}..={MAX_PARTIAL_EVAL_VARIABLES}",
+        mle.variables
+    );
+    Ok(tau)
+}
+
+/// Commit and query phases shared by the provers: sum-checks `sum_check_claim` over
+/// `repacked_mle` and `tensored_eq` while folding `encoding`, then opens the drawn queries.
+/// Returns the proof carrying `upper_partial_evals` and the round-0 leaf indices of the queries.
+#[allow(clippy::too_many_arguments)]
+fn prove_fri_phases<P, H>(
+    rounds: usize,
+    upper_partial_evals: Vec<BinaryField128b>,
+    mut repacked_mle: PackedMLE<BinaryField128b>,
+    mut tensored_eq: LagrangeBases,
+    mut sum_check_claim: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    merkle_tree: &MerkleTree<H>,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<(EvalProof<H>, Vec<usize>)>
+where
+    BinaryField128b: ExtensionField<P> + PackedExtension<P>,
+    P: BinaryField,
+    H: MerkleHasher,
+{
+    let mut proof_state = ProofState::<H>::default();
+
+    let final_code_folded_value = commit_phase(
+        rounds,
+        encoding,
+        ntt,
+        channel,
+        &mut proof_state,
+        &mut repacked_mle,
+        &mut tensored_eq,
+        &mut sum_check_claim,
+    )?;
+
+    channel.observe_field_elem(final_code_folded_value)?;
+
+    let queries = draw_queries(rounds, channel)?;
+    let (round_queried_symbols, round_merkle_paths) = open_queries(
+        rounds,
+        queries.clone(),
+        Some((merkle_tree, encoding)),
+        &proof_state,
+    );
+
+    let proof = EvalProof::new(
+        upper_partial_evals,
+        proof_state,
+        final_code_folded_value,
+        round_queried_symbols,
+        round_merkle_paths,
+        twiddle_fingerprint(ntt, rounds),
+    );
+    Ok((proof, queries))
+}
+
+/// An [`EvalProof`] whose per-query Merkle paths are pruned to one [`MerkleMultiProof`] per
+/// round, sending every node at most once. Queries collide more and more as the folded trees
+/// shrink, so most of the path bytes are shared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrunedEvalProof {
+    /// The proof, with `fri_merkle_paths` left empty.
+    pub proof: EvalProof,
+    /// Round `k`'s openings of the query leaves, shifted right by `k`.
+    pub fri_multi_proofs: Vec<MerkleMultiProof>,
+}
+
+/// [`prove`] with the Merkle paths of each round pruned to a [`MerkleMultiProof`], checked by
+/// [`verify_pruned`](crate::verifier::verify_pruned).
+pub fn prove_pruned<F, P>(
+    mle: &PackedMLE<F>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<PrunedEvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    let (mut proof, queries) = prove_bound_with_queries(
+        mle,
+        eval_point,
+        None,
+        None,
+        eval,
+        encoding,
+        commitment,
+        merkle_tree,
+        ntt,
+        channel,
+    )?;
+
+    let depths = std::iter::once(&commitment.vector_commitment)
+        .chain(&proof.fri_oracles)
+        .map(|oracle| oracle.depth);
+    let fri_multi_proofs = std::mem::take(&mut proof.fri_merkle_paths)
+        .iter()
+        .zip(depths)
+        .enumerate()
+        .map(|(round, (paths, depth))| {
+            let leaves: Vec<usize> = queries.iter().map(|query| query >> round).collect();
+            MerkleMultiProof::from_paths(&leaves, paths, depth)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PrunedEvalProof {
+        proof,
+        fri_multi_proofs,
+    })
+}
+
+/// [`prove`] followed by an immediate [`verify`](crate::verifier::verify) against a copy of
+/// `channel` taken before proving, panicking with the verifier's error chain so prover bugs
+/// surface at their source.
+#[cfg(debug_assertions)]
+pub fn prove_checked<F, P>(
+    mle: &PackedMLE<F>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<EvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    // The verifier must start from the transcript the prover started from, which need not be fresh.
+    let mut initial = channel.clone();
+    let proof = prove(
+        mle,
+        eval_point,
+        eval,
+        encoding,
+        commitment,
+        merkle_tree,
+        ntt,
+        channel,
+    )?;
+
+    if let Err(err) = crate::verifier::verify(
+        commitment,
+        eval_point,
+        eval,
+        proof.clone(),
+        ntt,
+        &mut initial.clone(),
+    ) {
+        panic!(
+            "prove_checked: proof for a {}-variable opening failed self-verification: {err:?}",
+            eval_point.len()
+        );
+    }
+
+    let (challenges, _) = crate::verifier::replay_commit_phase(
+        commitment,
+        eval_point,
+        eval,
+        &proof,
+        ntt,
+        &mut initial,
+    )?;
+    let expected = expected_final_value(mle, &challenges, commitment.tau());
+    assert_eq!(
+        expected, proof.final_folded_value,
+        "prove_checked: final folded code value disagrees with the MLE bound to the sum-check challenges"
+    );
+
+    Ok(proof)
+}
+
+/// Value the FRI folding must end on: the MLE repacked at the commitment's split `tau`, evaluated
+/// at the sum-check `challenges`, computed directly from the coefficients rather than by folding
+/// the code.
+pub fn expected_final_value<F>(
+    mle: &PackedMLE<F>,
+    challenges: &[BinaryField128b],
+    tau: usize,
+) -> BinaryField128b
+where
+    F: BinaryField + TowerField,
+    BinaryField128b: ExtensionField<F>,
+{
+    let repacked_mle = repack_at(mle, tau);
+    assert_eq!(
+        repacked_mle.len(),
+        1 << challenges.len(),
+        "expected one challenge per repacked variable"
+    );
+
+    compute_dot_product(&compute_eq(challenges), &repacked_mle.coeffs)
+}
+
+/// `mle` as the `BinaryField128b` message a commitment split at `tau` encodes.
+fn repack_at<F>(mle: &PackedMLE<F>, tau: usize) -> PackedMLE<BinaryField128b>
+where
+    F: BinaryField + TowerField,
+    BinaryField128b: ExtensionField<F>,
+{
+    match tau {
+        TAU => mle.clone().repack_for_fri(),
+        _ => mle.repack_for_tau(tau),
+    }
+}
+
+/// [`commit`] with every parallel section confined to `pool` instead of the global rayon pool.
+pub fn commit_in_pool<F, P>(
+    pool: &ThreadPool,
+    mle: &PackedMLE<F>,
+    ntt: &MultithreadedNTT<P>,
+) -> Result<(FriCommitment, Code<BinaryField128b>, MerkleTree)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    pool.install(|| commit(mle, ntt))
+}
+
+/// Commits to `mle`, observes the commitment and draws the opening point from `channel`, then
+/// proves the evaluation there on the same channel, so the point is bound to the transcript.
+///
+/// A verifier reproduces the point by observing the commitment and drawing `mle.variables`
+/// points from a fresh channel before calling `verify` on it.
+pub fn prove_at_channel_point<F, P>(
+    mle: &PackedMLE<F>,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<(
+    FriCommitment,
+    Vec<BinaryField128b>,
+    BinaryField128b,
+    EvalProof,
+)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    let (commitment, code, merkle_tree) = commit(mle, ntt)?;
+
+    channel.observe_fri_commitment(&commitment);
+    let point = channel.get_random_points(mle.variables)?;
+    let eval = mle.get_bound_elem(0, &LagrangeBases::gen_from_point(&point));
+
+    let proof = prove(
+        mle,
+        &point,
+        eval,
+        &code,
+        &commitment,
+        &merkle_tree,
+        ntt,
+        channel,
+    )?;
+    Ok((commitment, point, eval, proof))
+}
+
+/// A committed polynomial with its encoding and Merkle tree, opened any number of times without
+/// re-encoding or re-Merklizing.
+pub struct ProverContext<'a, F, P>
+where
+    F: BinaryField + TowerField,
+    BinaryField128b: ExtensionField<F>,
+{
+    pub mle: PackedMLE<F>,
+    pub commitment: FriCommitment,
+    pub code: Code<BinaryField128b>,
+    pub merkle_tree: MerkleTree,
+    ntt: &'a MultithreadedNTT<P>,
+}
+
+impl<'a, F, P> ProverContext<'a, F, P>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    /// Commits to `mle` under `ntt`.
+    pub fn new(mle: PackedMLE<F>, ntt: &'a MultithreadedNTT<P>) -> Result<Self> {
+        let (commitment, code, merkle_tree) = commit(&mle, ntt)?;
+        Ok(ProverContext {
+            mle,
+            commitment,
+            code,
+            merkle_tree,
+            ntt,
+        })
+    }
+
+    pub fn evaluate(&self, point: &[BinaryField128b]) -> BinaryField128b {
+        self.mle
+            .get_bound_elem(0, &LagrangeBases::gen_from_point(point))
+    }
+
+    /// [`prove`] the polynomial's evaluation at `point`.
+    pub fn prove(&self, point: &[BinaryField128b], channel: &mut Channel) -> Result<EvalProof> {
+        prove(
+            &self.mle,
+            point,
+            self.evaluate(point),
+            &self.code,
+            &self.commitment,
+            &self.merkle_tree,
+            self.ntt,
+            channel,
+        )
+    }
+
+    /// [`prove`] the restriction of the polynomial with its first `fixed_prefix.len()` variables
+    /// fixed, at `point`: an opening of `p(fixed_prefix || point)` against the same commitment,
+    /// returned with that evaluation.
+    pub fn prove_restriction(
+        &self,
+        fixed_prefix: &[BinaryField128b],
+        point: &[BinaryField128b],
+        channel: &mut Channel,
+    ) -> Result<(BinaryField128b, EvalProof)> {
+        ensure!(
+            fixed_prefix.len() + point.len() == self.mle.variables,
+            "{} fixed and {} free coordinates for a {}-variable polynomial",
+            fixed_prefix.len(),
+            point.len(),
+            self.mle.variables
+        );
+        let full_point = [fixed_prefix, point].concat();
+        Ok((
+            self.evaluate(&full_point),
+            self.prove(&full_point, channel)?,
+        ))
+    }
+
+    /// Sets coefficient `idx` to `value` and recommits; see [`ProverContext::update_coefficients`].
+    pub fn update_coefficient(&mut self, idx: usize, value: F) -> Result<()> {
+        self.update_coefficients(&[(idx, value)])
+    }
+
+    /// Applies every `(idx, value)` coefficient update, then re-encodes and re-Merklizes once.
+    ///
+    /// No cheaper per-coefficient path exists: the code is not systematic, and changing message
+    /// symbol `j` adds a multiple of the novel basis polynomial `X_j` to the codeword, which has
+    /// degree at most `j` and so vanishes on at most `j` of the points. Almost every symbol, hence
+    /// every leaf and every path, changes, so updates should be batched to share one recommit.
+    pub fn update_coefficients(&mut self, updates: &[(usize, F)]) -> Result<()> {
+        for &(idx, _) in updates {
+            ensure!(
+                idx < self.mle.coeffs.len(),
+                "coefficient index {idx} out of bounds for {} coefficients",
+                self.mle.coeffs.len()
+            );
+        }
+        for &(idx, value) in updates {
+            self.mle.coeffs[idx] = value;
+        }
+
+        let (commitment, code, merkle_tree) = commit(&self.mle, self.ntt)?;
+        self.commitment = commitment;
+        self.code = code;
+        self.merkle_tree = merkle_tree;
+        Ok(())
+    }
+
+    /// Opens the polynomial at every point of `points`, the `i`-th on the sub-transcript
+    /// `channel.fork(i)`. Only the encoding and Merkle tree are shared: each opening folds the
+    /// code under its own challenges.
+    pub fn open_many(
+        &self,
+        points: &[Vec<BinaryField128b>],
+        channel: &Channel,
+    ) -> Result<Vec<EvalProof>> {
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, point)| {
+                self.prove(point, &mut channel.fork(i as u64))
+                    .with_context(|| format!("open at point {i}"))
+            })
+            .collect()
+    }
+
+    /// Opens the codeword at every one of `indices`, with no folding: each symbol with its Merkle
+    /// path, for [`verify_positions`](crate::verifier::verify_positions).
+    pub fn open_positions(&self, indices: &[usize]) -> Result<Vec<PositionOpening>> {
+        let len = self.code.encoding.len();
+        if let Some(index) = indices.iter().find(|&&index| index >= len) {
+            anyhow::bail!("position {index} out of bounds for a codeword of {len} symbols");
+        }
+
+        let leaves: Vec<usize> = indices
+            .iter()
+            .map(|&index| codeword_index_to_leaf_index(index))
+            .collect();
+        let paths = self.merkle_tree.get_merkle_paths(&leaves);
+        Ok(indices
+            .iter()
+            .zip(paths)
+            .map(|(&index, merkle_path)| PositionOpening {
+                value: self.code.encoding[index],
+                leaf_sibling: self.code.encoding[index ^ 1],
+                merkle_path,
+            })
+            .collect())
+    }
+}
+
+/// A codeword symbol opened by [`ProverContext::open_positions`]. Leaves hash symbol pairs, so
+/// the other symbol of the leaf comes along to rebuild the leaf hash.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PositionOpening {
+    pub value: BinaryField128b,
+    /// The symbol sharing `value`'s Merkle leaf, at the position with the lowest bit flipped.
+    pub leaf_sibling: BinaryField128b,
+    pub merkle_path: Vec<Hash>,
+}
+
+/// Proof that `Σ_i c_i p_i(point) = target` for committed `p_i`, from [`prove_linear_combination`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearCombinationProof {
+    /// Each polynomial's `upper_partial_evals`; the row batching is not linear over the
+    /// coefficients, so the verifier batches them one by one.
+    pub partial_evals: Vec<Vec<BinaryField128b>>,
+    /// Round-0 symbol pairs of each polynomial's codeword at the queries.
+    pub committed_symbols: Vec<FriRoundSymbols>,
+    /// Paths of `committed_symbols` in each polynomial's own commitment.
+    pub committed_paths: Vec<FriRoundMerklePaths>,
+    /// Opening of the combined codeword `Σ_i c_i code_i`, which is never committed: its
+    /// `upper_partial_evals` are the combined ones and its round-0 query entries are empty.
+    pub combined: EvalProof,
+}
+
+/// Proves `Σ_i coeffs[i] · p_i(point) = target` for the polynomials of `ctxs` without committing
+/// to the combination: one sum-check and FRI run over the combined codeword, whose round-0
+/// symbols the verifier rebuilds from openings of the individual commitments.
+///
+/// All polynomials must have the same number of variables and share an NTT.
+pub fn prove_linear_combination<F, P>(
+    ctxs: &[ProverContext<F, P>],
+    coeffs: &[BinaryField128b],
+    point: &[BinaryField128b],
+    target: BinaryField128b,
+    channel: &mut Channel,
+) -> Result<LinearCombinationProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    let first = ctxs.first().context("no polynomials to combine")?;
+    ensure!(
+        coeffs.len() == ctxs.len(),
+        "{} coefficients for {} polynomials",
+        coeffs.len(),
+        ctxs.len()
+    );
+    ensure!(
+        ctxs.iter()
+            .all(|ctx| ctx.mle.variables == first.mle.variables
+                && ctx.code.encoding.len() == first.code.encoding.len()),
+        "combined polynomials must share their number of variables"
+    );
+    ensure!(
+        point.len() == first.mle.variables,
+        "point has {} coordinates, polynomials have {} variables",
+        point.len(),
+        first.mle.variables
+    );
+    validate_eval_point(point)?;
+    ensure!(
+        ctxs.iter().all(|ctx| ctx.code.coset_offset == 0
+            && ctx.commitment.coset_offset == 0
+            && ctx.commitment.symbols_per_leaf == DEFAULT_SYMBOLS_PER_LEAF),
+        "combined polynomials must use the default codeword layout"
+    );
+    for (j, ctx) in ctxs.iter().enumerate() {
+        ensure!(
+            ctx.commitment.tau == TAU,
+            "Combined openings split the point at TAU = {TAU}, commitment {j} uses tau = {}",
+            ctx.commitment.tau
+        );
+    }
+    let ntt = first.ntt;
+
+    for ctx in ctxs {
+        channel.observe_fri_commitment(&ctx.commitment);
+    }
+    channel.observe_field_elems(coeffs)?;
+    channel.observe_field_elems(point)?;
+    channel.observe_field_elem(target)?;
+
+    let (_, right) = point.split_at(TAU);
+    let right_eq = LagrangeBases::gen_from_point(right);
+    let partial_evals: Vec<Vec<BinaryField128b>> = ctxs
+        .iter()
+        .map(|ctx| get_partial_evals(&ctx.mle, &right_eq))
+        .collect::<Result<_>>()?;
+    for partials in &partial_evals {
+        channel.observe_field_elems(partials)?;
+    }
+
+    let tensor_batching_point = channel.get_random_points(TAU)?;
+    let batching_eq = LagrangeBases::gen_from_point(&tensor_batching_point);
+
+    let mut sum_check_claim = BinaryField128b::ZERO;
+    let mut combined_partials = vec![BinaryField128b::ZERO; 1 << TAU];
+    let mut combined_coeffs = vec![BinaryField128b::ZERO; 1 << (first.mle.variables - TAU)];
+    let mut combined_encoding = vec![BinaryField128b::ZERO; first.code.encoding.len()];
+    for ((ctx, partials), c) in ctxs.iter().zip(&partial_evals).zip(coeffs) {
+        sum_check_claim +=
+            *c * compute_row_batch(&batching_eq.vals, &ColumnView::new(partials.clone())?);
+        for (combined, partial) in combined_partials.iter_mut().zip(partials) {
+            *combined += *c * *partial;
+        }
+        let repacked = ctx.mle.clone().repack_for_fri();
+        for (combined, coeff) in combined_coeffs.iter_mut().zip(&repacked.coeffs) {
+            *combined += *c * *coeff;
+        }
+        for (combined, symbol) in combined_encoding.iter_mut().zip(&ctx.code.encoding) {
+            *combined += *c * *symbol;
+        }
+    }
+
+    let mut repacked_mle = PackedMLE::new(combined_coeffs, false);
+    let combined_code = Code {
+        encoding: combined_encoding,
+        coset_offset: 0,
+    };
+    let mut tensored_eq = right_eq.row_batch(&batching_eq);
+
+    let rounds = right.len();
+    let mut proof_state: ProofState = ProofState::default();
+    let final_code_folded_value = commit_phase(
+        rounds,
+        &combined_code,
+        ntt,
+        channel,
+        &mut proof_state,
+        &mut repacked_mle,
+        &mut tensored_eq,
+        &mut sum_check_claim,
+    )?;
+
+    channel.observe_field_elem(final_code_folded_value)?;
+
+    let queries = draw_queries(rounds, channel)?;
+    let (committed_paths, committed_symbols) = ctxs
+        .iter()
+        .map(|ctx| gather_round_queries(&ctx.merkle_tree, &ctx.code, &queries))
+        .unzip();
+    let (round_queried_symbols, round_merkle_paths) =
+        open_queries(rounds, queries, None, &proof_state);
+
+    Ok(LinearCombinationProof {
+        partial_evals,
+        committed_symbols,
+        committed_paths,
+        combined: EvalProof::new(
+            combined_partials,
+            proof_state,
+            final_code_folded_value,
+            round_queried_symbols,
+            round_merkle_paths,
+            twiddle_fingerprint(ntt, rounds),
+        ),
+    })
+}
+
+/// Proof of a hypercube sum from [`prove_sum`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SumProof {
+    /// Linear round polynomial of each variable, lowest first.
+    pub round_oracles: Vec<Univariate>,
+    /// Opening of the polynomial at the sum-check challenges.
+    pub opening: EvalProof,
+}
+
+/// Proves the sum `Σ_x p(x)` of `ctx`'s polynomial over the boolean hypercube, returned with its
+/// proof: a sum-check binding one variable per round reduces the claim to `p(r)` at the
+/// challenges `r`, which is then opened with [`ProverContext::prove`].
+///
+/// The sum-check runs on the unpacked hypercube values, `2^variables` field elements.
+pub fn prove_sum<F, P>(
+    ctx: &ProverContext<F, P>,
+    channel: &mut Channel,
+) -> Result<(BinaryField128b, SumProof)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    let mut table = ctx.mle.fold_as_unpacked_hi(&LagrangeBases::new());
+    let sum = table.coeffs.iter().copied().sum::<BinaryField128b>();
+
+    channel.observe_fri_commitment(&ctx.commitment);
+    channel.observe_field_elem(sum)?;
+
+    let mut round_oracles = Vec::with_capacity(ctx.mle.variables);
+    let mut point = Vec::with_capacity(ctx.mle.variables);
+    for _ in 0..ctx.mle.variables {
+        // h(X) = Σ_i v_2i + X (v_2i + v_2i+1), so h(0) + h(1) is the running claim.
+        let (at_zero, slope) = table.coeffs.chunks_exact(2).fold(
+            (BinaryField128b::ZERO, BinaryField128b::ZERO),
+            |(a, b), pair| (a + pair[0], b + pair[0] + pair[1]),
+        );
+        let oracle = Univariate::new(vec![at_zero, slope]);
+        channel.observe_oracle_coeffs(&oracle.coeffs)?;
+
+        let r = channel.get_random_point()?;
+        table = table.fold_lo(&r);
+        round_oracles.push(oracle);
+        point.push(r);
+    }
+
+    let opening = ctx.prove(&point, channel)?;
+    Ok((
+        sum,
+        SumProof {
+            round_oracles,
+            opening,
+        },
+    ))
+}
+
+/// Proof that `Σ_x eq(point, x) p(x) q(x) = v`, the product `p · q` evaluated at `point`, from
+/// [`prove_product`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProductProof {
+    /// Cubic round polynomial of each variable, lowest first.
+    pub round_oracles: Vec<Univariate>,
+    /// `p(r)` and `q(r)` at the sum-check challenges `r`.
+    pub p_eval: BinaryField128b,
+    pub q_eval: BinaryField128b,
+    pub p_opening: EvalProof,
+    pub q_opening: EvalProof,
+}
+
+/// Proves `(p · q)(point) = v` for the separately committed `p` of `ctx_p` and `q` of `ctx_q`:
+/// a sum-check over `eq(point, x) p(x) q(x)`, cubic in each variable, reduces the claim to
+/// `p(r)` and `q(r)`, which are then opened with [`ProverContext::prove`].
+pub fn prove_product<F, P>(
+    ctx_p: &ProverContext<F, P>,
+    ctx_q: &ProverContext<F, P>,
+    point: &[BinaryField128b],
+    v: BinaryField128b,
+    channel: &mut Channel,
+) -> Result<ProductProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField + ExtensionField<P>,
+    P: BinaryField + PackedField,
+{
+    let variables = ctx_p.mle.variables;
+    ensure!(
+        ctx_q.mle.variables == variables && point.len() == variables,
+        "product of {variables}- and {}-variable polynomials at a {}-coordinate point",
+        ctx_q.mle.variables,
+        point.len()
+    );
+
+    let mut p = ctx_p.mle.fold_as_unpacked_hi(&LagrangeBases::new());
+    let mut q = ctx_q.mle.fold_as_unpacked_hi(&LagrangeBases::new());
+    let mut eq = LagrangeBases::gen_from_point(point);
+
+    channel.observe_fri_commitment(&ctx_p.commitment);
+    channel.observe_fri_commitment(&ctx_q.commitment);
+    channel.observe_field_elems(point)?;
+    channel.observe_field_elem(v)?;
+
+    let mut round_oracles = Vec::with_capacity(variables);
+    let mut challenges = Vec::with_capacity(variables);
+    for _ in 0..variables {
+        // Each pair contributes (e + X de)(p + X dp)(q + X dq), expanded by powers of X.
+        let coeffs = (0..p.len() / 2)
+            .map(|i| {
+                let (e, p0, q0) = (eq.idx(i << 1), p.idx(i << 1), q.idx(i << 1));
+                let de = eq.idx((i << 1) | 1) + e;
+                let dp = p.idx((i << 1) | 1) + p0;
+                let dq = q.idx((i << 1) | 1) + q0;
+                [
+                    e * p0 * q0,
+                    de * p0 * q0 + e * dp * q0 + e * p0 * dq,
+                    e * dp * dq + de * p0 * dq + de * dp * q0,
+                    de * dp * dq,
+                ]
+            })
+            .fold([BinaryField128b::ZERO; 4], |mut acc, term| {
+                for (a, t) in acc.iter_mut().zip(term) {
+                    *a += t;
+                }
+                acc
+            });
+        let oracle = Univariate::new(coeffs.to_vec());
+        channel.observe_oracle_coeffs(&oracle.coeffs)?;
+
+        let r = channel.get_random_point()?;
+        p = p.fold_lo(&r);
+        q = q.fold_lo(&r);
+        eq.fold_lo(&r);
+        round_oracles.push(oracle);
+        challenges.push(r);
+    }
+
+    let (p_eval, q_eval) = (p.idx(0), q.idx(0));
+    channel.observe_field_elem(p_eval)?;
+    channel.observe_field_elem(q_eval)?;
+    let p_opening = ctx_p.prove(&challenges, channel)?;
+    let q_opening = ctx_q.prove(&challenges, channel)?;
+
+    Ok(ProductProof {
+        round_oracles,
+        p_eval,
+        q_eval,
+        p_opening,
+        q_opening,
+    })
+}
+
+/// Reduces the claims `evals[i] = mles[i](eval_point)` on a [`commit_batch`] commitment to one
+/// claim on the stacked polynomial: after the statement, draws the top coordinates `r` and
+/// returns the point `eval_point || r` with the claim `sum_i eq(r, i) evals[i]` on it.
+///
+/// Prover and verifier both run it, so the verifier recomputes the same combination.
+pub fn batch_statement(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    evals: &[BinaryField128b],
+    channel: &mut Channel,
+) -> Result<(Vec<BinaryField128b>, BinaryField128b)> {
+    let batch_variables = commitment
+        .variables
+        .checked_sub(eval_point.len())
+        .context("evaluation point has more coordinates than the stacked polynomial")?;
+    ensure!(
+        !evals.is_empty()
+            && evals.len().next_power_of_two().trailing_zeros() as usize <= batch_variables,
+        "{} evaluations do not fit a batch over {batch_variables} top variables",
+        evals.len()
+    );
+
+    channel.observe_fri_commitment(commitment);
+    channel.observe_field_elems(eval_point)?;
+    channel.observe_field_elems(evals)?;
+    let batching_point = channel.get_random_points(batch_variables)?;
+    let claim = compute_dot_product(&compute_eq(&batching_point), evals);
+    Ok(([eval_point, &batching_point].concat(), claim))
+}
+
+/// Proves `evals[i] = mles[i](eval_point)` for every polynomial of a [`commit_batch`], given the
+/// `stacked` polynomial it returned, with a single [`prove`] of the [`batch_statement`] claim.
+pub fn prove_batch<F, P>(
+    stacked: &PackedMLE<F>,
+    eval_point: &[BinaryField128b],
+    evals: &[BinaryField128b],
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<EvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    let (point, claim) = batch_statement(commitment, eval_point, evals, channel)?;
+    prove(
+        stacked,
+        &point,
+        claim,
+        encoding,
+        commitment,
+        merkle_tree,
+        ntt,
+        channel,
+    )
+}
+
+/// Openings of one committed polynomial at several points, sharing one sum-check and one FRI run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiEvalProof {
+    /// [`EvalProof::upper_partial_evals`] of each point, in the order of the points.
+    pub upper_partial_evals: Vec<Vec<BinaryField128b>>,
+    /// The shared sum-check and FRI part, with `upper_partial_evals` left empty.
+    pub proof: EvalProof,
+}
+
+/// Proves `evals[k] = mle(points[k])` for every `k` with a single commit and query phase.
+///
+/// Once every point's ring-switch partial evaluations are observed, channel-drawn coefficients
+/// `alpha_k` combine the points' sum-check claims, and their row-batched eq tables, into the one
+/// claim the sum-check proves.
+pub fn prove_multi<F, P>(
+    mle: &PackedMLE<F>,
+    points: &[Vec<BinaryField128b>],
+    evals: &[BinaryField128b],
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<MultiEvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    ensure!(
+        !points.is_empty() && points.len() == evals.len(),
+        "{} points for {} evaluations",
+        points.len(),
+        evals.len()
+    );
+    let tau = check_opening_inputs(mle, points.iter().map(Vec::as_slice), encoding, commitment)?;
+    ensure!(
+        tau == TAU,
+        "prove_multi splits each point at TAU = {TAU}, commitment uses tau = {tau}"
+    );
+    for point in points {
+        ensure!(
+            point.len() == mle.variables,
+            "Evaluation point has {} coordinates, polynomial has {} variables",
+            point.len(),
+            mle.variables
+        );
+    }
+
+    channel.observe_fri_commitment(commitment);
+    for (point, eval) in points.iter().zip(evals) {
+        channel.observe_field_elems(point)?;
+        channel.observe_field_elem(*eval)?;
+    }
+
+    let right_eqs: Vec<LagrangeBases> = points
+        .iter()
+        .map(|point| LagrangeBases::gen_from_point(&point[TAU..]))
+        .collect();
+    let upper_partial_evals: Vec<Vec<BinaryField128b>> = right_eqs
+        .iter()
+        .map(|right_eq| get_partial_evals(mle, right_eq))
+        .collect::<Result<_>>()?;
+    for partial_evals in &upper_partial_evals {
+        channel.observe_field_elems(partial_evals)?;
+    }
+
+    let tensor_batching_point = channel.get_random_points(TAU)?;
+    let batching_eq = LagrangeBases::gen_from_point(&tensor_batching_point);
+    let alphas = channel.get_random_points(points.len())?;
+
+    // Row batching is only F_2-linear, so each point's table is batched before combining.
+    let mut sum_check_claim = BinaryField128b::ZERO;
+    let mut tensored_eq = LagrangeBases {
+        vals: vec![BinaryField128b::ZERO; right_eqs[0].vals.len()],
+        vars: right_eqs[0].vars,
+    };
+    for ((right_eq, partial_evals), alpha) in right_eqs.iter().zip(&upper_partial_evals).zip(alphas)
+    {
+        sum_check_claim +=
+            alpha * compute_row_batch(&batching_eq.vals, &ColumnView::new(partial_evals.clone())?);
+        let batched = right_eq.row_batch(&batching_eq);
+        tensored_eq
+            .vals
+            .iter_mut()
+            .zip(batched.vals)
+            .for_each(|(acc, val)| *acc += alpha * val);
+    }
+
+    let (proof, _) = prove_fri_phases(
+        mle.variables - TAU,
+        Vec::new(),
+        mle.clone().repack_for_fri(),
+        tensored_eq,
+        sum_check_claim,
+        encoding,
+        merkle_tree,
+        ntt,
+        channel,
+    )?;
+
+    Ok(MultiEvalProof {
+        upper_partial_evals,
+        proof,
+    })
+}
+
+/// [`prove`] on a fresh [`Channel::for_application`] transcript: the proof only verifies with
+/// [`verify_for_application`](crate::verifier::verify_for_application) under the same `domain`.
+pub fn prove_for_application<F, P>(
+    domain: &str,
+    mle: &PackedMLE<F>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+) -> Result<EvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    prove(
+        mle,
+        eval_point,
+        eval,
+        encoding,
+        commitment,
+        merkle_tree,
+        ntt,
+        &mut Channel::for_application(domain),
+    )
+}
+
+/// [`prove`] with every parallel section confined to `pool` instead of the global rayon pool.
+pub fn prove_in_pool<F, P>(
+    pool: &ThreadPool,
+    mle: &PackedMLE<F>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<EvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    pool.install(|| {
+        prove(
+            mle,
+            eval_point,
+            eval,
+            encoding,
+            commitment,
+            merkle_tree,
+            ntt,
+            channel,
+        )
+    })
+}
+
+/// Rejects evaluation points too short to split into the `TAU` ring-switch variables and at least
+/// one folding round, and warns on degenerate structure such as zero or repeated coordinates.
+pub fn validate_eval_point(point: &[BinaryField128b]) -> Result<()> {
+    ensure!(
+        point.len() > TAU,
+        "evaluation point has {} coordinates, need more than TAU = {TAU}",
+        point.len()
+    );
+
+    for warning in eval_point_warnings(point) {
+        warn!("degenerate evaluation point: {warning}");
+    }
+
+    Ok(())
+}
+
+fn eval_point_warnings(point: &[BinaryField128b]) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let zeros = point
+        .iter()
+        .filter(|x| **x == BinaryField128b::ZERO)
+        .count();
+    if zeros > 0 {
+        warnings.push(format!("{zeros} of {} coordinates are zero", point.len()));
+    }
+
+    let mut sorted: Vec<u128> = point.iter().map(|x| x.val()).collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let repeats = point.len() - sorted.len();
+    if repeats > 0 {
+        warnings.push(format!("{repeats} coordinates repeat an earlier one"));
+    }
+
+    warnings
+}
+
+/// Proves `eval` is the value at `domain_point` of the MLE whose values are given on `domain`
+/// rather than the boolean hypercube, by lifting the point to the hypercube and calling [`prove`].
+pub fn prove_over_domain<F, P>(
+    mle: &PackedMLE<F>,
+    domain: &AffineDomain,
+    domain_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<EvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    let eval_point = domain.lift_point(domain_point)?;
+    prove(
+        mle,
+        &eval_point,
+        eval,
+        encoding,
+        commitment,
+        merkle_tree,
+        ntt,
+        channel,
+    )
+}
+
+/// Openings proving both `p(point)` and `∂p/∂x_i(point)` for one variable `x_i`.
+///
+/// Over a binary field `∂p/∂x_i = p|_{x_i = 1} + p|_{x_i = 0}` and, by multilinearity,
+/// `p(point) = p|_{x_i = 0} + point_i * ∂p/∂x_i`, so opening the two restrictions proves both claims.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivativeProof {
+    pub eval_at_zero: BinaryField128b,
+    pub eval_at_one: BinaryField128b,
+    pub proof_at_zero: EvalProof,
+    pub proof_at_one: EvalProof,
+}
+
+/// Point with coordinate `var_index` replaced by `value`.
+pub fn restrict_point(
+    point: &[BinaryField128b],
+    var_index: usize,
+    value: BinaryField128b,
+) -> Result<Vec<BinaryField128b>> {
+    ensure!(
+        var_index < point.len(),
+        "variable index {var_index} out of range for a {}-variable point",
+        point.len()
+    );
+    let mut restricted = point.to_vec();
+    restricted[var_index] = value;
+    Ok(restricted)
+}
+
+/// Proves `p(point)` and `∂p/∂x_{var_index}(point)`, returning both values with the proof.
+pub fn prove_with_derivative<F, P>(
+    mle: &PackedMLE<F>,
+    point: &[BinaryField128b],
+    var_index: usize,
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<(BinaryField128b, BinaryField128b, DerivativeProof)>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    let mut open_at = |value: BinaryField128b| -> Result<(BinaryField128b, EvalProof)> {
+        let restricted = restrict_point(point, var_index, value)?;
+        let eval = mle.get_bound_elem(0, &LagrangeBases::gen_from_point(&restricted));
+        let proof = prove(
+            mle,
+            &restricted,
+            eval,
+            encoding,
+            commitment,
+            merkle_tree,
+            ntt,
+            channel,
+        )?;
+        Ok((eval, proof))
+    };
+
+    let (eval_at_zero, proof_at_zero) = open_at(BinaryField128b::ZERO)?;
+    let (eval_at_one, proof_at_one) = open_at(BinaryField128b::ONE)?;
+
+    let derivative = eval_at_one - eval_at_zero;
+    let eval = eval_at_zero + point[var_index] * derivative;
+
+    Ok((
+        eval,
+        derivative,
+        DerivativeProof {
+            eval_at_zero,
+            eval_at_one,
+            proof_at_zero,
+            proof_at_one,
+        },
+    ))
+}
+
+/// Opens polynomial `i` of a [`commit_heterogeneous`] commitment at `points[i]` (of its original
+/// size) with one [`prove_multi`] of the stacked polynomial at the padded points.
+pub fn prove_heterogeneous<F, P>(
+    witness: &HeterogeneousWitness<F>,
+    commitment: &HeterogeneousCommitment,
+    points: &[Vec<BinaryField128b>],
+    evals: &[BinaryField128b],
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<MultiEvalProof>
+where
+    BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+    F: BinaryField + TowerField,
+    P: BinaryField,
+{
+    ensure!(
+        points.len() == commitment.variables.len() && evals.len() == points.len(),
+        "expected {} points and evaluations, got {} and {}",
+        commitment.variables.len(),
+        points.len(),
+        evals.len()
+    );
+
+    let padded_points = points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| commitment.padded_point(i, point))
+        .collect::<Result<Vec<_>>>()?;
+    prove_multi(
+        &witness.stacked,
+        &padded_points,
+        evals,
+        &witness.code,
+        &commitment.commitment,
+        &witness.merkle_tree,
+        ntt,
+        channel,
+    )
+}
+
+/// Standalone FRI low-degree test of a committed codeword, carrying no evaluation claim.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProximityProof {
+    pub fri_oracles: Vec<VectorCommitment>,
+    pub final_folded_value: BinaryField128b,
+    pub fri_queried_symbols: FriQueriedSymbols,
+    pub fri_merkle_paths: FriMerkleProofs,
+}
+
+/// Proves that the committed codeword is close to a Reed–Solomon codeword by folding it with
+/// transcript challenges down to a constant and opening the queried positions of every round.
+pub fn prove_proximity<P>(
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<ProximityProof>
+where
+    BinaryField128b: ExtensionField<P>,
+    P: BinaryField,
+{
+    ensure!(
+        commitment.symbols_per_leaf == DEFAULT_SYMBOLS_PER_LEAF,
+        "FRI queries open symbol pairs; commitment uses {} symbols per leaf",
+        commitment.symbols_per_leaf
+    );
+    ensure!(
+        encoding.coset_offset == commitment.coset_offset,
+        "encoding uses coset offset {}, commitment records {}",
+        encoding.coset_offset,
+        commitment.coset_offset
+    );
+
+    let rounds = proximity_rounds(commitment)?;
+    ensure!(
+        encoding.encoding.len() == 1 << (rounds + LOG_RATE),
+        "codeword has {} symbols, commitment implies {}",
+        encoding.encoding.len(),
+        1usize << (rounds + LOG_RATE)
+    );
+
+    channel.observe_fri_commitment(commitment);
+
+    let mut proof_state: ProofState = ProofState::default();
+    for round in 0..rounds {
+        let r = channel.get_random_point()?;
+
+        let code = match round {
+            0 => encoding,
+            _ => &proof_state.fri_folded_codes[round - 1],
+        };
+        let folded_code = code.fold_code(r, round, ntt);
+        let (oracle, oracle_tree) = commit_oracle(&folded_code);
+        channel.observe_vector_commitment(&oracle);
+
+        proof_state.fri_folded_codes.push(folded_code);
+        proof_state.fri_oracles.push(oracle);
+        proof_state.fri_merkle_trees.push(oracle_tree);
+        proof_state.random_challenges.push(r);
+    }
+
+    let final_folded_value = proof_state.fri_folded_codes[rounds - 1].idx(0);
+    channel.observe_field_elem(final_folded_value)?;
+
+    let (fri_queried_symbols, fri_merkle_paths) =
+        query_phase(rounds, encoding, merkle_tree, channel, &proof_state)?;
+
+    Ok(ProximityProof {
+        fri_oracles: proof_state.fri_oracles,
+        final_folded_value,
+        fri_queried_symbols,
+        fri_merkle_paths,
+    })
+}
+
+/// Number of folds taking the commitment's codeword down to `RATE` symbols.
+pub fn proximity_rounds(commitment: &FriCommitment) -> Result<usize> {
+    commitment
+        .variables()
+        .checked_sub(commitment.tau())
+        .filter(|rounds| *rounds > 0)
+        .with_context(|| {
+            format!(
+                "commitment has {} variables, need more than tau = {}",
+                commitment.variables(),
+                commitment.tau()
+            )
+        })
+}
+
+/// Opening of `p(0, ..., 0)`: the committed leaf holding codeword position 0 and its Merkle path.
+///
+/// Position 0 of the coset-0 block evaluates the message at the domain point 0, where every
+/// novel basis polynomial but the constant one vanishes, so it equals the first packed message
+/// symbol and its lowest bit is the constant term.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZeroOpening {
+    pub symbols: FriSymbolPair,
+    pub merkle_path: FriMerklePath,
+}
+
+/// Constant term `p(0, ..., 0)` read off the codeword symbol at position 0.
+pub fn constant_term(symbol: BinaryField128b) -> BinaryField128b {
+    <BinaryField128b as ExtensionField<BinaryField1b>>::iter_bases(&symbol)
+        .next()
+        .expect("BinaryField128b has F_2 bases")
+        .into()
+}
+
+/// Opens the committed polynomial at the all-zeros point with a single Merkle path, returning
+/// `p(0, ..., 0)` and its [`ZeroOpening`]. The opening binds only an honest committer; see
+/// [`verify_at_zero`](crate::verifier::verify_at_zero).
+pub fn prove_at_zero(
+    encoding: &Code<BinaryField128b>,
+    commitment: &FriCommitment,
+    merkle_tree: &MerkleTree,
+) -> Result<(BinaryField128b, ZeroOpening)> {
+    ensure!(
+        commitment.symbols_per_leaf == DEFAULT_SYMBOLS_PER_LEAF,
+        "zero openings reveal a symbol pair; commitment uses {} symbols per leaf",
+        commitment.symbols_per_leaf
+    );
+    ensure!(
+        encoding.coset_offset == 0,
+        "zero openings read the coset-0 block, which is stored at coset offset {}",
+        encoding.coset_offset
+    );
+
+    let symbols = (encoding.idx(0), encoding.idx(1));
+    let opening = ZeroOpening {
+        symbols,
+        merkle_path: merkle_tree.get_merkle_path(codeword_index_to_leaf_index(0)),
+    };
+    Ok((constant_term(symbols.0), opening))
+}
+
+/// Ordered pair of sibling code symbols opened at a query index.
+pub type FriSymbolPair = (BinaryField128b, BinaryField128b);
+/// All queried symbol pairs within a single FRI round.
+pub type FriRoundSymbols = Vec<FriSymbolPair>;
 /// Round-by-round list of every queried symbol pair sent in the proof.
 pub type FriQueriedSymbols = Vec<FriRoundSymbols>;
 
-/// Merkle authentication path proving a single symbol pair.
-pub type FriMerklePath = Vec<Hash>;
-/// All Merkle paths corresponding to the queries of one round.
-pub type FriRoundMerklePaths = Vec<FriMerklePath>;
-/// Round-by-round Merkle proofs accompanying the queried symbols.
-pub type FriMerkleProofs = Vec<FriRoundMerklePaths>;
+/// Merkle authentication path proving a single symbol pair.
+pub type FriMerklePath<H = Keccak256Hasher> = Vec<<H as MerkleHasher>::Digest>;
+/// All Merkle paths corresponding to the queries of one round.
+pub type FriRoundMerklePaths<H = Keccak256Hasher> = Vec<FriMerklePath<H>>;
+/// Round-by-round Merkle proofs accompanying the queried symbols.
+pub type FriMerkleProofs<H = Keccak256Hasher> = Vec<FriRoundMerklePaths<H>>;
+
+/// Version of the serialized [`EvalProof`] layout, written first by every `to_bytes_*` method.
+///
+/// Bump it whenever the layout changes (leaf arity, hash length, field order, ...) so that proofs
+/// from another version are rejected on decoding instead of misparsed.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// Reads the version header of a serialized proof, rejecting every version but [`FORMAT_VERSION`].
+fn read_format_version(reader: &mut ByteReader) -> Result<()> {
+    let version = reader.u16().context("missing proof format version")?;
+    ensure!(
+        version == FORMAT_VERSION,
+        "unsupported proof format version {version}, expected {FORMAT_VERSION}"
+    );
+    Ok(())
+}
+
+/// Opening proof against a [`FriCommitment`] whose Merkle trees, the committed one and every FRI
+/// oracle, are built by `H`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvalProof<H: MerkleHasher = Keccak256Hasher> {
+    pub upper_partial_evals: Vec<BinaryField128b>,
+    pub sum_check_oracles: Vec<Univariate>,
+    pub final_folded_value: BinaryField128b,
+    pub fri_oracles: Vec<VectorCommitment<H>>,
+    pub fri_queried_symbols: FriQueriedSymbols,
+    pub fri_merkle_paths: FriMerkleProofs<H>,
+    /// [`twiddle_fingerprint`] of the prover's NTT, checked by the verifier against its own.
+    pub twiddle_fingerprint: Hash,
+    /// How `sum_check_oracles` are represented.
+    pub oracle_form: OracleForm,
+}
+
+/// Representation of the degree-2 sum-check round polynomials in an [`EvalProof`].
+///
+/// The transcript always absorbs coefficients, so both forms draw the same challenges; the
+/// evaluation form only spares the verifier the interpolating evaluations at 0 and 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OracleForm {
+    /// `[c_0, c_1, c_2]` with `p(X) = c_0 + c_1 X + c_2 X^2`.
+    #[default]
+    Coeffs,
+    /// `[p(0), p(1), p(∞)]`, where `p(∞)` is the leading coefficient.
+    Evals,
+}
+
+impl OracleForm {
+    fn to_tag(self) -> usize {
+        match self {
+            OracleForm::Coeffs => 0,
+            OracleForm::Evals => 1,
+        }
+    }
+
+    fn from_tag(tag: usize) -> Result<OracleForm> {
+        match tag {
+            0 => Ok(OracleForm::Coeffs),
+            1 => Ok(OracleForm::Evals),
+            _ => anyhow::bail!("unknown sum-check oracle form {tag}"),
+        }
+    }
+}
+
+/// Bytes each component of an [`EvalProof`] takes in [`EvalProof::to_bytes`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProofSizeBreakdown {
+    /// The [`FORMAT_VERSION`] header.
+    pub header: usize,
+    pub upper_partial_evals: usize,
+    pub sum_check_oracles: usize,
+    /// The final folded value and the [`OracleForm`] tag.
+    pub final_folded_value: usize,
+    pub fri_oracles: usize,
+    pub fri_queried_symbols: usize,
+    pub fri_merkle_paths: usize,
+    pub twiddle_fingerprint: usize,
+}
+
+impl ProofSizeBreakdown {
+    pub fn total(&self) -> usize {
+        self.header
+            + self.upper_partial_evals
+            + self.sum_check_oracles
+            + self.final_folded_value
+            + self.fri_oracles
+            + self.fri_queried_symbols
+            + self.fri_merkle_paths
+            + self.twiddle_fingerprint
+    }
+}
+
+impl<H: MerkleHasher> EvalProof<H> {
+    /// Re-expresses the sum-check round polynomials in `form`.
+    pub fn into_oracle_form(mut self, form: OracleForm) -> Result<EvalProof<H>> {
+        if form == self.oracle_form {
+            return Ok(self);
+        }
+
+        for (round, oracle) in self.sum_check_oracles.iter_mut().enumerate() {
+            let &[a, b, c] = oracle.coeffs.as_slice() else {
+                anyhow::bail!(
+                    "sum-check oracle of round {round} has {} entries, expected 3",
+                    oracle.coeffs.len()
+                );
+            };
+            // Coefficients [c0, c1, c2] and evaluations [c0, c0 + c1 + c2, c2] map to each other
+            // by the same involution in characteristic 2.
+            oracle.coeffs = vec![a, a + b + c, c];
+        }
+        self.oracle_form = form;
+        Ok(self)
+    }
+
+    fn new(
+        upper_partial_evals: Vec<BinaryField128b>,
+        proof_state: ProofState<H>,
+        final_folded_value: BinaryField128b,
+        fri_queried_symbols: FriQueriedSymbols,
+        fri_merkle_paths: FriMerkleProofs<H>,
+        twiddle_fingerprint: Hash,
+    ) -> EvalProof<H> {
+        EvalProof {
+            upper_partial_evals,
+            sum_check_oracles: proof_state.sum_check_oracles,
+            fri_oracles: proof_state.fri_oracles,
+            final_folded_value,
+            fri_queried_symbols,
+            fri_merkle_paths,
+            twiddle_fingerprint,
+            oracle_form: OracleForm::Coeffs,
+        }
+    }
+}
+
+impl<H: MerkleHasher> EvalProof<H> {
+    /// Replays the statement and sum-check transcript on a copy of `channel`, which must be in
+    /// the state the proof was produced from, checking each round's claim reduction against the
+    /// Fiat-Shamir challenges it produces. `channel` itself is left untouched.
+    ///
+    /// A cheap structural filter: no NTT, Merkle or query work is done, so passing it says
+    /// nothing about the FRI part of the proof. See [`crate::verifier::verify_sumcheck_only`].
+    pub fn check_transcript_consistency(
+        &self,
+        commitment: &FriCommitment<H>,
+        eval_point: &[BinaryField128b],
+        eval: BinaryField128b,
+        channel: &Channel,
+    ) -> Result<()> {
+        Ok(crate::verifier::verify_sumcheck_only(
+            commitment,
+            eval_point,
+            eval,
+            self,
+            &mut channel.clone(),
+        )?)
+    }
+
+    /// Keccak digest of the little-endian [`EvalProof::to_bytes_with`] serialization, a 32-byte
+    /// key under which equal proofs collide and distinct ones do not.
+    pub fn fingerprint(&self) -> Hash {
+        Hash(Keccak256::digest(self.to_bytes_with(ProofEncoding::Little)))
+    }
+
+    /// Canonical transport encoding: [`EvalProof::to_bytes_with`] in little-endian order.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with(ProofEncoding::Little)
+    }
+
+    /// Inverse of [`EvalProof::to_bytes`], rejecting truncated or trailing input.
+    pub fn from_bytes(bytes: &[u8]) -> Result<EvalProof<H>> {
+        Self::from_bytes_with(bytes, ProofEncoding::Little)
+    }
+
+    /// Length of [`EvalProof::to_bytes`], computed without serializing.
+    pub fn size_in_bytes(&self) -> usize {
+        self.breakdown().total()
+    }
+
+    /// Serialized size of each component of the proof, length prefixes included.
+    pub fn breakdown(&self) -> ProofSizeBreakdown {
+        const LEN: usize = 8;
+        const FIELD: usize = 16;
+        const FINGERPRINT: usize = 32;
+
+        ProofSizeBreakdown {
+            header: 2,
+            upper_partial_evals: LEN + FIELD * self.upper_partial_evals.len(),
+            sum_check_oracles: LEN
+                + self
+                    .sum_check_oracles
+                    .iter()
+                    .map(|oracle| LEN + FIELD * oracle.coeffs.len())
+                    .sum::<usize>(),
+            // The oracle form tag follows the final value.
+            final_folded_value: FIELD + LEN,
+            fri_oracles: LEN + VectorCommitment::<H>::BYTES * self.fri_oracles.len(),
+            fri_queried_symbols: LEN
+                + self
+                    .fri_queried_symbols
+                    .iter()
+                    .map(|round| LEN + 2 * FIELD * round.len())
+                    .sum::<usize>(),
+            fri_merkle_paths: LEN
+                + self
+                    .fri_merkle_paths
+                    .iter()
+                    .map(|round| {
+                        LEN + round
+                            .iter()
+                            .map(|path| LEN + H::DIGEST_BYTES * path.len())
+                            .sum::<usize>()
+                    })
+                    .sum::<usize>(),
+            twiddle_fingerprint: FINGERPRINT,
+        }
+    }
+
+    /// Serializes the proof query by query after the [`FORMAT_VERSION`] header, each vector
+    /// prefixed by its length, writing integers and field elements in the byte order of `encoding`.
+    pub fn to_bytes_with(&self, encoding: ProofEncoding) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        encoding.put_u16(&mut bytes, FORMAT_VERSION);
+
+        encoding.put_u64(&mut bytes, self.upper_partial_evals.len());
+        for eval in &self.upper_partial_evals {
+            encoding.put_field(&mut bytes, *eval);
+        }
+
+        encoding.put_u64(&mut bytes, self.sum_check_oracles.len());
+        for oracle in &self.sum_check_oracles {
+            encoding.put_u64(&mut bytes, oracle.coeffs.len());
+            for coeff in &oracle.coeffs {
+                encoding.put_field(&mut bytes, *coeff);
+            }
+        }
+        encoding.put_field(&mut bytes, self.final_folded_value);
+        encoding.put_u64(&mut bytes, self.oracle_form.to_tag());
+
+        encoding.put_u64(&mut bytes, self.fri_oracles.len());
+        for oracle in &self.fri_oracles {
+            put_hash(&mut bytes, &oracle.root);
+            encoding.put_u64(&mut bytes, oracle.depth);
+        }
+
+        encoding.put_u64(&mut bytes, self.fri_queried_symbols.len());
+        for round_symbols in &self.fri_queried_symbols {
+            encoding.put_u64(&mut bytes, round_symbols.len());
+            for (s0, s1) in round_symbols {
+                encoding.put_field(&mut bytes, *s0);
+                encoding.put_field(&mut bytes, *s1);
+            }
+        }
+
+        encoding.put_u64(&mut bytes, self.fri_merkle_paths.len());
+        for round_paths in &self.fri_merkle_paths {
+            encoding.put_u64(&mut bytes, round_paths.len());
+            for path in round_paths {
+                encoding.put_u64(&mut bytes, path.len());
+                for hash in path {
+                    put_hash(&mut bytes, hash);
+                }
+            }
+        }
+
+        put_hash(&mut bytes, &self.twiddle_fingerprint);
+        bytes
+    }
+
+    /// Inverse of [`EvalProof::to_bytes_with`] for the same `encoding`, rejecting truncated or
+    /// trailing input and other format versions.
+    pub fn from_bytes_with(bytes: &[u8], encoding: ProofEncoding) -> Result<EvalProof<H>> {
+        let mut reader = ByteReader::with_encoding(bytes, encoding);
+        read_format_version(&mut reader)?;
+
+        let num_upper = reader.read_len(16)?;
+        let upper_partial_evals = (0..num_upper)
+            .map(|_| reader.field())
+            .collect::<Result<Vec<_>>>()?;
+
+        let num_oracles = reader.read_len(8)?;
+        let sum_check_oracles = (0..num_oracles)
+            .map(|_| {
+                let len = reader.read_len(16)?;
+                (0..len)
+                    .map(|_| reader.field())
+                    .collect::<Result<Vec<_>>>()
+                    .map(Univariate::new)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let final_folded_value = reader.field()?;
+        let oracle_form = OracleForm::from_tag(reader.u64()?)?;
+
+        let num_fri_oracles = reader.read_len(VectorCommitment::<H>::BYTES)?;
+        let fri_oracles = (0..num_fri_oracles)
+            .map(|_| -> Result<VectorCommitment<H>> {
+                Ok(VectorCommitment {
+                    root: reader.digest::<H>()?,
+                    depth: reader.u64()?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let num_rounds = reader.read_len(8)?;
+        let fri_queried_symbols = (0..num_rounds)
+            .map(|_| {
+                let len = reader.read_len(32)?;
+                (0..len)
+                    .map(|_| -> Result<FriSymbolPair> { Ok((reader.field()?, reader.field()?)) })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let num_path_rounds = reader.read_len(8)?;
+        let fri_merkle_paths = (0..num_path_rounds)
+            .map(|_| {
+                let num_paths = reader.read_len(8)?;
+                (0..num_paths)
+                    .map(|_| {
+                        let len = reader.read_len(H::DIGEST_BYTES)?;
+                        (0..len)
+                            .map(|_| reader.digest::<H>())
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let twiddle_fingerprint = reader.hash()?;
+        reader.finish()?;
+
+        Ok(EvalProof {
+            upper_partial_evals,
+            sum_check_oracles,
+            final_folded_value,
+            fri_oracles,
+            fri_queried_symbols,
+            fri_merkle_paths,
+            twiddle_fingerprint,
+            oracle_form,
+        })
+    }
+
+    /// Serializes the proof column by column: each round's symbols and each Merkle path level are
+    /// laid out across all queries, so that hashes shared near the root end up adjacent and a
+    /// generic compressor downstream can exploit them.
+    ///
+    /// Columns need every round to open the same number of queries and every path of a round to
+    /// have the same depth; ragged query data is rejected.
+    pub fn to_bytes_columnar(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        ProofEncoding::Little.put_u16(&mut bytes, FORMAT_VERSION);
+
+        put_u64(&mut bytes, self.upper_partial_evals.len());
+        for eval in &self.upper_partial_evals {
+            put_field(&mut bytes, *eval);
+        }
+
+        put_u64(&mut bytes, self.sum_check_oracles.len());
+        for oracle in &self.sum_check_oracles {
+            put_u64(&mut bytes, oracle.coeffs.len());
+        }
+        for oracle in &self.sum_check_oracles {
+            for coeff in &oracle.coeffs {
+                put_field(&mut bytes, *coeff);
+            }
+        }
+        put_field(&mut bytes, self.final_folded_value);
+        put_u64(&mut bytes, self.oracle_form.to_tag());
+
+        put_u64(&mut bytes, self.fri_oracles.len());
+        for oracle in &self.fri_oracles {
+            bytes.extend_from_slice(&oracle.to_bytes());
+        }
+
+        let num_queries = self.fri_queried_symbols.first().map_or(0, Vec::len);
+        ensure!(
+            self.fri_queried_symbols
+                .iter()
+                .all(|round_symbols| round_symbols.len() == num_queries),
+            "Rounds open different numbers of queries; columns need equal counts"
+        );
+        put_u64(&mut bytes, self.fri_queried_symbols.len());
+        put_u64(&mut bytes, num_queries);
+        for round_symbols in &self.fri_queried_symbols {
+            for (s0, _) in round_symbols {
+                put_field(&mut bytes, *s0);
+            }
+            for (_, s1) in round_symbols {
+                put_field(&mut bytes, *s1);
+            }
+        }
+
+        put_u64(&mut bytes, self.fri_merkle_paths.len());
+        for round_paths in &self.fri_merkle_paths {
+            let depth = round_paths.first().map_or(0, Vec::len);
+            ensure!(
+                round_paths.iter().all(|path| path.len() == depth),
+                "Merkle paths of one round have different depths; columns need equal depths"
+            );
+            put_u64(&mut bytes, round_paths.len());
+            put_u64(&mut bytes, depth);
+            for level in 0..depth {
+                for path in round_paths {
+                    put_hash(&mut bytes, &path[level]);
+                }
+            }
+        }
+
+        put_hash(&mut bytes, &self.twiddle_fingerprint);
+        Ok(bytes)
+    }
+
+    /// Inverse of [`EvalProof::to_bytes_columnar`], rejecting truncated or trailing input and
+    /// other format versions.
+    pub fn from_bytes_columnar(bytes: &[u8]) -> Result<EvalProof<H>> {
+        let mut reader = ByteReader::new(bytes);
+        read_format_version(&mut reader)?;
+
+        let num_upper = reader.read_len(16)?;
+        let upper_partial_evals = (0..num_upper)
+            .map(|_| reader.field())
+            .collect::<Result<Vec<_>>>()?;
+
+        let num_oracles = reader.read_len(8)?;
+        let degrees = (0..num_oracles)
+            .map(|_| reader.read_len(16))
+            .collect::<Result<Vec<_>>>()?;
+        let sum_check_oracles = degrees
+            .into_iter()
+            .map(|len| {
+                (0..len)
+                    .map(|_| reader.field())
+                    .collect::<Result<Vec<_>>>()
+                    .map(Univariate::new)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let final_folded_value = reader.field()?;
+        let oracle_form = OracleForm::from_tag(reader.u64()?)?;
+
+        let num_fri_oracles = reader.read_len(VectorCommitment::<H>::BYTES)?;
+        let fri_oracles = (0..num_fri_oracles)
+            .map(|_| VectorCommitment::from_bytes(reader.take(VectorCommitment::<H>::BYTES)?))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Counts are bounded as if every round and path took at least one byte, so that empty
+        // ones cannot inflate them.
+        let num_rounds = reader.u64()?;
+        let num_queries = reader.u64()?;
+        ensure!(
+            num_rounds.saturating_mul(num_queries.saturating_mul(32).max(1)) <= reader.remaining(),
+            "queried symbol counts exceed remaining input"
+        );
+        let mut fri_queried_symbols = Vec::with_capacity(num_rounds);
+        for _ in 0..num_rounds {
+            let s0 = (0..num_queries)
+                .map(|_| reader.field())
+                .collect::<Result<Vec<_>>>()?;
+            let s1 = (0..num_queries)
+                .map(|_| reader.field())
+                .collect::<Result<Vec<_>>>()?;
+            fri_queried_symbols.push(s0.into_iter().zip(s1).collect());
+        }
+
+        let num_path_rounds = reader.read_len(16)?;
+        let mut fri_merkle_paths = Vec::with_capacity(num_path_rounds);
+        for _ in 0..num_path_rounds {
+            let num_paths = reader.u64()?;
+            let depth = reader.u64()?;
+            ensure!(
+                num_paths.saturating_mul(depth.saturating_mul(H::DIGEST_BYTES).max(1))
+                    <= reader.remaining(),
+                "Merkle path counts exceed remaining input"
+            );
+            let mut round_paths = vec![Vec::with_capacity(depth); num_paths];
+            for _ in 0..depth {
+                for path in round_paths.iter_mut() {
+                    path.push(reader.digest::<H>()?);
+                }
+            }
+            fri_merkle_paths.push(round_paths);
+        }
+
+        let twiddle_fingerprint = reader.hash()?;
+        reader.finish()?;
+
+        Ok(EvalProof {
+            upper_partial_evals,
+            sum_check_oracles,
+            final_folded_value,
+            fri_oracles,
+            fri_queried_symbols,
+            fri_merkle_paths,
+            twiddle_fingerprint,
+            oracle_form,
+        })
+    }
+}
+
+/// Several [`EvalProof`]s over the same commitment with their FRI oracles and Merkle paths pooled,
+/// so oracle roots and paths shared between proofs are sent once.
+#[derive(Clone, Debug, Default)]
+pub struct AggregatedProof {
+    pub fri_oracles: Vec<VectorCommitment>,
+    pub merkle_paths: Vec<FriMerklePath>,
+    pub proofs: Vec<AggregatedEntry>,
+}
+
+/// An [`EvalProof`] whose oracles and Merkle paths are indices into its [`AggregatedProof`] pools.
+#[derive(Clone, Debug)]
+pub struct AggregatedEntry {
+    pub upper_partial_evals: Vec<BinaryField128b>,
+    pub sum_check_oracles: Vec<Univariate>,
+    pub final_folded_value: BinaryField128b,
+    pub fri_oracle_ids: Vec<usize>,
+    pub fri_queried_symbols: FriQueriedSymbols,
+    pub fri_merkle_path_ids: Vec<Vec<usize>>,
+    pub twiddle_fingerprint: Hash,
+    pub oracle_form: OracleForm,
+}
+
+/// Pools the oracle roots and Merkle paths of independently generated proofs, deduplicating
+/// identical entries (e.g. round-0 paths of queries that landed on the same leaf).
+pub fn aggregate_proofs(proofs: Vec<EvalProof>) -> AggregatedProof {
+    let mut aggregated = AggregatedProof::default();
+    let mut oracle_ids: HashMap<VectorCommitment, usize> = HashMap::new();
+    let mut path_ids: HashMap<FriMerklePath, usize> = HashMap::new();
+
+    for proof in proofs {
+        let fri_oracle_ids = proof
+            .fri_oracles
+            .into_iter()
+            .map(|oracle| intern(&mut oracle_ids, &mut aggregated.fri_oracles, oracle))
+            .collect();
+
+        let fri_merkle_path_ids = proof
+            .fri_merkle_paths
+            .into_iter()
+            .map(|round_paths| {
+                round_paths
+                    .into_iter()
+                    .map(|path| intern(&mut path_ids, &mut aggregated.merkle_paths, path))
+                    .collect()
+            })
+            .collect();
+
+        aggregated.proofs.push(AggregatedEntry {
+            upper_partial_evals: proof.upper_partial_evals,
+            sum_check_oracles: proof.sum_check_oracles,
+            final_folded_value: proof.final_folded_value,
+            fri_oracle_ids,
+            fri_queried_symbols: proof.fri_queried_symbols,
+            fri_merkle_path_ids,
+            twiddle_fingerprint: proof.twiddle_fingerprint,
+            oracle_form: proof.oracle_form,
+        });
+    }
+
+    aggregated
+}
+
+/// Index of `item` in `pool`, appending it on first sight.
+fn intern<T: Clone + Eq + std::hash::Hash>(
+    ids: &mut HashMap<T, usize>,
+    pool: &mut Vec<T>,
+    item: T,
+) -> usize {
+    *ids.entry(item).or_insert_with_key(|item| {
+        pool.push(item.clone());
+        pool.len() - 1
+    })
+}
+
+impl AggregatedProof {
+    /// Rebuilds the individual proofs, failing on dangling pool indices.
+    pub fn expand(&self) -> Result<Vec<EvalProof>> {
+        self.proofs
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let fri_oracles = entry
+                    .fri_oracle_ids
+                    .iter()
+                    .map(|&id| {
+                        self.fri_oracles
+                            .get(id)
+                            .cloned()
+                            .with_context(|| format!("proof {i} references missing oracle {id}"))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let fri_merkle_paths = entry
+                    .fri_merkle_path_ids
+                    .iter()
+                    .map(|round_ids| {
+                        round_ids
+                            .iter()
+                            .map(|&id| {
+                                self.merkle_paths.get(id).cloned().with_context(|| {
+                                    format!("proof {i} references missing Merkle path {id}")
+                                })
+                            })
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(EvalProof {
+                    upper_partial_evals: entry.upper_partial_evals.clone(),
+                    sum_check_oracles: entry.sum_check_oracles.clone(),
+                    final_folded_value: entry.final_folded_value,
+                    fri_oracles,
+                    fri_queried_symbols: entry.fri_queried_symbols.clone(),
+                    fri_merkle_paths,
+                    twiddle_fingerprint: entry.twiddle_fingerprint,
+                    oracle_form: entry.oracle_form,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FriCommitment<H: MerkleHasher = Keccak256Hasher> {
+    pub vector_commitment: VectorCommitment<H>,
+    pub packing_factor: usize,
+    /// Number of variables of the committed MLE, which every opening point must match.
+    pub variables: usize,
+    /// Codeword symbols hashed into each Merkle leaf.
+    pub symbols_per_leaf: usize,
+    /// Number of leading point coordinates absorbed by the ring switch.
+    pub tau: usize,
+    /// Codeword block holding the coset-0 transform, as in [`Code::coset_offset`];
+    /// the verifier folds queries in this layout.
+    pub coset_offset: usize,
+}
+
+impl<H: MerkleHasher> FriCommitment<H> {
+    pub fn variables(&self) -> usize {
+        self.variables
+    }
 
-pub struct EvalProof {
-    pub upper_partial_evals: Vec<BinaryField128b>,
-    pub sum_check_oracles: Vec<Univariate>,
-    pub final_folded_value: BinaryField128b,
-    pub fri_oracles: Vec<VectorCommitment>,
-    pub fri_queried_symbols: FriQueriedSymbols,
-    pub fri_merkle_paths: FriMerkleProofs,
+    pub fn tau(&self) -> usize {
+        self.tau
+    }
 }
 
-impl EvalProof {
-    fn new(
-        upper_partial_evals: Vec<BinaryField128b>,
-        proof_state: ProofState,
-        final_folded_value: BinaryField128b,
-        fri_queried_symbols: FriQueriedSymbols,
-        fri_merkle_paths: FriMerkleProofs,
-    ) -> EvalProof {
-        EvalProof {
-            upper_partial_evals,
-            sum_check_oracles: proof_state.sum_check_oracles,
-            fri_oracles: proof_state.fri_oracles,
-            final_folded_value,
-            fri_queried_symbols,
-            fri_merkle_paths,
+impl Channel {
+    pub fn observe_fri_commitment<H: MerkleHasher>(&mut self, commitment: &FriCommitment<H>) {
+        self.observe_vector_commitment(&commitment.vector_commitment);
+        self.absorb_bytes(&commitment.packing_factor.to_le_bytes());
+        self.absorb_bytes(&commitment.variables.to_le_bytes());
+        self.absorb_bytes(&commitment.symbols_per_leaf.to_le_bytes());
+        self.absorb_bytes(&commitment.tau.to_le_bytes());
+        self.absorb_bytes(&commitment.coset_offset.to_le_bytes());
+    }
+}
+
+impl FriCommitment {
+    /// Commitment from its raw parts, with the default leaf arity, `TAU` and codeword layout.
+    pub fn from_parts(
+        root: [u8; 32],
+        depth: usize,
+        packing_factor: usize,
+        variables: usize,
+    ) -> FriCommitment {
+        FriCommitment {
+            vector_commitment: VectorCommitment {
+                root: Hash(root.into()),
+                depth,
+            },
+            packing_factor,
+            variables,
+            symbols_per_leaf: DEFAULT_SYMBOLS_PER_LEAF,
+            tau: TAU,
+            coset_offset: 0,
         }
     }
 }
 
-pub struct FriCommitment {
-    pub vector_commitment: VectorCommitment,
-    pub packing_factor: usize,
+impl<H: MerkleHasher> FriCommitment<H> {
+    /// Serialized size: the vector commitment followed by the packing factor, the variable count,
+    /// the symbols per leaf, tau and the coset offset, each as a little-endian `u64`.
+    pub const BYTES: usize = VectorCommitment::<H>::BYTES + 5 * 8;
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.vector_commitment.to_bytes();
+        bytes.extend_from_slice(&(self.packing_factor as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.variables as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.symbols_per_leaf as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.tau as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.coset_offset as u64).to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<FriCommitment<H>> {
+        ensure!(
+            bytes.len() == Self::BYTES,
+            "FRI commitment must be {} bytes, got {}",
+            Self::BYTES,
+            bytes.len()
+        );
+
+        let (vector_commitment, rest) = bytes.split_at(VectorCommitment::<H>::BYTES);
+        let (packing_factor, rest) = rest.split_at(8);
+        let (variables, rest) = rest.split_at(8);
+        let (symbols_per_leaf, rest) = rest.split_at(8);
+        let (tau, coset_offset) = rest.split_at(8);
+        let packing_factor = u64::from_le_bytes(packing_factor.try_into()?);
+        let variables = u64::from_le_bytes(variables.try_into()?);
+        let symbols_per_leaf = u64::from_le_bytes(symbols_per_leaf.try_into()?);
+        let tau = u64::from_le_bytes(tau.try_into()?);
+        let coset_offset = u64::from_le_bytes(coset_offset.try_into()?);
+        let variables = usize::try_from(variables).context("variables does not fit in usize")?;
+        ensure!(
+            variables <= MAX_PARTIAL_EVAL_VARIABLES,
+            "{variables} variables exceed the addressable {MAX_PARTIAL_EVAL_VARIABLES}"
+        );
+
+        Ok(FriCommitment {
+            vector_commitment: VectorCommitment::from_bytes(vector_commitment)?,
+            packing_factor: usize::try_from(packing_factor)
+                .context("packing factor does not fit in usize")?,
+            variables,
+            symbols_per_leaf: usize::try_from(symbols_per_leaf)
+                .context("symbols per leaf does not fit in usize")?,
+            tau: usize::try_from(tau).context("tau does not fit in usize")?,
+            coset_offset: usize::try_from(coset_offset)
+                .context("coset offset does not fit in usize")?,
+        })
+    }
 }
 
 #[derive(Default)]
-struct ProofState {
+struct ProofState<H: MerkleHasher = Keccak256Hasher> {
     fri_folded_codes: Vec<Code<BinaryField128b>>,
-    fri_oracles: Vec<VectorCommitment>,
-    fri_merkle_trees: Vec<MerkleTree>,
+    fri_oracles: Vec<VectorCommitment<H>>,
+    fri_merkle_trees: Vec<MerkleTree<H>>,
     sum_check_oracles: Vec<Univariate>,
     random_challenges: Vec<BinaryField128b>,
 }
 
-impl ProofState {
+impl<H: MerkleHasher> ProofState<H> {
     fn update(
         &mut self,
         folded_code: Code<BinaryField128b>,
-        commitment: VectorCommitment,
-        merkle_tree: MerkleTree,
+        commitment: VectorCommitment<H>,
+        merkle_tree: MerkleTree<H>,
         poly: Univariate,
         challenge: BinaryField128b,
     ) {
@@ -198,12 +3072,12 @@ impl ProofState {
     }
 }
 
-fn commit_phase<P>(
+fn commit_phase<P, H>(
     rounds: usize,
     encoding: &Code<BinaryField128b>,
     ntt: &MultithreadedNTT<P>,
     channel: &mut Channel,
-    proof_state: &mut ProofState,
+    proof_state: &mut ProofState<H>,
     repacked_mle: &mut PackedMLE<BinaryField128b>,
     tensored_eq: &mut LagrangeBases,
     sum_check_claim: &mut BinaryField128b,
@@ -211,89 +3085,156 @@ fn commit_phase<P>(
 where
     BinaryField128b: ExtensionField<P> + PackedExtension<P>,
     P: BinaryField,
+    H: MerkleHasher,
 {
+    ensure!(
+        rounds > 0,
+        "no folding rounds: the point has no coordinates beyond TAU = {TAU}"
+    );
     for round in 0..rounds {
         //Sum check Logic
         let poly = sum_check_round(repacked_mle, tensored_eq, *sum_check_claim);
 
-        channel.observe_field_elems(&poly.coeffs)?;
+        channel.observe_oracle_coeffs(&poly.coeffs)?;
 
         let r = channel.get_random_point()?;
 
         *sum_check_claim = poly.evaluate(r);
 
-        //Folding the code with the sum check challenges.
-        let folded_code: Code<BinaryField128b> = match round {
-            0 => encoding.fold_code(r, round, ntt),
-            _ => proof_state.fri_folded_codes[round - 1].fold_code(r, round, ntt),
+        //Folding the code, MLE and eq table with the sum check challenge.
+        let code = match round {
+            0 => encoding,
+            _ => &proof_state.fri_folded_codes[round - 1],
         };
+        let folded_code = fri_round(code, repacked_mle, tensored_eq, r, round, ntt);
 
-        let (commitment, merkle_tree) = commit_oracle(&folded_code);
+        let (commitment, merkle_tree) = commit_oracle::<H>(&folded_code);
 
         channel.observe_vector_commitment(&commitment);
 
         proof_state.update(folded_code, commitment, merkle_tree, poly, r);
-        *repacked_mle = repacked_mle.fold_lo(&r);
-        tensored_eq.fold_lo(&r);
     }
 
     Ok(proof_state.fri_folded_codes[rounds - 1].idx(0))
 }
 
-fn query_phase(
+/// Folds the round's code, the repacked MLE and the tensored eq table with the same challenge `r`.
+fn fri_round<P>(
+    code: &Code<BinaryField128b>,
+    repacked_mle: &mut PackedMLE<BinaryField128b>,
+    tensored_eq: &mut LagrangeBases,
+    r: BinaryField128b,
+    round: usize,
+    ntt: &MultithreadedNTT<P>,
+) -> Code<BinaryField128b>
+where
+    BinaryField128b: ExtensionField<P>,
+    P: BinaryField,
+{
+    *repacked_mle = repacked_mle.fold_lo(&r);
+    tensored_eq.fold_lo(&r);
+    code.fold_code(r, round, ntt)
+}
+
+fn query_phase<H: MerkleHasher>(
     rounds: usize,
     encoding: &Code<BinaryField128b>,
-    merkle_tree: &MerkleTree,
+    merkle_tree: &MerkleTree<H>,
     channel: &mut Channel,
-    proof_state: &ProofState,
-) -> Result<(FriQueriedSymbols, FriMerkleProofs)> {
-    let mut current_queries: Vec<usize> = channel
+    proof_state: &ProofState<H>,
+) -> Result<(FriQueriedSymbols, FriMerkleProofs<H>)> {
+    let queries = draw_queries(rounds, channel)?;
+    Ok(open_queries(
+        rounds,
+        queries,
+        Some((merkle_tree, encoding)),
+        proof_state,
+    ))
+}
+
+/// Round-0 leaf indices of the query phase.
+fn draw_queries(rounds: usize, channel: &mut Channel) -> Result<Vec<usize>> {
+    Ok(channel
         .gen_queries(rounds + LOG_RATE)?
-        .iter()
-        .map(|i| i >> 1)
-        .collect();
+        .into_iter()
+        .map(codeword_index_to_leaf_index)
+        .collect())
+}
 
-    let mut round_merkle_paths: FriMerkleProofs = Vec::with_capacity(rounds);
+/// Opens `queries` in every round's oracle. Round 0 is opened in `committed` when given and left
+/// empty otherwise, for callers that open the committed codeword themselves.
+fn open_queries<H: MerkleHasher>(
+    rounds: usize,
+    mut current_queries: Vec<usize>,
+    committed: Option<(&MerkleTree<H>, &Code<BinaryField128b>)>,
+    proof_state: &ProofState<H>,
+) -> (FriQueriedSymbols, FriMerkleProofs<H>) {
+    let mut round_merkle_paths: FriMerkleProofs<H> = Vec::with_capacity(rounds);
     let mut round_queried_symbols: FriQueriedSymbols = Vec::with_capacity(rounds);
 
     for round in 0..rounds {
-        let (tree, code) = match round {
-            0 => (merkle_tree, encoding),
-            _ => (
+        let (merkle_paths, queried_symbols) = match (round, committed) {
+            (0, Some((tree, code))) => gather_round_queries(tree, code, &current_queries),
+            (0, None) => (Vec::new(), Vec::new()),
+            _ => gather_round_queries(
                 &proof_state.fri_merkle_trees[round - 1],
                 &proof_state.fri_folded_codes[round - 1],
+                &current_queries,
             ),
         };
 
-        let (merkle_paths, queried_symbols) = gather_round_queries(tree, code, &current_queries);
-
         round_merkle_paths.push(merkle_paths);
         round_queried_symbols.push(queried_symbols);
 
         current_queries.iter_mut().for_each(|i| {
-            *i >>= 1;
+            *i = codeword_index_to_leaf_index(*i);
         });
     }
 
-    Ok((round_queried_symbols, round_merkle_paths))
+    (round_queried_symbols, round_merkle_paths)
 }
 
-fn gather_round_queries(
-    tree: &MerkleTree,
+/// Opens `queries` in one round's tree, reading each distinct leaf's path once.
+///
+/// Within a round the `>> 1` shifts make queries collide as the folded trees shrink (the last
+/// rounds hold far fewer leaves than there are queries), so repeated paths are copied, not
+/// re-walked. Nothing carries over between rounds: each round commits a separate tree over that
+/// round's folded codeword, so no node of one round's tree is a node of another's, and a path read
+/// in round `k` answers nothing in round `k + 1` even where the query lands on the same leaf index.
+/// Distinct queries of one round do share their upper nodes, but a path read is a copy of one
+/// digest per level, so sharing those would save copies rather than hashes.
+fn gather_round_queries<H: MerkleHasher>(
+    tree: &MerkleTree<H>,
     code: &Code<BinaryField128b>,
     queries: &[usize],
-) -> (FriRoundMerklePaths, FriRoundSymbols) {
-    let merkle_paths = queries.iter().map(|i| tree.get_merkle_path(*i)).collect();
+) -> (FriRoundMerklePaths<H>, FriRoundSymbols) {
+    let mut unique_queries = queries.to_vec();
+    unique_queries.sort_unstable();
+    unique_queries.dedup();
+    let unique_paths = tree.get_merkle_paths(&unique_queries);
+
+    let merkle_paths = queries
+        .iter()
+        .map(|query| {
+            let slot = unique_queries
+                .binary_search(query)
+                .expect("every query is among the unique queries");
+            unique_paths[slot].clone()
+        })
+        .collect();
 
     let queried_symbols = queries
         .iter()
-        .map(|i| (code.encoding[i << 1], code.encoding[(i << 1) | 1]))
+        .map(|i| {
+            let index = leaf_index_to_codeword_index(*i);
+            (code.encoding[index], code.encoding[index | 1])
+        })
         .collect();
 
     (merkle_paths, queried_symbols)
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Univariate {
     pub coeffs: Vec<BinaryField128b>,
 }
@@ -311,27 +3252,76 @@ impl Univariate {
         }
         eval
     }
+
+    /// Keccak digest of the coefficients, absorbed in their place by a channel from
+    /// [`Channel::digesting_oracles`].
+    pub fn digest(&self) -> Hash {
+        Self::digest_coeffs(&self.coeffs)
+    }
+
+    pub fn digest_coeffs(coeffs: &[BinaryField128b]) -> Hash {
+        oracle_digest(coeffs)
+    }
 }
 
+/// Largest variable count [`get_partial_evals`] can address: 31 on 32-bit targets and 63 on
+/// 64-bit ones. Every coefficient bit index `k | (j << tau)` is below `2^variables`, and at
+/// `tau = 0` the `2^variables` bits of a row must themselves be counted in a `usize`, so the bound
+/// sits one below `usize::BITS` whatever the split.
+pub const MAX_PARTIAL_EVAL_VARIABLES: usize = usize::BITS as usize - 1;
+
 #[instrument(skip_all, name = "get partial evals", level = "debug")]
-pub fn get_partial_evals<F>(mle: &PackedMLE<F>, eq: &LagrangeBases) -> Vec<BinaryField128b>
+pub fn get_partial_evals<F>(mle: &PackedMLE<F>, eq: &LagrangeBases) -> Result<Vec<BinaryField128b>>
+where
+    F: BinaryField + TowerField,
+    BinaryField128b: ExtensionField<F>,
+{
+    get_partial_evals_at(mle, eq, TAU)
+}
+
+/// Number of rows and of bits per row of a ring switch of `variables` variables at split `tau`:
+/// `2^tau` rows of `2^(variables - tau)` bits, or an error unless
+/// `tau <= variables <= MAX_PARTIAL_EVAL_VARIABLES`.
+fn partial_eval_shape(variables: usize, tau: usize) -> Result<(usize, usize)> {
+    ensure!(
+        (tau..=MAX_PARTIAL_EVAL_VARIABLES).contains(&variables),
+        "{variables} variables outside the addressable range {tau}..={MAX_PARTIAL_EVAL_VARIABLES}"
+    );
+    let shift = |bits: usize| {
+        u32::try_from(bits)
+            .ok()
+            .and_then(|bits| 1usize.checked_shl(bits))
+            .with_context(|| format!("2^{bits} does not fit in usize"))
+    };
+    Ok((shift(tau)?, shift(variables - tau)?))
+}
+
+/// [`get_partial_evals`] for a ring switch at split `tau`: one partial evaluation per row
+/// `k < 2^tau`. Errors unless `tau <= mle.variables <= MAX_PARTIAL_EVAL_VARIABLES`.
+pub fn get_partial_evals_at<F>(
+    mle: &PackedMLE<F>,
+    eq: &LagrangeBases,
+    tau: usize,
+) -> Result<Vec<BinaryField128b>>
 where
     F: BinaryField + TowerField,
     BinaryField128b: ExtensionField<F>,
 {
-    (0..1 << TAU)
+    let (rows, row_len) = partial_eval_shape(mle.variables, tau)?;
+    // Below the bound, `j < 2^(variables - tau)` keeps `j << tau` under `2^variables`.
+    let shift = tau as u32;
+
+    Ok((0..rows)
         .into_par_iter()
         .map(|k| {
-            let vars = mle.variables;
-
-            let res: BinaryField128b = (0..1 << (vars - TAU))
+            let res: BinaryField128b = (0..row_len)
                 .into_iter()
-                .map(move |j| mle.packed_idx(k.clone() | (j << TAU)) * eq.idx(j))
+                .map(move |j| mle.packed_idx(k | (j << shift)) * eq.idx(j))
                 .sum();
 
             res
         })
-        .collect()
+        .collect())
 }
 
 #[instrument(skip_all, name = "sum check", level = "debug")]
@@ -341,18 +3331,23 @@ pub fn sum_check_round(
     sum_check_claim: BinaryField128b,
 ) -> Univariate {
     let half_size = mle.len() / 2;
-    let (eval_at_0, eval_at_inf) = (0..half_size)
-        .into_par_iter()
-        .map(|i| {
-            (
-                mle.idx(i << 1) * eq.idx(i << 1),
-                (mle.idx(i << 1) + mle.idx((i << 1) | 1)) * (eq.idx(i << 1) + eq.idx((i << 1) | 1)),
-            )
-        })
-        .reduce(
-            || (BinaryField128b::ZERO, BinaryField128b::ZERO),
-            |(acc_0, acc_1), (e_0, e_1)| (acc_0 + e_0, acc_1 + e_1),
-        );
+    let pair_evals = |i: usize| {
+        (
+            mle.idx(i << 1) * eq.idx(i << 1),
+            (mle.idx(i << 1) + mle.idx((i << 1) | 1)) * (eq.idx(i << 1) + eq.idx((i << 1) | 1)),
+        )
+    };
+    let zero = (BinaryField128b::ZERO, BinaryField128b::ZERO);
+    let add =
+        |(acc_0, acc_1): (BinaryField128b, BinaryField128b), (e_0, e_1)| (acc_0 + e_0, acc_1 + e_1);
+    let (eval_at_0, eval_at_inf) = if parallelism_available() {
+        (0..half_size)
+            .into_par_iter()
+            .map(pair_evals)
+            .reduce(|| zero, add)
+    } else {
+        (0..half_size).map(pair_evals).fold(zero, add)
+    };
 
     let eval_at_1 = sum_check_claim - eval_at_0;
     Univariate::new(vec![
@@ -361,3 +3356,833 @@ pub fn sum_check_round(
         eval_at_inf,
     ])
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use binius_field::{BinaryField32b, BinaryField64b};
+    use binius_ntt::SingleThreadedNTT;
+    use rand::{Rng, thread_rng};
+
+    use super::*;
+    use crate::{
+        utils::{
+            merkle::{Blake3Hasher, compute_leaf_hashes, merkle_root_from_symbols, merklize},
+            parallel::with_serial_fallback,
+        },
+        verifier::verify_positions,
+    };
+
+    fn random_mle(log_len: usize) -> PackedMLE<BinaryField64b> {
+        let coeffs = (0..1 << log_len)
+            .map(|_| BinaryField64b::random(thread_rng()))
+            .collect();
+        PackedMLE::new(coeffs, true)
+    }
+
+    /// A random polynomial of `2^l` coefficients, an NTT sized for it and its default commitment.
+    struct TestInstance {
+        mle: PackedMLE<BinaryField64b>,
+        ntt: MultithreadedNTT<BinaryField32b>,
+        commitment: FriCommitment,
+        code: Code<BinaryField128b>,
+        merkle_tree: MerkleTree,
+    }
+
+    fn random_instance(l: usize) -> TestInstance {
+        let mle = random_mle(l);
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(l + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let (commitment, code, merkle_tree) = commit(&mle, &ntt).unwrap();
+        TestInstance {
+            mle,
+            ntt,
+            commitment,
+            code,
+            merkle_tree,
+        }
+    }
+
+    #[test]
+    fn partial_eval_bounds_test() {
+        // At the bound every split, down to tau = 0, still counts its rows and row bits.
+        let max = MAX_PARTIAL_EVAL_VARIABLES;
+        for tau in [0, 1, TAU] {
+            assert_eq!(
+                partial_eval_shape(max, tau).unwrap(),
+                (1 << tau, 1 << (max - tau))
+            );
+            assert!(partial_eval_shape(max + 1, tau).is_err());
+        }
+        assert!(partial_eval_shape(TAU - 1, TAU).is_err());
+
+        // Provers report an unaddressable polynomial as an error rather than panicking.
+        let mle = |variables| PackedMLE::<BinaryField64b> {
+            packing_factor: 6,
+            variables,
+            coeffs: vec![],
+            unpadded_len: 0,
+        };
+        let point = vec![BinaryField128b::ONE; TAU + 1];
+        let commitment = FriCommitment::from_parts([0; 32], 1, 6, TAU + 1);
+        let encoding = Code::default();
+        let check = |variables| {
+            check_opening_inputs(&mle(variables), [point.as_slice()], &encoding, &commitment)
+        };
+        assert_eq!(check(MAX_PARTIAL_EVAL_VARIABLES).unwrap(), TAU);
+        assert!(check(MAX_PARTIAL_EVAL_VARIABLES + 1).is_err());
+        assert!(check(TAU - 1).is_err());
+    }
+
+    #[test]
+    fn partial_evals_overflow_test() {
+        let mle = PackedMLE::<BinaryField64b> {
+            packing_factor: 6,
+            variables: MAX_PARTIAL_EVAL_VARIABLES + 1,
+            coeffs: vec![],
+            unpadded_len: 0,
+        };
+        for tau in [0, TAU] {
+            assert!(get_partial_evals_at(&mle, &LagrangeBases::new(), tau).is_err());
+        }
+    }
+
+    #[test]
+    fn padded_mle_test() {
+        let len = (1 << 12) + 37;
+        let coeffs: Vec<BinaryField64b> = (0..len)
+            .map(|_| BinaryField64b::random(thread_rng()))
+            .collect();
+        let mle = PackedMLE::new_padded(coeffs.clone(), true);
+        assert_eq!(mle.len(), 1 << 13);
+        assert_eq!(mle.unpadded_len, len);
+        assert_eq!(mle.unpadded_coeffs(), &coeffs[..]);
+        assert_eq!(mle.variables, 13 + 6);
+
+        // The original polynomial's evaluation, summed over its own bits only.
+        let point: Vec<BinaryField128b> = (0..mle.variables)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        let eq = compute_eq(&point);
+        let eval = (0..len << 6)
+            .filter(|&i| mle.packed_idx(i) == BinaryField1b::ONE)
+            .map(|i| eq[i])
+            .sum::<BinaryField128b>();
+        assert_eq!(
+            mle.get_bound_elem(0, &LagrangeBases::gen_from_point(&point)),
+            eval
+        );
+
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(13 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let (commitment, code, merkle_tree) = commit(&mle, &ntt).unwrap();
+        let proof = prove(
+            &mle,
+            &point,
+            eval,
+            &code,
+            &commitment,
+            &merkle_tree,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        crate::verifier::verify(&commitment, &point, eval, proof, &ntt, &mut Channel::new())
+            .unwrap();
+    }
+
+    #[test]
+    fn validate_params_test() {
+        let l = 10;
+        let TestInstance { mle, ntt, .. } = random_instance(l);
+        validate_params(&mle, &ntt).unwrap();
+
+        let small_ntt = SingleThreadedNTT::<BinaryField32b>::new(l + LOG_RATE - 1)
+            .unwrap()
+            .multithreaded();
+        assert!(validate_params(&mle, &small_ntt).is_err());
+        assert!(commit(&mle, &small_ntt).is_err());
+    }
+
+    #[test]
+    fn shared_ntt_test() {
+        // One NTT sized for the largest polynomial serves every smaller one.
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(12 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+
+        for l in [10, 11, 12] {
+            let mle = random_mle(l);
+            let sized_ntt = SingleThreadedNTT::<BinaryField32b>::new(l + LOG_RATE)
+                .unwrap()
+                .multithreaded();
+            let (commitment, code, merkle_tree) = commit(&mle, &ntt).unwrap();
+            let (sized_commitment, sized_code, _) = commit(&mle, &sized_ntt).unwrap();
+            assert_eq!(code.encoding, sized_code.encoding);
+            assert_eq!(commitment, sized_commitment);
+
+            let point: Vec<BinaryField128b> = (0..mle.variables)
+                .map(|_| BinaryField128b::random(thread_rng()))
+                .collect();
+            let eval = mle.get_bound_elem(0, &LagrangeBases::gen_from_point(&point));
+            let proof = prove(
+                &mle,
+                &point,
+                eval,
+                &code,
+                &commitment,
+                &merkle_tree,
+                &ntt,
+                &mut Channel::new(),
+            )
+            .unwrap();
+            crate::verifier::verify(
+                &commitment,
+                &point,
+                eval,
+                proof.clone(),
+                &ntt,
+                &mut Channel::new(),
+            )
+            .unwrap();
+            // The fold twiddles agree, so a verifier holding a right-sized NTT accepts it too.
+            crate::verifier::verify(
+                &commitment,
+                &point,
+                eval,
+                proof,
+                &sized_ntt,
+                &mut Channel::new(),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn fri_commitment_bytes_test() {
+        let TestInstance { commitment, .. } = random_instance(10);
+
+        let bytes = commitment.to_bytes();
+        assert_eq!(bytes.len(), <FriCommitment>::BYTES);
+        assert_eq!(<FriCommitment>::from_bytes(&bytes).unwrap(), commitment);
+        assert!(<FriCommitment>::from_bytes(&bytes[..<FriCommitment>::BYTES - 1]).is_err());
+
+        let oversized = FriCommitment {
+            variables: MAX_PARTIAL_EVAL_VARIABLES + 1,
+            ..commitment
+        };
+        assert!(<FriCommitment>::from_bytes(&oversized.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn commit_with_symbols_per_leaf_test() {
+        use crate::utils::merkle::{
+            codeword_index_to_leaf_index_with, compute_leaf_hashes_with, verify_merkle_path,
+        };
+
+        let TestInstance {
+            mle,
+            ntt,
+            commitment: default_commitment,
+            ..
+        } = random_instance(10);
+
+        let (commitment, code, merkle_tree) = commit_with_symbols_per_leaf(&mle, &ntt, 1).unwrap();
+        assert_eq!(
+            commitment.vector_commitment.depth,
+            code.encoding.len().trailing_zeros() as usize
+        );
+
+        let bytes = commitment.to_bytes();
+        assert_eq!(<FriCommitment>::from_bytes(&bytes).unwrap(), commitment);
+
+        let index = thread_rng().gen_range(0..code.encoding.len());
+        let leaf = codeword_index_to_leaf_index_with(index, commitment.symbols_per_leaf);
+        let leaf_hash =
+            compute_leaf_hashes_with::<Keccak256Hasher>(&code.encoding[index..index + 1], 1)[0];
+        verify_merkle_path(
+            &commitment.vector_commitment,
+            leaf_hash,
+            leaf,
+            &merkle_tree.get_merkle_path(leaf),
+        )
+        .unwrap();
+
+        assert_eq!(
+            default_commitment.vector_commitment.depth + 1,
+            commitment.vector_commitment.depth
+        );
+    }
+
+    #[test]
+    fn expected_final_value_test() {
+        for (l, tau) in [(8, TAU), (10, TAU), (10, 5)] {
+            let mle = random_mle(l);
+            let ntt = SingleThreadedNTT::<BinaryField32b>::new(l + 1 + LOG_RATE)
+                .unwrap()
+                .multithreaded();
+            let (_, code, _) = commit_with_tau::<_, _, Keccak256Hasher>(&mle, &ntt, tau).unwrap();
+
+            let rounds = mle.variables - tau;
+            let challenges: Vec<BinaryField128b> = (0..rounds)
+                .map(|_| BinaryField128b::random(thread_rng()))
+                .collect();
+
+            let mut folded_code = code;
+            for (round, r) in challenges.iter().enumerate() {
+                folded_code = folded_code.fold_code(*r, round, &ntt);
+            }
+
+            assert_eq!(
+                expected_final_value(&mle, &challenges, tau),
+                folded_code.idx(0)
+            );
+        }
+    }
+
+    #[test]
+    fn commit_hypercube_evals_test() {
+        let TestInstance {
+            mle,
+            ntt,
+            commitment,
+            code,
+            merkle_tree,
+        } = random_instance(10);
+        let evals = &mle.coeffs;
+
+        let point: Vec<BinaryField128b> = (0..mle.variables)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+
+        // Reference multilinear extension: fold the unpacked hypercube values one variable at a
+        // time, lowest index bit first.
+        let mut table: Vec<BinaryField128b> = evals
+            .iter()
+            .flat_map(|elem| {
+                <BinaryField64b as ExtensionField<BinaryField1b>>::iter_bases(elem)
+                    .map(BinaryField128b::from)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        for r in &point {
+            table = table
+                .chunks_exact(2)
+                .map(|pair| pair[0] + *r * (pair[0] + pair[1]))
+                .collect();
+        }
+        let eval = table[0];
+        assert_eq!(
+            mle.get_bound_elem(0, &LagrangeBases::gen_from_point(&point)),
+            eval
+        );
+
+        let proof = prove(
+            &mle,
+            &point,
+            eval,
+            &code,
+            &commitment,
+            &merkle_tree,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        crate::verifier::verify(&commitment, &point, eval, proof, &ntt, &mut Channel::new())
+            .unwrap();
+    }
+
+    #[test]
+    fn commit_from_iter_test() {
+        let l = 10;
+        let TestInstance {
+            mle,
+            ntt,
+            commitment,
+            code,
+            ..
+        } = random_instance(l);
+        let (iter_mle, iter_commitment, iter_code, _) =
+            commit_from_iter(mle.coeffs.iter().copied(), 1 << l, true, &ntt).unwrap();
+        assert_eq!(iter_commitment, commitment);
+        assert_eq!(iter_code.encoding, code.encoding);
+        assert_eq!(iter_mle.coeffs, mle.coeffs);
+        assert_eq!(iter_mle.variables, mle.variables);
+
+        let coeffs = || mle.coeffs.iter().copied();
+        assert!(commit_from_iter(coeffs().take((1 << l) - 1), 1 << l, true, &ntt).is_err());
+        assert!(commit_from_iter(coeffs().chain(coeffs()), 1 << l, true, &ntt).is_err());
+    }
+
+    #[test]
+    fn commit_root_only_test() {
+        let TestInstance {
+            mle,
+            ntt,
+            commitment,
+            code,
+            merkle_tree,
+        } = random_instance(10);
+        assert_eq!(commit_root_only(&mle, &ntt).unwrap(), commitment);
+        assert_eq!(
+            merkle_root_from_symbols(&code.encoding).unwrap(),
+            merkle_tree.get_root()
+        );
+        assert!(merkle_root_from_symbols(&code.encoding[..3]).is_err());
+    }
+
+    #[test]
+    fn expected_root_test() {
+        let TestInstance {
+            mle,
+            ntt,
+            commitment,
+            ..
+        } = random_instance(10);
+        assert_eq!(
+            expected_root::<_, _, Keccak256Hasher>(&mle.coeffs, &ntt).unwrap(),
+            commitment.vector_commitment.root
+        );
+        assert!(expected_root::<_, _, Keccak256Hasher>(&mle.coeffs[..3], &ntt).is_err());
+
+        let (commitment, _, _) = commit_with_hasher::<_, _, Blake3Hasher>(&mle, &ntt).unwrap();
+        assert_eq!(
+            expected_root::<_, _, Blake3Hasher>(&mle.coeffs, &ntt).unwrap(),
+            commitment.vector_commitment.root
+        );
+    }
+
+    #[test]
+    fn commit_from_encoding_test() {
+        let TestInstance {
+            mle,
+            ntt,
+            commitment,
+            code,
+            merkle_tree,
+        } = random_instance(10);
+        let external = Code::new_ext(&mle.coeffs, &ntt);
+        let (enc_commitment, enc_code, enc_tree) =
+            commit_from_encoding(external.encoding, BinaryField64b::TOWER_LEVEL).unwrap();
+        assert_eq!(enc_commitment, commitment);
+        assert_eq!(enc_code.encoding, code.encoding);
+        assert_eq!(enc_tree.get_root(), merkle_tree.get_root());
+
+        let mut odd = code.encoding.clone();
+        odd.pop();
+        assert!(commit_from_encoding::<Keccak256Hasher>(odd, BinaryField64b::TOWER_LEVEL).is_err());
+        assert!(commit_from_encoding::<Keccak256Hasher>(code.encoding, 8).is_err());
+    }
+
+    #[test]
+    fn prove_restriction_test() {
+        let TestInstance { mle, ntt, .. } = random_instance(10);
+        let ctx = ProverContext::new(mle, &ntt).unwrap();
+        let full_point: Vec<BinaryField128b> = (0..ctx.mle.variables)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        let (fixed_prefix, point) = full_point.split_at(3);
+
+        let (eval, proof) = ctx
+            .prove_restriction(fixed_prefix, point, &mut Channel::new())
+            .unwrap();
+        assert_eq!(eval, ctx.evaluate(&full_point));
+        assert_eq!(proof, ctx.prove(&full_point, &mut Channel::new()).unwrap());
+        crate::verifier::verify(
+            &ctx.commitment,
+            &full_point,
+            eval,
+            proof,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        assert!(
+            ctx.prove_restriction(fixed_prefix, &point[1..], &mut Channel::new())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn open_positions_test() {
+        let TestInstance { mle, ntt, .. } = random_instance(10);
+        let ctx = ProverContext::new(mle, &ntt).unwrap();
+        let len = ctx.code.encoding.len();
+        let indices: Vec<usize> = (0..32).map(|_| thread_rng().gen_range(0..len)).collect();
+
+        let openings = ctx.open_positions(&indices).unwrap();
+        for (&index, opening) in indices.iter().zip(&openings) {
+            assert_eq!(opening.value, ctx.code.encoding[index]);
+        }
+        verify_positions(&ctx.commitment, &indices, &openings).unwrap();
+
+        let mut tampered = openings.clone();
+        tampered[5].value += BinaryField128b::ONE;
+        let mut moved = indices.clone();
+        moved[5] ^= 2;
+        assert!(verify_positions(&ctx.commitment, &moved, &openings).is_err());
+        assert!(verify_positions(&ctx.commitment, &indices, &tampered).is_err());
+        // Positions past the codeword would otherwise alias leaves of the tree.
+        let mut aliased = indices.clone();
+        aliased[0] += len;
+        assert!(verify_positions(&ctx.commitment, &aliased, &openings).is_err());
+        assert!(verify_positions(&ctx.commitment, &indices[1..], &openings).is_err());
+        assert!(ctx.open_positions(&[len]).is_err());
+    }
+
+    #[test]
+    fn update_coefficients_test() {
+        let l = 10;
+        let TestInstance { mle, ntt, .. } = random_instance(l);
+        let mut ctx = ProverContext::new(mle, &ntt).unwrap();
+        let before = ctx.code.encoding.clone();
+
+        let value = BinaryField64b::random(thread_rng());
+        ctx.update_coefficient(37, value).unwrap();
+        let fresh = ProverContext::new(ctx.mle.clone(), &ntt).unwrap();
+        assert_eq!(ctx.mle.coeffs[37], value);
+        assert_eq!(ctx.commitment, fresh.commitment);
+        assert_eq!(ctx.code.encoding, fresh.code.encoding);
+
+        // One coefficient moves nearly every codeword symbol.
+        let changed = before
+            .iter()
+            .zip(&ctx.code.encoding)
+            .filter(|(a, b)| a != b)
+            .count();
+        assert!(changed >= 3 * before.len() / 4);
+
+        let updates: Vec<(usize, BinaryField64b)> = (0..8)
+            .map(|i| (i * 100, BinaryField64b::random(thread_rng())))
+            .collect();
+        ctx.update_coefficients(&updates).unwrap();
+        let recommitted = commit(&ctx.mle, &ntt).unwrap().0;
+        assert_eq!(ctx.commitment, recommitted);
+
+        assert!(ctx.update_coefficient(1 << l, value).is_err());
+        assert_eq!(ctx.commitment, recommitted);
+    }
+
+    #[test]
+    fn commit_bits_test() {
+        // 65000 bits pack into 1016 coefficients of 64 bits, padded to 1024.
+        let bits: Vec<BinaryField1b> = (0..65000)
+            .map(|_| BinaryField1b::random(thread_rng()))
+            .collect();
+        let packed_len = FieldChoice::B64.packed_len(bits.len());
+        assert_eq!(packed_len, 1 << 10);
+
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(10 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let (commitment, witness) = commit_bits(&bits, FieldChoice::B64, &ntt).unwrap();
+        assert_eq!(witness.bit_len, bits.len());
+        assert_eq!(commitment.variables(), 16);
+
+        let point: Vec<BinaryField128b> = (0..16)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+
+        // Reference multilinear extension over the zero-padded bits.
+        let mut table: Vec<BinaryField128b> = bits
+            .iter()
+            .map(|bit| BinaryField128b::from(*bit))
+            .chain(std::iter::repeat(BinaryField128b::ZERO))
+            .take(1 << 16)
+            .collect();
+        for r in &point {
+            table = table
+                .chunks_exact(2)
+                .map(|pair| pair[0] + *r * (pair[0] + pair[1]))
+                .collect();
+        }
+        let eval = witness.evaluate(&point);
+        assert_eq!(eval, table[0]);
+
+        let proof = witness
+            .prove(&point, eval, &commitment, &ntt, &mut Channel::new())
+            .unwrap();
+        crate::verifier::verify(&commitment, &point, eval, proof, &ntt, &mut Channel::new())
+            .unwrap();
+
+        // The NTT is sized for the 64-bit packing, not the 32-bit one.
+        assert!(commit_bits(&bits, FieldChoice::B32, &ntt).is_err());
+    }
+
+    #[test]
+    fn prove_at_channel_point_test() {
+        let TestInstance { mle, ntt, .. } = random_instance(10);
+
+        let (commitment, point, eval, proof) =
+            prove_at_channel_point(&mle, &ntt, &mut Channel::new()).unwrap();
+        assert_eq!(point.len(), mle.variables);
+
+        let mut channel = Channel::new();
+        channel.observe_fri_commitment(&commitment);
+        assert_eq!(channel.get_random_points(mle.variables).unwrap(), point);
+        crate::verifier::verify(&commitment, &point, eval, proof.clone(), &ntt, &mut channel)
+            .unwrap();
+
+        // Without the point-drawing prefix the transcripts diverge.
+        assert!(
+            crate::verifier::verify(&commitment, &point, eval, proof, &ntt, &mut Channel::new())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn open_many_test() {
+        let TestInstance { mle, ntt, .. } = random_instance(10);
+        let ctx = ProverContext::new(mle, &ntt).unwrap();
+
+        let points: Vec<Vec<BinaryField128b>> = (0..3)
+            .map(|_| {
+                (0..ctx.mle.variables)
+                    .map(|_| BinaryField128b::random(thread_rng()))
+                    .collect()
+            })
+            .collect();
+        let channel = Channel::new();
+        let proofs = ctx.open_many(&points, &channel).unwrap();
+        assert_eq!(proofs.len(), points.len());
+
+        for (i, (point, proof)) in points.iter().zip(proofs).enumerate() {
+            crate::verifier::verify(
+                &ctx.commitment,
+                point,
+                ctx.evaluate(point),
+                proof,
+                &ntt,
+                &mut channel.fork(i as u64),
+            )
+            .unwrap();
+        }
+    }
+
+    fn random_tree_and_code(log_len: usize) -> (MerkleTree, Code<BinaryField128b>) {
+        let encoding: Vec<BinaryField128b> = (0..1 << log_len)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        let merkle_tree = merklize(compute_leaf_hashes::<Keccak256Hasher>(&encoding));
+        let code = Code {
+            encoding,
+            coset_offset: 0,
+        };
+        (merkle_tree, code)
+    }
+
+    #[test]
+    fn gather_round_queries_test() {
+        // A small tree forces repeated queries, as in the late rounds of the query phase.
+        for log_len in [3, 12] {
+            let (merkle_tree, code) = random_tree_and_code(log_len);
+            let queries: Vec<usize> = (0..144)
+                .map(|_| thread_rng().gen_range(0..1 << (log_len - 1)))
+                .collect();
+
+            let (paths, symbols) = gather_round_queries(&merkle_tree, &code, &queries);
+            for (i, query) in queries.iter().enumerate() {
+                assert_eq!(paths[i], merkle_tree.get_merkle_path(*query));
+                let index = leaf_index_to_codeword_index(*query);
+                assert_eq!(symbols[i], (code.encoding[index], code.encoding[index | 1]));
+            }
+        }
+    }
+
+    #[test]
+    fn deduplicated_paths_match_batched_test() {
+        let (merkle_tree, code) = random_tree_and_code(4);
+        let queries: Vec<usize> = (0..16).map(|_| thread_rng().gen_range(0..1 << 3)).collect();
+        let (deduplicated, _) = gather_round_queries(&merkle_tree, &code, &queries);
+        assert_eq!(deduplicated, merkle_tree.get_merkle_paths(&queries));
+    }
+
+    #[test]
+    fn serial_fallback_test() {
+        let TestInstance { mle, ntt, .. } = random_instance(10);
+        let point: Vec<BinaryField128b> = (0..mle.variables)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        let eval = mle.get_bound_elem(0, &LagrangeBases::gen_from_point(&point));
+
+        let commit_and_prove = || {
+            let (commitment, code, merkle_tree) = commit(&mle, &ntt).unwrap();
+            let proof = prove(
+                &mle,
+                &point,
+                eval,
+                &code,
+                &commitment,
+                &merkle_tree,
+                &ntt,
+                &mut Channel::new(),
+            )
+            .unwrap();
+            (commitment, merkle_tree, proof)
+        };
+
+        let parallel = commit_and_prove();
+        let serial = with_serial_fallback(|| {
+            assert!(!parallelism_available());
+            commit_and_prove()
+        });
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn prove_in_pool_test() {
+        let TestInstance {
+            mle,
+            ntt,
+            commitment,
+            code,
+            merkle_tree,
+        } = random_instance(10);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+
+        let (pool_commitment, pool_code, pool_merkle_tree) =
+            commit_in_pool(&pool, &mle, &ntt).unwrap();
+        assert_eq!(pool_commitment, commitment);
+        assert_eq!(pool_code.encoding, code.encoding);
+        assert_eq!(pool_merkle_tree, merkle_tree);
+
+        let point: Vec<BinaryField128b> = (0..mle.variables)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        let eval = mle.get_bound_elem(0, &LagrangeBases::gen_from_point(&point));
+
+        let proof = prove(
+            &mle,
+            &point,
+            eval,
+            &code,
+            &commitment,
+            &merkle_tree,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        let pool_proof = prove_in_pool(
+            &pool,
+            &mle,
+            &point,
+            eval,
+            &code,
+            &commitment,
+            &merkle_tree,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert_eq!(pool_proof, proof);
+    }
+
+    /// Counts the `WARN` events raised while it is the default subscriber.
+    struct WarnCounter(Arc<AtomicUsize>);
+
+    impl tracing::Subscriber for WarnCounter {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            if *event.metadata().level() == tracing::Level::WARN {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[test]
+    fn validate_eval_point_test() {
+        let warnings = |point: &[BinaryField128b]| {
+            let count = Arc::new(AtomicUsize::new(0));
+            tracing::subscriber::with_default(WarnCounter(count.clone()), || {
+                validate_eval_point(point)
+            })
+            .unwrap();
+            count.load(Ordering::Relaxed)
+        };
+
+        // Zero coordinates, which also repeat one another, raise one warning each.
+        let zero_point = vec![BinaryField128b::ZERO; TAU + 4];
+        assert_eq!(warnings(&zero_point), 2);
+
+        let random_point: Vec<BinaryField128b> = (0..TAU + 4)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        assert_eq!(warnings(&random_point), 0);
+
+        assert!(validate_eval_point(&random_point[..TAU]).is_err());
+    }
+
+    #[test]
+    fn tau_variables_test() {
+        // Two 64-bit coefficients: exactly TAU variables, no coordinates left to fold.
+        let mle = PackedMLE::new(vec![BinaryField64b::ONE; 2], true);
+        assert_eq!(mle.variables, TAU);
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(1 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+
+        let err = commit(&mle, &ntt).unwrap_err();
+        assert!(err.to_string().contains("TAU"), "{err}");
+        assert!(ProverContext::new(mle, &ntt).is_err());
+
+        let point = vec![BinaryField128b::ONE; TAU];
+        assert!(PointEqTables::new(&point).is_err());
+    }
+
+    #[test]
+    fn fri_round_test() {
+        let l = 8;
+        let coeffs: Vec<BinaryField128b> = (0..1 << l)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        let point: Vec<BinaryField128b> = (0..l)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        let r = BinaryField128b::random(thread_rng());
+
+        let ntt = SingleThreadedNTT::<BinaryField128b>::new(l + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let code = Code::new(&coeffs, &ntt);
+
+        let mle = PackedMLE::new(coeffs, false);
+        let mut fused_mle = mle.clone();
+        let mut fused_eq = LagrangeBases::gen_from_point(&point);
+        let fused_code = fri_round(&code, &mut fused_mle, &mut fused_eq, r, 0, &ntt);
+
+        let separate_code = code.fold_code(r, 0, &ntt);
+        let separate_mle = mle.fold_lo(&r);
+        let mut separate_eq = LagrangeBases::gen_from_point(&point);
+        separate_eq.fold_lo(&r);
+
+        assert_eq!(fused_code.encoding, separate_code.encoding);
+        assert_eq!(fused_mle.coeffs, separate_mle.coeffs);
+        assert_eq!(fused_eq.vals, separate_eq.vals);
+        assert_eq!(fused_eq.vars, separate_eq.vars);
+    }
+}
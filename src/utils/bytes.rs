@@ -0,0 +1,145 @@
+use crate::{
+    Result,
+    utils::merkle::{Hash, MerkleHasher},
+};
+use anyhow::{Context, ensure};
+use binius_field::BinaryField128b;
+use sha3::digest::generic_array::GenericArray;
+
+/// Byte order of integers and field elements in the external proof format.
+///
+/// Only the wire format is affected; the Fiat-Shamir transcript always absorbs little-endian bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProofEncoding {
+    #[default]
+    Little,
+    Big,
+}
+
+impl ProofEncoding {
+    pub fn put_u64(self, bytes: &mut Vec<u8>, val: usize) {
+        let val = val as u64;
+        match self {
+            ProofEncoding::Little => bytes.extend_from_slice(&val.to_le_bytes()),
+            ProofEncoding::Big => bytes.extend_from_slice(&val.to_be_bytes()),
+        }
+    }
+
+    pub fn put_u16(self, bytes: &mut Vec<u8>, val: u16) {
+        match self {
+            ProofEncoding::Little => bytes.extend_from_slice(&val.to_le_bytes()),
+            ProofEncoding::Big => bytes.extend_from_slice(&val.to_be_bytes()),
+        }
+    }
+
+    pub fn put_field(self, bytes: &mut Vec<u8>, elem: BinaryField128b) {
+        match self {
+            ProofEncoding::Little => bytes.extend_from_slice(&elem.val().to_le_bytes()),
+            ProofEncoding::Big => bytes.extend_from_slice(&elem.val().to_be_bytes()),
+        }
+    }
+}
+
+/// Little-endian writers for the fixed-width pieces of serialized proofs.
+pub fn put_u64(bytes: &mut Vec<u8>, val: usize) {
+    ProofEncoding::Little.put_u64(bytes, val);
+}
+
+pub fn put_field(bytes: &mut Vec<u8>, elem: BinaryField128b) {
+    ProofEncoding::Little.put_field(bytes, elem);
+}
+
+pub fn put_hash(bytes: &mut Vec<u8>, hash: &impl AsRef<[u8]>) {
+    bytes.extend_from_slice(hash.as_ref());
+}
+
+/// Cursor over serialized bytes whose reads fail, rather than panic, on truncated input.
+pub struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    encoding: ProofEncoding,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self::with_encoding(bytes, ProofEncoding::Little)
+    }
+
+    pub fn with_encoding(bytes: &'a [u8], encoding: ProofEncoding) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            encoding,
+        }
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .with_context(|| format!("unexpected end of input at byte {}", self.pos))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    pub fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?.try_into()?;
+        Ok(match self.encoding {
+            ProofEncoding::Little => u16::from_le_bytes(bytes),
+            ProofEncoding::Big => u16::from_be_bytes(bytes),
+        })
+    }
+
+    pub fn u64(&mut self) -> Result<usize> {
+        let bytes = self.take(8)?.try_into()?;
+        let val = match self.encoding {
+            ProofEncoding::Little => u64::from_le_bytes(bytes),
+            ProofEncoding::Big => u64::from_be_bytes(bytes),
+        };
+        usize::try_from(val).context("length does not fit in usize")
+    }
+
+    /// Reads a length prefix, rejecting counts that could not fit in the remaining input.
+    pub fn read_len(&mut self, elem_size: usize) -> Result<usize> {
+        let len = self.u64()?;
+        ensure!(
+            len.saturating_mul(elem_size) <= self.remaining(),
+            "length {len} exceeds remaining input"
+        );
+        Ok(len)
+    }
+
+    pub fn field(&mut self) -> Result<BinaryField128b> {
+        let bytes = self.take(16)?.try_into()?;
+        let val = match self.encoding {
+            ProofEncoding::Little => u128::from_le_bytes(bytes),
+            ProofEncoding::Big => u128::from_be_bytes(bytes),
+        };
+        Ok(BinaryField128b::new(val))
+    }
+
+    pub fn hash(&mut self) -> Result<Hash> {
+        Ok(Hash(GenericArray::clone_from_slice(self.take(32)?)))
+    }
+
+    /// Reads one `H` digest of [`MerkleHasher::DIGEST_BYTES`].
+    pub fn digest<H: MerkleHasher>(&mut self) -> Result<H::Digest> {
+        H::digest_from_bytes(self.take(H::DIGEST_BYTES)?)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    /// Fails unless every byte has been consumed.
+    pub fn finish(self) -> Result<()> {
+        ensure!(
+            self.remaining() == 0,
+            "{} trailing bytes after proof",
+            self.remaining()
+        );
+        Ok(())
+    }
+}
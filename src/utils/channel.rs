@@ -1,16 +1,142 @@
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, bail, ensure};
 use binius_field::{BinaryField, BinaryField128b, TowerField, serialize_canonical};
 use binius_utils::serialization::DeserializeBytes;
 use sha3::{Digest, Keccak256};
 
-use crate::prover::FriCommitment;
+use super::{
+    code::{LOG_RATE, query_count},
+    merkle::{Hash, MerkleHasher, VectorCommitment},
+};
 
-use super::merkle::VectorCommitment;
+/// Queries [`Channel::gen_queries`] draws by default: 96 bits of security at R=4 as per [DP24].
+pub const DEFAULT_NUM_QUERIES: usize = 144;
+
+/// Absorbed by [`Channel::for_application`] ahead of the domain.
+pub const APP_DOMAIN_TAG: &[u8] = b"fri-binius/app-domain";
+
+/// A single Fiat–Shamir interaction recorded by a [`Channel`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptEntry {
+    /// Raw bytes absorbed into the sponge.
+    Observe(Vec<u8>),
+    /// Challenge squeezed from the sponge.
+    Challenge(BinaryField128b),
+    /// Raw squeezed bytes backing a [`ChallengeField`] element other than a 128-bit point.
+    Squeeze(Vec<u8>),
+}
+
+/// Field whose elements can be drawn from a [`Channel`], consuming [`ChallengeField::BYTES`]
+/// squeezed bytes per element.
+pub trait ChallengeField: Sized {
+    /// Number of squeezed bytes consumed per element.
+    const BYTES: usize;
+
+    fn from_challenge_bytes(bytes: &[u8]) -> Result<Self>;
+}
+
+impl ChallengeField for BinaryField128b {
+    const BYTES: usize = 16;
+
+    fn from_challenge_bytes(bytes: &[u8]) -> Result<Self> {
+        BinaryField128b::deserialize(bytes).context("draw random point from channel")
+    }
+}
+
+const DIGEST_BYTES: usize = 32;
+
+/// Concatenates `digest(counter)` for consecutive counters until `len` bytes are available,
+/// returning the bytes and the next unused counter.
+fn squeeze_bytes(
+    len: usize,
+    mut counter: usize,
+    digest: impl Fn(usize) -> [u8; DIGEST_BYTES],
+) -> Result<(Vec<u8>, usize)> {
+    let mut bytes = Vec::with_capacity(len.next_multiple_of(DIGEST_BYTES));
+    while bytes.len() < len {
+        bytes.extend_from_slice(&digest(counter));
+        counter = counter.checked_add(1).context("channel counter overflow")?;
+    }
+    bytes.truncate(len);
+    Ok((bytes, counter))
+}
+
+/// Ordered list of every observation and challenge of a transcript, enough to re-derive the
+/// challenges with an independent sponge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TranscriptScript {
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl TranscriptScript {
+    /// Re-derives every challenge from the recorded observations and checks it matches the script.
+    pub fn replay(&self) -> Result<()> {
+        let mut sponge = Keccak256::new();
+        let mut counter = 0usize;
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            match entry {
+                TranscriptEntry::Observe(bytes) => sponge.update(bytes),
+                TranscriptEntry::Challenge(challenge) => {
+                    let (bytes, next) =
+                        squeeze_bytes(BinaryField128b::BYTES, counter, |c| digest(&sponge, c))
+                            .context("script counter overflow")?;
+                    let derived = BinaryField128b::from_challenge_bytes(&bytes)
+                        .context("derive challenge from script")?;
+                    ensure!(
+                        derived == *challenge,
+                        "Challenge at script entry {i} does not match its observations"
+                    );
+                    counter = next;
+                }
+                TranscriptEntry::Squeeze(squeezed) => {
+                    let (bytes, next) =
+                        squeeze_bytes(squeezed.len(), counter, |c| digest(&sponge, c))
+                            .context("script counter overflow")?;
+                    ensure!(
+                        bytes == *squeezed,
+                        "Squeezed bytes at script entry {i} do not match their observations"
+                    );
+                    counter = next;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Keccak digest of a sum-check oracle's coefficients, absorbed in their place by a channel from
+/// [`Channel::digesting_oracles`].
+pub fn oracle_digest(coeffs: &[BinaryField128b]) -> Hash {
+    let mut hasher = Keccak256::new();
+    for coeff in coeffs {
+        hasher.update(coeff.val().to_le_bytes());
+    }
+    Hash(hasher.finalize())
+}
+
+fn digest(state: &Keccak256, counter: usize) -> [u8; DIGEST_BYTES] {
+    let mut sponge = state.clone();
+    sponge.update(counter.to_le_bytes());
+    sponge.finalize().into()
+}
 
 /// Fiat–Shamir transcript helper for deriving deterministic challenges.
+#[derive(Clone)]
 pub struct Channel {
     state: Keccak256,
     round_idx: usize,
+    script: Option<TranscriptScript>,
+    /// Absorb each sum-check oracle as its [`oracle_digest`] instead of coefficient by
+    /// coefficient.
+    digest_oracles: bool,
+    /// Every byte absorbed into `state`, which `Keccak256` cannot export, kept so the channel
+    /// can be serialized with [`Channel::state_bytes`] and restored by [`Channel::from_state`].
+    absorbed: Vec<u8>,
+    /// Queries drawn by [`Channel::gen_queries`]; prover and verifier must agree on it.
+    num_queries: usize,
+    /// Sort each [`Channel::gen_queries`] draw; prover and verifier must agree on it.
+    canonical_queries: bool,
 }
 
 impl Channel {
@@ -18,19 +144,132 @@ impl Channel {
         Self {
             state: Keccak256::new(),
             round_idx: 0,
+            script: None,
+            digest_oracles: false,
+            absorbed: Vec::new(),
+            num_queries: DEFAULT_NUM_QUERIES,
+            canonical_queries: false,
         }
     }
 
-    fn absorb_bytes(&mut self, bytes: &[u8]) {
-        self.state.update(bytes);
+    /// Channel drawing [`query_count`]`(security_bits, LOG_RATE)` queries instead of
+    /// [`DEFAULT_NUM_QUERIES`]. Prover and verifier must both use the same `security_bits`.
+    pub fn with_security_bits(security_bits: usize) -> Self {
+        Self {
+            num_queries: query_count(security_bits as f64, LOG_RATE),
+            ..Self::new()
+        }
+    }
+
+    /// This channel with [`Channel::gen_queries`] returning its draw in ascending order, so
+    /// query data laid out in query order is the same however the indices were drawn.
+    ///
+    /// Leaf indices only shift right from round to round, so the order holds in every round.
+    /// Sorting happens after the draw and leaves the transcript untouched, but prover and
+    /// verifier must both use it.
+    pub fn with_canonical_queries(self) -> Self {
+        Self {
+            canonical_queries: true,
+            ..self
+        }
+    }
+
+    /// Number of queries [`Channel::gen_queries`] draws from a large enough domain.
+    pub fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    /// Channel resuming from [`Channel::state_bytes`] and [`Channel::round_idx`] of a paused one:
+    /// it draws exactly the challenges the paused channel would have drawn next.
+    ///
+    /// Only the sponge is restored; recording, the [`Channel::digesting_oracles`] mode and the
+    /// query count start as in [`Channel::new`].
+    pub fn from_state(keccak_state_bytes: &[u8], round_idx: usize) -> Result<Channel> {
+        let mut channel = Channel::new();
+        channel.state.update(keccak_state_bytes);
+        channel.absorbed = keccak_state_bytes.to_vec();
+        channel.round_idx = round_idx;
+        Ok(channel)
+    }
+
+    /// Bytes absorbed so far, to be passed to [`Channel::from_state`].
+    pub fn state_bytes(&self) -> &[u8] {
+        &self.absorbed
+    }
+
+    /// Counter of the next digest to squeeze, to be passed to [`Channel::from_state`].
+    pub fn round_idx(&self) -> usize {
+        self.round_idx
+    }
+
+    /// Channel that additionally records every observation and challenge into a [`TranscriptScript`].
+    pub fn recording() -> Self {
+        Self {
+            script: Some(TranscriptScript::default()),
+            ..Self::new()
+        }
+    }
+
+    /// Channel whose transcript starts by absorbing [`APP_DOMAIN_TAG`] and the length-prefixed
+    /// application `domain`, so that applications proving identical statements under different
+    /// domains produce proofs that do not verify for each other. The tag keeps these transcripts
+    /// apart from a [`Channel::fork`], whose label is absorbed the same way as the length.
+    pub fn for_application(domain: &str) -> Self {
+        let mut channel = Self::new();
+        channel.absorb_bytes(APP_DOMAIN_TAG);
+        channel.absorb_bytes(&(domain.len() as u64).to_le_bytes());
+        channel.absorb_bytes(domain.as_bytes());
+        channel
     }
 
-    fn sample_digest(&self, counter: usize) -> [u8; 32] {
+    /// Channel absorbing sum-check oracles as one digest each. The transcript differs from
+    /// [`Channel::new`]'s, so prover and verifier must both use this mode.
+    pub fn digesting_oracles() -> Self {
+        Self {
+            digest_oracles: true,
+            ..Self::new()
+        }
+    }
+
+    /// Consumes the channel, returning the recorded script if recording was enabled.
+    pub fn into_script(self) -> Option<TranscriptScript> {
+        self.script
+    }
+
+    /// Independent sub-transcript: a copy of this channel's state that has absorbed `label`.
+    /// Forks with distinct labels draw unrelated challenges; this channel is left untouched.
+    pub fn fork(&self, label: u64) -> Channel {
+        let mut fork = self.clone();
+        fork.absorb_bytes(&label.to_le_bytes());
+        fork
+    }
+
+    /// Digest of everything absorbed and squeezed so far, leaving the channel untouched. Two
+    /// channels with equal snapshots draw equal challenges.
+    pub fn snapshot(&self) -> [u8; DIGEST_BYTES] {
         let mut sponge = self.state.clone();
-        sponge.update(counter.to_le_bytes());
+        sponge.update(b"snapshot");
+        sponge.update(self.round_idx.to_le_bytes());
         sponge.finalize().into()
     }
 
+    pub(crate) fn absorb_bytes(&mut self, bytes: &[u8]) {
+        self.state.update(bytes);
+        self.absorbed.extend_from_slice(bytes);
+        if let Some(script) = self.script.as_mut() {
+            script
+                .entries
+                .push(TranscriptEntry::Observe(bytes.to_vec()));
+        }
+    }
+
+    /// Squeezes `len` bytes, advancing the counter once per digest consumed.
+    fn sample_bytes(&mut self, len: usize) -> Result<Vec<u8>> {
+        let (bytes, next) = squeeze_bytes(len, self.round_idx, |c| digest(&self.state, c))?;
+        self.round_idx = next;
+        Ok(bytes)
+    }
+
     pub fn observe_field_elem<F: BinaryField + TowerField>(&mut self, elem: F) -> Result<()> {
         let mut buffer = Vec::new();
         serialize_canonical(elem, &mut buffer).context("serialize field element")?;
@@ -45,24 +284,39 @@ impl Channel {
             .context("serialize field element collection")
     }
 
-    pub fn observe_vector_commitment(&mut self, commitment: &VectorCommitment) {
-        self.absorb_bytes(&commitment.root().0);
+    /// Absorbs a sum-check oracle's coefficients, or their digest in [`Channel::digesting_oracles`] mode.
+    pub fn observe_oracle_coeffs(&mut self, coeffs: &[BinaryField128b]) -> Result<()> {
+        match self.digest_oracles {
+            true => {
+                self.absorb_bytes(&oracle_digest(coeffs).0);
+                Ok(())
+            }
+            false => self.observe_field_elems(coeffs),
+        }
+    }
+
+    pub fn observe_vector_commitment<H: MerkleHasher>(&mut self, commitment: &VectorCommitment<H>) {
+        self.absorb_bytes(commitment.root().as_ref());
         self.absorb_bytes(&commitment.depth().to_le_bytes());
     }
 
-    pub fn observe_fri_commitment(&mut self, commitment: &FriCommitment) {
-        self.observe_vector_commitment(&commitment.vector_commitment);
-        self.absorb_bytes(&commitment.packing_factor.to_le_bytes())
+    /// Draws an element of any [`ChallengeField`], consuming as many digests as its width needs.
+    pub fn get_random_elem<C: ChallengeField>(&mut self) -> Result<C> {
+        let bytes = self.sample_bytes(C::BYTES)?;
+        let elem = C::from_challenge_bytes(&bytes)?;
+        if let Some(script) = self.script.as_mut() {
+            script.entries.push(TranscriptEntry::Squeeze(bytes));
+        }
+        Ok(elem)
     }
 
     pub fn get_random_point(&mut self) -> Result<BinaryField128b> {
-        let digest = self.sample_digest(self.round_idx);
-        self.round_idx = self
-            .round_idx
-            .checked_add(1)
-            .context("channel counter overflow")?;
-
-        BinaryField128b::deserialize(digest.as_slice()).context("draw random point from channel")
+        let bytes = self.sample_bytes(BinaryField128b::BYTES)?;
+        let point = BinaryField128b::from_challenge_bytes(&bytes)?;
+        if let Some(script) = self.script.as_mut() {
+            script.entries.push(TranscriptEntry::Challenge(point));
+        }
+        Ok(point)
     }
 
     pub fn get_random_points(&mut self, n: usize) -> Result<Vec<BinaryField128b>> {
@@ -72,7 +326,8 @@ impl Channel {
             .context("draw random points from channel")
     }
 
-    /// Number of queries hard coded for 96 bits of security and R=4 as per [DP24].
+    /// Draws [`Channel::num_queries`] indices into a domain of `2^log_max_len`, or every index
+    /// if the domain is smaller.
     pub fn gen_queries(&mut self, log_max_len: usize) -> Result<Vec<usize>> {
         let shift = u32::try_from(log_max_len).context("log_max_len does not fit in u32")?;
         let domain_size = 1usize
@@ -83,16 +338,234 @@ impl Channel {
             bail!("domain size must be positive");
         }
 
-        if domain_size < 144 {
+        if domain_size < self.num_queries {
             // Domain is small; query every element once.
             return Ok((0..domain_size).collect());
         }
 
         let bit_mask = u128::try_from(domain_size - 1).context("domain size exceeds 2^128")?;
-        let random_elems = self.get_random_points(144)?;
-        Ok(random_elems
+        let random_elems = self.get_random_points(self.num_queries)?;
+        let mut queries: Vec<usize> = random_elems
             .iter()
             .map(|elem| (elem.val() & bit_mask) as usize)
-            .collect())
+            .collect();
+        if self.canonical_queries {
+            queries.sort_unstable();
+        }
+        Ok(queries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stand-in for a challenge field wider than one digest.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Wide(Vec<u8>);
+
+    impl ChallengeField for Wide {
+        const BYTES: usize = 48;
+
+        fn from_challenge_bytes(bytes: &[u8]) -> Result<Self> {
+            Ok(Wide(bytes.to_vec()))
+        }
+    }
+
+    fn seeded(channel: &mut Channel) {
+        channel
+            .observe_field_elem(BinaryField128b::new(42))
+            .unwrap();
+    }
+
+    #[test]
+    fn challenge_width_test() {
+        let mut channel = Channel::new();
+        seeded(&mut channel);
+        let mut sponge = Keccak256::new();
+        let mut buffer = Vec::new();
+        serialize_canonical(BinaryField128b::new(42), &mut buffer).unwrap();
+        sponge.update(&buffer);
+
+        // 128-bit challenges still come from the first 16 bytes of one digest per counter.
+        for counter in 0..3usize {
+            let mut squeeze = sponge.clone();
+            squeeze.update(counter.to_le_bytes());
+            let digest: [u8; 32] = squeeze.finalize().into();
+            let expected = BinaryField128b::deserialize(digest.as_slice()).unwrap();
+            assert_eq!(channel.get_random_point().unwrap(), expected);
+        }
+
+        let mut generic = Channel::new();
+        seeded(&mut generic);
+        let mut points = Channel::new();
+        seeded(&mut points);
+        assert_eq!(
+            generic.get_random_elem::<BinaryField128b>().unwrap(),
+            points.get_random_point().unwrap()
+        );
+
+        // A 48-byte element spans two digests, so the next point uses the counter after both.
+        let mut wide = Channel::new();
+        seeded(&mut wide);
+        let elem = wide.get_random_elem::<Wide>().unwrap();
+        assert_eq!(elem.0.len(), Wide::BYTES);
+        let after_wide = wide.get_random_point().unwrap();
+
+        let mut reference = Channel::new();
+        seeded(&mut reference);
+        let _ = reference.get_random_points(2).unwrap();
+        assert_eq!(after_wide, reference.get_random_point().unwrap());
+
+        let mut recording = Channel::recording();
+        seeded(&mut recording);
+        recording.get_random_elem::<Wide>().unwrap();
+        recording.get_random_point().unwrap();
+        let script = recording.into_script().unwrap();
+        script.replay().unwrap();
+
+        let mut tampered = script.clone();
+        tampered.entries[1] = TranscriptEntry::Squeeze(vec![0; Wide::BYTES]);
+        assert!(tampered.replay().is_err());
+    }
+
+    #[test]
+    fn order_sensitivity_test() {
+        let (a, b) = (BinaryField128b::new(1), BinaryField128b::new(2));
+
+        let mut forward = Channel::new();
+        forward.observe_field_elems(&[a, b]).unwrap();
+        let mut backward = Channel::new();
+        backward.observe_field_elems(&[b, a]).unwrap();
+        assert_ne!(forward.snapshot(), backward.snapshot());
+
+        // A batch absorbs exactly like its elements one by one, in order.
+        let mut one_by_one = Channel::new();
+        one_by_one.observe_field_elem(a).unwrap();
+        one_by_one.observe_field_elem(b).unwrap();
+        assert_eq!(forward.snapshot(), one_by_one.snapshot());
+
+        // Snapshots do not disturb the transcript.
+        let snapshot = forward.snapshot();
+        assert_eq!(forward.snapshot(), snapshot);
+        assert_eq!(
+            forward.get_random_point().unwrap(),
+            one_by_one.get_random_point().unwrap()
+        );
+        assert_ne!(
+            forward.get_random_point().unwrap(),
+            backward.get_random_points(2).unwrap()[1]
+        );
+        assert_ne!(forward.snapshot(), snapshot);
+
+        // Duplicates are absorbed, not deduplicated, and queries keep their drawn order.
+        let mut repeated = Channel::new();
+        repeated.observe_field_elems(&[a, a]).unwrap();
+        let mut single = Channel::new();
+        single.observe_field_elems(&[a]).unwrap();
+        assert_ne!(repeated.snapshot(), single.snapshot());
+
+        let mut queries = Channel::new();
+        seeded(&mut queries);
+        let mut points = Channel::new();
+        seeded(&mut points);
+        let expected: Vec<usize> = points
+            .get_random_points(144)
+            .unwrap()
+            .iter()
+            .map(|elem| (elem.val() & 0xfff) as usize)
+            .collect();
+        assert_eq!(queries.gen_queries(12).unwrap(), expected);
+    }
+
+    #[test]
+    fn from_state_test() {
+        let mut channel = Channel::new();
+        seeded(&mut channel);
+        channel
+            .observe_field_elems(&[BinaryField128b::new(7), BinaryField128b::new(9)])
+            .unwrap();
+        channel.get_random_points(3).unwrap();
+
+        let mut restored = Channel::from_state(channel.state_bytes(), channel.round_idx()).unwrap();
+        assert_eq!(restored.snapshot(), channel.snapshot());
+        assert_eq!(
+            restored.get_random_points(4).unwrap(),
+            channel.get_random_points(4).unwrap()
+        );
+
+        restored
+            .observe_field_elem(BinaryField128b::new(11))
+            .unwrap();
+        channel
+            .observe_field_elem(BinaryField128b::new(11))
+            .unwrap();
+        assert_eq!(
+            restored.gen_queries(12).unwrap(),
+            channel.gen_queries(12).unwrap()
+        );
+
+        let mut shifted =
+            Channel::from_state(channel.state_bytes(), channel.round_idx() + 1).unwrap();
+        assert_ne!(
+            shifted.get_random_point().unwrap(),
+            channel.get_random_point().unwrap()
+        );
+    }
+
+    #[test]
+    fn security_bits_test() {
+        assert_eq!(Channel::new().num_queries(), DEFAULT_NUM_QUERIES);
+
+        for bits in [80, 128] {
+            let mut channel = Channel::with_security_bits(bits);
+            let expected = query_count(bits as f64, LOG_RATE);
+            assert_eq!(channel.num_queries(), expected);
+            assert_eq!(channel.gen_queries(20).unwrap().len(), expected);
+            assert_eq!(channel.fork(0).num_queries(), expected);
+            // Small domains are still queried exhaustively.
+            assert_eq!(channel.gen_queries(6).unwrap(), (0..64).collect::<Vec<_>>());
+        }
+        assert!(query_count(80.0, LOG_RATE) < query_count(128.0, LOG_RATE));
+    }
+
+    #[test]
+    fn canonical_queries_test() {
+        let mut plain = Channel::new();
+        let mut canonical = Channel::new().with_canonical_queries();
+        let mut drawn = plain.gen_queries(20).unwrap();
+        let sorted = canonical.gen_queries(20).unwrap();
+        assert!(!drawn.is_sorted());
+        drawn.sort_unstable();
+        assert_eq!(sorted, drawn);
+
+        // Sorting leaves the transcript untouched.
+        assert_eq!(
+            plain.get_random_point().unwrap(),
+            canonical.get_random_point().unwrap()
+        );
+        assert!(canonical.fork(0).canonical_queries);
+        let mut fewer = Channel::with_security_bits(80).with_canonical_queries();
+        assert!(fewer.gen_queries(20).unwrap().is_sorted());
+    }
+
+    #[test]
+    fn application_domain_test() {
+        let challenge = |mut channel: Channel| channel.get_random_point().unwrap();
+        let a = challenge(Channel::for_application("A"));
+        assert_eq!(a, challenge(Channel::for_application("A")));
+        assert_ne!(a, challenge(Channel::for_application("B")));
+        assert_ne!(a, challenge(Channel::new()));
+        // The length prefix keeps the empty domain apart from no domain.
+        assert_ne!(
+            challenge(Channel::for_application("")),
+            challenge(Channel::new())
+        );
+        // The tag keeps the empty domain apart from a fork at label 0, whose absorbed bytes
+        // would otherwise equal the zero length prefix.
+        assert_ne!(
+            challenge(Channel::for_application("")),
+            challenge(Channel::new().fork(0))
+        );
     }
 }
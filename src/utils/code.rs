@@ -1,53 +1,97 @@
-use binius_field::{BinaryField, BinaryField128b, ExtensionField, PackedExtension, TowerField};
+use anyhow::ensure;
+use binius_field::{
+    BinaryField, BinaryField1b, BinaryField128b, ExtensionField, Field, PackedExtension, TowerField,
+};
 use binius_ntt::{AdditiveNTT, MultithreadedNTT};
 use rayon::{
-    iter::{IndexedParallelIterator, ParallelIterator},
+    iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator},
     slice::ParallelSlice,
 };
+use sha3::{Digest, Keccak256};
+
+use super::{
+    merkle::Hash,
+    parallel::{min_grain, parallelism_available},
+};
+use crate::Result;
 
 pub const RATE: usize = 4;
 pub const LOG_RATE: usize = 2;
+
+/// Fewest queries giving `soundness_bits` of query soundness for a rate `2^-log_rate` code, by
+/// the bound the verifier's `SoundnessReport` applies.
+pub fn query_count(soundness_bits: f64, log_rate: usize) -> usize {
+    let rate = (-(log_rate as f64)).exp2();
+    let bits_per_query = -((1.0 + rate) / 2.0).log2();
+    (soundness_bits / bits_per_query).ceil() as usize
+}
 //Struct containing the Reed-Solomon encoding of a message of packed elements. We assume the elements of the message contain packed base field elements.
 #[derive(Clone, Debug, Default)]
 pub struct Code<F: BinaryField> {
     pub encoding: Vec<F>,
+    /// Block holding the coset-0 transform: block `j` of the codeword evaluates coset `j ^ coset_offset`.
+    pub coset_offset: usize,
 }
 
 impl Code<BinaryField128b> {
-    fn repack_message<F>(message: &[F]) -> Vec<BinaryField128b>
-    where
-        BinaryField128b: ExtensionField<F>,
-        F: BinaryField + TowerField,
-    {
-        message
-            .par_chunks(<BinaryField128b as ExtensionField<F>>::DEGREE)
-            .map(|base_elems| {
-                BinaryField128b::from_bases(base_elems).expect("failed to repack base elements")
-            })
-            .collect()
-    }
-
+    /// Encodes straight into the codeword buffer: the message is repacked into block 0 and copied
+    /// into the other blocks, then every block is transformed in place, so no per-block copy of
+    /// the message is allocated.
     fn encode_with_transform<F, N, T>(
         message: &[F],
         ntt: &MultithreadedNTT<N>,
+        coset_offset: usize,
         mut transform: T,
     ) -> Code<BinaryField128b>
     where
         BinaryField128b: ExtensionField<F>,
         F: BinaryField + TowerField,
         N: BinaryField,
-        T: FnMut(&MultithreadedNTT<N>, &mut Vec<BinaryField128b>, u32),
+        T: FnMut(&MultithreadedNTT<N>, &mut [BinaryField128b], u32),
     {
-        let repacked_message = Self::repack_message(message);
-        let mut encoding = Vec::with_capacity(repacked_message.len() * RATE);
+        assert!(
+            coset_offset < RATE,
+            "coset offset {coset_offset} must be below RATE"
+        );
 
-        for round in 0..RATE as u32 {
-            let mut temp = repacked_message.clone();
-            transform(ntt, &mut temp, round);
-            encoding.append(&mut temp);
+        let degree = <BinaryField128b as ExtensionField<F>>::DEGREE;
+        let block_len = message.len().div_ceil(degree);
+        let mut encoding = vec![BinaryField128b::ZERO; block_len * RATE];
+        if block_len == 0 {
+            return Code {
+                encoding,
+                coset_offset,
+            };
         }
 
-        Code { encoding }
+        let (first, rest) = encoding.split_at_mut(block_len);
+        let repack = |(elem, base_elems): (&mut BinaryField128b, &[F])| {
+            *elem = BinaryField128b::from_bases(base_elems).expect("failed to repack base elements")
+        };
+        if parallelism_available() {
+            first
+                .par_iter_mut()
+                .zip(message.par_chunks(degree))
+                .with_min_len(min_grain())
+                .for_each(repack);
+        } else {
+            first
+                .iter_mut()
+                .zip(message.chunks(degree))
+                .for_each(repack);
+        }
+        for block in rest.chunks_exact_mut(block_len) {
+            block.copy_from_slice(first);
+        }
+
+        for (block, symbols) in encoding.chunks_exact_mut(block_len).enumerate() {
+            transform(ntt, symbols, (block ^ coset_offset) as u32);
+        }
+
+        Code {
+            encoding,
+            coset_offset,
+        }
     }
 
     pub fn new<F>(message: &[F], ntt: &MultithreadedNTT<BinaryField128b>) -> Code<BinaryField128b>
@@ -55,7 +99,20 @@ impl Code<BinaryField128b> {
         BinaryField128b: ExtensionField<F>,
         F: BinaryField + TowerField,
     {
-        Self::encode_with_transform(message, ntt, |ntt, temp, round| {
+        Self::new_with_offset(message, ntt, 0)
+    }
+
+    /// [`Code::new`] with the coset-0 block placed at block `coset_offset`.
+    pub fn new_with_offset<F>(
+        message: &[F],
+        ntt: &MultithreadedNTT<BinaryField128b>,
+        coset_offset: usize,
+    ) -> Code<BinaryField128b>
+    where
+        BinaryField128b: ExtensionField<F>,
+        F: BinaryField + TowerField,
+    {
+        Self::encode_with_transform(message, ntt, coset_offset, |ntt, temp, round| {
             ntt.forward_transform(temp, round, 0)
                 .expect("NTT forward transform failed")
         })
@@ -67,7 +124,21 @@ impl Code<BinaryField128b> {
         F: BinaryField + TowerField + ExtensionField<P>,
         P: BinaryField,
     {
-        Self::encode_with_transform(message, ntt, |ntt, temp, round| {
+        Self::new_ext_with_offset(message, ntt, 0)
+    }
+
+    /// [`Code::new_ext`] with the coset-0 block placed at block `coset_offset`.
+    pub fn new_ext_with_offset<F, P>(
+        message: &[F],
+        ntt: &MultithreadedNTT<P>,
+        coset_offset: usize,
+    ) -> Code<BinaryField128b>
+    where
+        BinaryField128b: ExtensionField<F> + ExtensionField<P> + PackedExtension<P>,
+        F: BinaryField + TowerField + ExtensionField<P>,
+        P: BinaryField,
+    {
+        Self::encode_with_transform(message, ntt, coset_offset, |ntt, temp, round| {
             ntt.forward_transform_ext::<BinaryField128b>(temp, round)
                 .expect("extended NTT forward transform failed");
         })
@@ -83,34 +154,200 @@ impl Code<BinaryField128b> {
         BinaryField128b: ExtensionField<P>,
         P: BinaryField,
     {
-        let encoding: Vec<BinaryField128b> = self
-            .encoding
-            .par_chunks_exact(2)
-            .enumerate()
-            .map(|(i, pair)| fold(r, round, i, pair[0], pair[1], ntt))
-            .collect();
+        let coset_mask = coset_index_mask(self.encoding.len() >> 1, self.coset_offset);
+        let fold_pair = |(i, pair): (usize, &[BinaryField128b])| {
+            fold(r, round, i ^ coset_mask, pair[0], pair[1], ntt)
+        };
+        let encoding: Vec<BinaryField128b> = if parallelism_available() {
+            self.encoding
+                .par_chunks_exact(2)
+                .enumerate()
+                .map(fold_pair)
+                .collect()
+        } else {
+            self.encoding
+                .chunks_exact(2)
+                .enumerate()
+                .map(fold_pair)
+                .collect()
+        };
 
-        Code { encoding }
+        Code {
+            encoding,
+            coset_offset: self.coset_offset,
+        }
     }
 
     pub fn idx(&self, idx: usize) -> BinaryField128b {
         self.encoding[idx]
     }
+
+    /// Where `encoding[i]` lies in the evaluation domain: the codeword is `RATE` blocks of one
+    /// message length each, block `j` holding the transform over coset `j ^ coset_offset`, so
+    /// `i` is point `i % block_len` of coset `(i / block_len) ^ coset_offset`.
+    ///
+    /// The additive NTT reads the message as novel-basis coefficients, so no coset, the
+    /// coset-0 one included, repeats message symbols verbatim.
+    pub fn index_layout(&self, i: usize) -> CodewordPosition {
+        assert!(
+            i < self.encoding.len(),
+            "codeword index {i} out of bounds for {} symbols",
+            self.encoding.len()
+        );
+        let block_len = self.encoding.len() / RATE;
+        CodewordPosition {
+            coset: (i / block_len) ^ self.coset_offset,
+            within: i % block_len,
+        }
+    }
+}
+
+/// Position of a codeword symbol in the evaluation domain, as returned by [`Code::index_layout`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CodewordPosition {
+    /// Coset of the evaluation domain, in `0..RATE`.
+    pub coset: usize,
+    /// Point within the coset, in `0..block_len`.
+    pub within: usize,
 }
 
+/// XOR mask taking a stored pair index to its domain index when the `RATE` coset blocks of a
+/// `num_pairs`-pair codeword are permuted by `coset_offset` (coset bits are the top index bits).
 #[inline(always)]
-pub fn fold<P>(
+pub fn coset_index_mask(num_pairs: usize, coset_offset: usize) -> usize {
+    coset_offset * (num_pairs / RATE)
+}
+
+/// Source of the folding twiddles, so the verifier can run over any NTT subfield, or without an
+/// NTT at all, behind one non-generic `&dyn FoldTwiddles`.
+pub trait FoldTwiddles: Sync {
+    fn log_domain_size(&self) -> usize;
+
+    /// The twiddle of pair `idx` in folding round `round`, lifted into `BinaryField128b`.
+    fn get_subspace_eval(&self, round: usize, idx: usize) -> BinaryField128b;
+}
+
+impl<P> FoldTwiddles for MultithreadedNTT<P>
+where
+    BinaryField128b: ExtensionField<P>,
+    P: BinaryField,
+{
+    fn log_domain_size(&self) -> usize {
+        AdditiveNTT::log_domain_size(self)
+    }
+
+    fn get_subspace_eval(&self, round: usize, idx: usize) -> BinaryField128b {
+        BinaryField128b::from(AdditiveNTT::get_subspace_eval(self, round, idx))
+    }
+}
+
+impl<P> FoldTwiddles for SubspaceEvalOracle<P>
+where
+    BinaryField128b: ExtensionField<P>,
+    P: BinaryField,
+{
+    fn log_domain_size(&self) -> usize {
+        self.evals.len()
+    }
+
+    fn get_subspace_eval(&self, round: usize, idx: usize) -> BinaryField128b {
+        BinaryField128b::from(SubspaceEvalOracle::get_subspace_eval(self, round, idx))
+    }
+}
+
+/// Digest of the twiddles [`fold`] reads over `rounds` folds of a rate-`RATE` codeword, so prover
+/// and verifier can detect mismatched NTT parameters up front.
+///
+/// Subspace evaluations are F_2-linear in the index, so the images of the index basis vectors
+/// determine every twiddle of a round. The domain size is left out: an NTT larger than the
+/// codeword folds with the same twiddles, so its proofs verify against a right-sized one.
+/// Every canonical-basis NTT of at least `rounds + LOG_RATE` points therefore shares one
+/// fingerprint, and the check only catches a twiddle source over another basis.
+pub fn twiddle_fingerprint<T: FoldTwiddles + ?Sized>(ntt: &T, rounds: usize) -> Hash {
+    let mut hasher = Keccak256::new();
+
+    for round in 0..rounds {
+        // Round `round` folds 2^(rounds + LOG_RATE - round - 1) symbol pairs.
+        for bit in 0..rounds + LOG_RATE - round - 1 {
+            let twiddle = ntt.get_subspace_eval(round, 1 << bit);
+            hasher.update(twiddle.val().to_le_bytes());
+        }
+    }
+
+    Hash(hasher.finalize())
+}
+
+/// The subspace evaluations [`fold`] reads, computed on their own for a domain of
+/// `2^log_domain` points over the canonical basis, as `SingleThreadedNTT::new` builds it but
+/// without the rest of the NTT's precomputation.
+#[derive(Clone, Debug)]
+pub struct SubspaceEvalOracle<P: BinaryField> {
+    /// `evals[round][k]`: the normalised subspace polynomial of `round` at basis element
+    /// `round + 1 + k`. Evaluations at other points follow by F_2-linearity.
+    evals: Vec<Vec<P>>,
+}
+
+impl<P: BinaryField> SubspaceEvalOracle<P> {
+    pub fn new(log_domain: usize) -> Result<Self> {
+        ensure!(
+            (1..=P::N_BITS).contains(&log_domain),
+            "log domain size {log_domain} must be between 1 and the field's {} bits",
+            P::N_BITS
+        );
+
+        let basis = (0..log_domain).map(|k| {
+            let mut bits = vec![BinaryField1b::ZERO; P::N_BITS];
+            bits[k] = BinaryField1b::ONE;
+            P::from_bases(&bits).expect("unit vector has one base element per bit")
+        });
+
+        // s_0(x) = x; s_{i+1}(x) = s_i(x)^2 + s_i(β_i) s_i(x), each normalised by s_i(β_i).
+        let mut unnormalised: Vec<P> = basis.collect();
+        let mut evals = Vec::with_capacity(log_domain);
+        for _ in 0..log_domain {
+            let norm = unnormalised[0];
+            let inverse = norm
+                .invert()
+                .expect("basis elements are linearly independent");
+            evals.push(
+                unnormalised[1..]
+                    .iter()
+                    .map(|eval| *eval * inverse)
+                    .collect(),
+            );
+            unnormalised = unnormalised[1..]
+                .iter()
+                .map(|eval| eval.square() + norm * *eval)
+                .collect();
+        }
+
+        Ok(SubspaceEvalOracle { evals })
+    }
+
+    pub fn log_domain_size(&self) -> usize {
+        self.evals.len()
+    }
+
+    /// The twiddle `MultithreadedNTT::get_subspace_eval(round, idx)` returns for the same domain.
+    pub fn get_subspace_eval(&self, round: usize, idx: usize) -> P {
+        self.evals[round]
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| (idx >> bit) & 1 == 1)
+            .map(|(_, eval)| *eval)
+            .sum()
+    }
+}
+
+#[inline(always)]
+pub fn fold<T: FoldTwiddles + ?Sized>(
     r: BinaryField128b,
     round: usize,
     idx: usize,
     val0: BinaryField128b,
     val1: BinaryField128b,
-    ntt: &MultithreadedNTT<P>,
-) -> BinaryField128b
-where
-    BinaryField128b: ExtensionField<P>,
-    P: BinaryField,
-{
+    ntt: &T,
+) -> BinaryField128b {
     //twiddle for inverse ntt component of the fold i.e the twiddle for the butterfly unit
     //at the index if we were to apply the inverse ntt
 
@@ -124,13 +361,102 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::utils::mle::LagrangeBases;
+    use crate::utils::{
+        merkle::{Keccak256Hasher, compute_leaf_hashes},
+        mle::{LagrangeBases, PackedMLE},
+        parallel::with_min_grain,
+    };
 
     use super::*;
-    use binius_field::Field;
+    use binius_field::{BinaryField32b, BinaryField64b};
     use binius_ntt::SingleThreadedNTT;
     use rand::thread_rng;
     use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator};
+    /// The clone-per-block encoding [`Code::new_ext`] used before encoding in place.
+    fn encode_with_clones(
+        message: &[BinaryField64b],
+        ntt: &MultithreadedNTT<BinaryField32b>,
+        coset_offset: usize,
+    ) -> Vec<BinaryField128b> {
+        let repacked_message: Vec<BinaryField128b> = message
+            .chunks(2)
+            .map(|base_elems| BinaryField128b::from_bases(base_elems).unwrap())
+            .collect();
+        let mut encoding = Vec::with_capacity(repacked_message.len() * RATE);
+        for block in 0..RATE {
+            let mut temp = repacked_message.clone();
+            ntt.forward_transform_ext::<BinaryField128b>(&mut temp, (block ^ coset_offset) as u32)
+                .unwrap();
+            encoding.append(&mut temp);
+        }
+        encoding
+    }
+
+    #[test]
+    fn test_encode_in_place() {
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(10 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        for l in [2, 4, 10] {
+            let message: Vec<BinaryField64b> = (0..1 << l)
+                .map(|_| BinaryField64b::random(thread_rng()))
+                .collect();
+            for coset_offset in 0..RATE {
+                let code = Code::new_ext_with_offset(&message, &ntt, coset_offset);
+                assert_eq!(
+                    code.encoding,
+                    encode_with_clones(&message, &ntt, coset_offset),
+                    "message of 2^{l} elements, coset offset {coset_offset}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_grain_independence() {
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(10 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let message: Vec<BinaryField64b> = (0..1 << 10)
+            .map(|_| BinaryField64b::random(thread_rng()))
+            .collect();
+        let mle = PackedMLE::new(message.clone(), true);
+
+        let reference = Code::new_ext(&message, &ntt).encoding;
+        let repacked = mle.clone().repack_for_fri().coeffs;
+        let leaves = compute_leaf_hashes::<Keccak256Hasher>(&reference);
+        for grain in [1, 3, 64, 1 << 12] {
+            with_min_grain(grain, || {
+                assert_eq!(Code::new_ext(&message, &ntt).encoding, reference);
+                assert_eq!(mle.clone().repack_for_fri().coeffs, repacked);
+                assert_eq!(compute_leaf_hashes::<Keccak256Hasher>(&reference), leaves);
+            });
+        }
+    }
+
+    #[test]
+    fn test_index_layout() {
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(6 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let message: Vec<BinaryField64b> = (0..1 << 6)
+            .map(|_| BinaryField64b::random(thread_rng()))
+            .collect();
+        for coset_offset in 0..RATE {
+            let code = Code::new_ext_with_offset(&message, &ntt, coset_offset);
+            let block_len = code.encoding.len() / RATE;
+            let coset_mask = coset_index_mask(code.encoding.len() >> 1, coset_offset);
+            for i in 0..code.encoding.len() {
+                let position = code.index_layout(i);
+                assert!(position.coset < RATE && position.within < block_len);
+                // `fold_code` folds stored pair `i >> 1` at domain pair `(i >> 1) ^ coset_mask`.
+                let domain_index = position.coset * block_len + position.within;
+                assert_eq!(domain_index >> 1, (i >> 1) ^ coset_mask);
+                assert_eq!(domain_index & 1, i & 1);
+            }
+        }
+    }
+
     #[test]
     fn test_fold() {
         let l = 11;
@@ -169,6 +495,110 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fold_with_offset() {
+        let l = 9;
+        let poly: Vec<BinaryField128b> = (0..1 << l)
+            .into_par_iter()
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+
+        let ntt = SingleThreadedNTT::<BinaryField128b>::new(l + 2)
+            .unwrap()
+            .multithreaded();
+
+        let code = Code::new(&poly, &ntt);
+        let block_len = code.encoding.len() / RATE;
+
+        let r: Vec<BinaryField128b> = (0..l)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        let poly_eval: BinaryField128b = poly
+            .par_iter()
+            .zip(LagrangeBases::gen_from_point(&r).vals)
+            .map(|(coeff, eq_val)| *coeff * eq_val)
+            .sum();
+
+        for offset in 0..RATE {
+            let shifted = Code::new_with_offset(&poly, &ntt, offset);
+            assert_eq!(
+                shifted.encoding[offset * block_len..(offset + 1) * block_len],
+                code.encoding[..block_len]
+            );
+
+            let mut folded_code = shifted;
+            for (round, r) in r.iter().enumerate() {
+                folded_code = folded_code.fold_code(*r, round, &ntt);
+            }
+            assert!(folded_code.encoding.iter().all(|val| *val == poly_eval));
+        }
+    }
+
+    #[test]
+    fn test_prefix_ntt() {
+        let l = 9;
+        let poly: Vec<BinaryField128b> = (0..1 << l)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+
+        let ntt = SingleThreadedNTT::<BinaryField128b>::new(l + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let larger_ntt = SingleThreadedNTT::<BinaryField128b>::new(l + LOG_RATE + 3)
+            .unwrap()
+            .multithreaded();
+
+        let code = Code::new_ext(&poly, &ntt);
+        let prefix_code = Code::new_ext(&poly, &larger_ntt);
+        assert_eq!(code.encoding, prefix_code.encoding);
+
+        let r = BinaryField128b::random(thread_rng());
+        assert_eq!(
+            code.fold_code(r, 0, &ntt).encoding,
+            prefix_code.fold_code(r, 0, &larger_ntt).encoding
+        );
+    }
+
+    #[test]
+    fn test_subspace_eval_oracle() {
+        let log_domain = 12;
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(log_domain).unwrap();
+        let oracle = SubspaceEvalOracle::<BinaryField32b>::new(log_domain).unwrap();
+        assert_eq!(oracle.log_domain_size(), log_domain);
+
+        for round in 0..log_domain {
+            for idx in 0..1 << (log_domain - round - 1) {
+                assert_eq!(
+                    oracle.get_subspace_eval(round, idx),
+                    ntt.get_subspace_eval(round, idx),
+                    "round {round}, index {idx}"
+                );
+            }
+        }
+
+        assert!(SubspaceEvalOracle::<BinaryField32b>::new(0).is_err());
+        assert!(SubspaceEvalOracle::<BinaryField32b>::new(33).is_err());
+    }
+
+    #[test]
+    fn test_twiddle_fingerprint() {
+        let rounds = 9;
+        let ntt = SingleThreadedNTT::<BinaryField128b>::new(rounds + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let same_ntt = SingleThreadedNTT::<BinaryField128b>::new(rounds + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let larger_ntt = SingleThreadedNTT::<BinaryField128b>::new(rounds + LOG_RATE + 1)
+            .unwrap()
+            .multithreaded();
+
+        let fingerprint = twiddle_fingerprint(&ntt, rounds);
+        assert_eq!(fingerprint, twiddle_fingerprint(&same_ntt, rounds));
+        assert_eq!(fingerprint, twiddle_fingerprint(&larger_ntt, rounds));
+        assert_ne!(fingerprint, twiddle_fingerprint(&ntt, rounds - 1));
+    }
+
     #[test]
     fn test_ntt() {
         let l = 11;
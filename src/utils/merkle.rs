@@ -1,46 +1,293 @@
-use anyhow::ensure;
+use std::collections::BTreeMap;
+
+use anyhow::{Context, ensure};
 use binius_field::{BinaryField128b, ExtensionField, Field};
-use rayon::{iter::ParallelIterator, slice::ParallelSlice};
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::ParallelSlice,
+};
 use sha3::{
-    Digest, Keccak256,
-    digest::{consts::U32, generic_array::GenericArray},
+    Digest, Keccak256, Sha3_512,
+    digest::{
+        consts::{U32, U64},
+        generic_array::GenericArray,
+    },
 };
 use tracing::instrument;
 
+use super::parallel::{min_grain, parallelism_available};
+
 /// Wrapper struct for Keccak-256 digests.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Hash(pub GenericArray<u8, U32>);
 
 /// Merkle tree backed by contiguous layers (index 0 = root, last = leaves).
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct MerkleTree {
-    pub data: Vec<Vec<Hash>>,
+pub struct MerkleTree<H: MerkleHasher = Keccak256Hasher> {
+    pub data: Vec<Vec<H::Digest>>,
+}
+
+/// Hash function backing a [`MerkleTree`]. [`Keccak256Hasher`] is the default; [`Blake3Hasher`]
+/// commits faster, and [`Sha3_512Hasher`] trades 64-byte nodes for 256-bit second-preimage resistance.
+/// The [`Channel`](crate::utils::channel::Channel) keeps Keccak for Fiat-Shamir either way.
+pub trait MerkleHasher {
+    type Digest: Clone
+        + Copy
+        + std::fmt::Debug
+        + PartialEq
+        + Eq
+        + std::hash::Hash
+        + Send
+        + Sync
+        + AsRef<[u8]>;
+
+    /// Length of a digest's [`AsRef`] bytes, as serialized in proofs.
+    const DIGEST_BYTES: usize;
+
+    /// Inverse of a digest's [`AsRef`] bytes, rejecting any other length.
+    fn digest_from_bytes(bytes: &[u8]) -> anyhow::Result<Self::Digest>;
+
+    /// Hash a run of symbols into a leaf.
+    fn hash_leaf(symbols: &[BinaryField128b]) -> Self::Digest;
+
+    /// Hash the concatenation `left || right` into their parent.
+    fn hash_concatenation(left: &Self::Digest, right: &Self::Digest) -> Self::Digest;
+}
+
+/// Wrapper struct for Blake3 digests, kept apart from [`Hash`] so the two cannot be mixed up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Blake3Hash(pub [u8; 32]);
+
+/// Wrapper struct for SHA3-512 digests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Hash512(pub GenericArray<u8, U64>);
+
+impl AsRef<[u8]> for Hash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Blake3Hash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Hash512 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+fn check_digest_len<H: MerkleHasher>(bytes: &[u8]) -> anyhow::Result<()> {
+    ensure!(
+        bytes.len() == H::DIGEST_BYTES,
+        "Digest must be {} bytes, got {}",
+        H::DIGEST_BYTES,
+        bytes.len()
+    );
+    Ok(())
+}
+
+/// Keccak-256, the hasher behind [`merklize`] and [`compute_leaf_hashes`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Keccak256Hasher;
+
+impl MerkleHasher for Keccak256Hasher {
+    type Digest = Hash;
+
+    const DIGEST_BYTES: usize = 32;
+
+    fn digest_from_bytes(bytes: &[u8]) -> anyhow::Result<Hash> {
+        check_digest_len::<Self>(bytes)?;
+        Ok(Hash(GenericArray::clone_from_slice(bytes)))
+    }
+
+    fn hash_leaf(symbols: &[BinaryField128b]) -> Hash {
+        let mut hasher = Keccak256::new();
+        for symbol in symbols {
+            hasher.update(symbol.val().to_le_bytes());
+        }
+        Hash(hasher.finalize())
+    }
+
+    fn hash_concatenation(left: &Hash, right: &Hash) -> Hash {
+        hash_concatenation(left, right)
+    }
+}
+
+/// Blake3 with 32-byte [`Blake3Hash`] nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Blake3Hasher;
+
+impl MerkleHasher for Blake3Hasher {
+    type Digest = Blake3Hash;
+
+    const DIGEST_BYTES: usize = 32;
+
+    fn digest_from_bytes(bytes: &[u8]) -> anyhow::Result<Blake3Hash> {
+        check_digest_len::<Self>(bytes)?;
+        Ok(Blake3Hash(bytes.try_into()?))
+    }
+
+    fn hash_leaf(symbols: &[BinaryField128b]) -> Blake3Hash {
+        let mut hasher = blake3::Hasher::new();
+        for symbol in symbols {
+            hasher.update(&symbol.val().to_le_bytes());
+        }
+        Blake3Hash(*hasher.finalize().as_bytes())
+    }
+
+    fn hash_concatenation(left: &Blake3Hash, right: &Blake3Hash) -> Blake3Hash {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&left.0);
+        hasher.update(&right.0);
+        Blake3Hash(*hasher.finalize().as_bytes())
+    }
+}
+
+/// SHA3-512 with 64-byte nodes, so parents hash 128-byte inputs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Sha3_512Hasher;
+
+impl MerkleHasher for Sha3_512Hasher {
+    type Digest = Hash512;
+
+    const DIGEST_BYTES: usize = 64;
+
+    fn digest_from_bytes(bytes: &[u8]) -> anyhow::Result<Hash512> {
+        check_digest_len::<Self>(bytes)?;
+        Ok(Hash512(GenericArray::clone_from_slice(bytes)))
+    }
+
+    fn hash_leaf(symbols: &[BinaryField128b]) -> Hash512 {
+        let mut hasher = Sha3_512::new();
+        for symbol in symbols {
+            hasher.update(symbol.val().to_le_bytes());
+        }
+        Hash512(hasher.finalize())
+    }
+
+    fn hash_concatenation(left: &Hash512, right: &Hash512) -> Hash512 {
+        let mut val = [0; 128];
+        val[..64].copy_from_slice(&left.0);
+        val[64..].copy_from_slice(&right.0);
+        Hash512(Sha3_512::digest(val))
+    }
 }
 
 /// Commitment that stores the Merkle root and the number of hashing rounds (tree depth).
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct VectorCommitment {
-    pub root: Hash,
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VectorCommitment<H: MerkleHasher = Keccak256Hasher> {
+    pub root: H::Digest,
     pub depth: usize,
 }
 
-impl VectorCommitment {
-    pub fn root(&self) -> Hash {
-        self.root.clone()
+impl<H: MerkleHasher> VectorCommitment<H> {
+    pub fn root(&self) -> H::Digest {
+        self.root
     }
     pub fn depth(&self) -> usize {
         self.depth
     }
 }
 
-impl MerkleTree {
-    pub fn get_merkle_path(&self, leaf_index: usize) -> Vec<Hash> {
+impl<H: MerkleHasher> VectorCommitment<H> {
+    /// Serialized size: the root's [`MerkleHasher::DIGEST_BYTES`] followed by the depth as a
+    /// little-endian `u64`.
+    pub const BYTES: usize = H::DIGEST_BYTES + 8;
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::BYTES);
+        bytes.extend_from_slice(self.root.as_ref());
+        bytes.extend_from_slice(&(self.depth as u64).to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<VectorCommitment<H>> {
+        ensure!(
+            bytes.len() == Self::BYTES,
+            "Vector commitment must be {} bytes, got {}",
+            Self::BYTES,
+            bytes.len()
+        );
+
+        let (root, depth) = bytes.split_at(H::DIGEST_BYTES);
+        let depth = u64::from_le_bytes(depth.try_into()?);
+
+        Ok(VectorCommitment {
+            root: H::digest_from_bytes(root)?,
+            depth: usize::try_from(depth).context("depth does not fit in usize")?,
+        })
+    }
+}
+
+impl<H: MerkleHasher> MerkleTree<H> {
+    /// Hash `symbols_per_leaf` symbols into each leaf and build every layer above them.
+    pub fn from_symbols(symbols: &[BinaryField128b], symbols_per_leaf: usize) -> Self {
+        Self::from_leaves(compute_leaf_hashes_with::<H>(symbols, symbols_per_leaf))
+    }
+
+    /// Build every layer of the tree from a power-of-two set of leaf digests.
+    pub fn from_leaves(leaf_hashes: Vec<H::Digest>) -> Self {
+        assert!(
+            leaf_hashes.len().is_power_of_two(),
+            "Leaf hashes are not power of 2, cannot make Merkle Tree"
+        );
+
+        let tree_depth = leaf_hashes.len().trailing_zeros() as usize;
+        let mut layers = Vec::with_capacity(tree_depth + 1);
+        layers.push(leaf_hashes);
+
+        for _ in 0..tree_depth {
+            let parent_layer = build_parent_layer::<H>(layers.last().unwrap());
+            layers.push(parent_layer);
+        }
+
+        layers.reverse();
+
+        MerkleTree { data: layers }
+    }
+
+    pub fn get_merkle_path(&self, leaf_index: usize) -> Vec<H::Digest> {
         get_merkle_path(&self.data, leaf_index)
     }
 
-    pub fn get_root(&self) -> Hash {
+    /// Gathers the paths of many leaves layer by layer, so each layer's siblings are read together.
+    pub fn get_merkle_paths(&self, leaf_indices: &[usize]) -> Vec<Vec<H::Digest>> {
+        get_merkle_paths(&self.data, leaf_indices)
+    }
+
+    pub fn get_root(&self) -> H::Digest {
         self.data[0][0]
     }
+
+    pub fn depth(&self) -> usize {
+        self.data.len() - 1
+    }
+}
+
+/// Symbols hashed into each leaf by default, matching the arity-2 FRI fold.
+pub const DEFAULT_SYMBOLS_PER_LEAF: usize = 2;
+
+/// Leaves hash the adjacent symbol pair `(2i, 2i + 1)` (see [`compute_leaf_hashes`]), so codeword
+/// index `i` is opened by leaf `i / 2`. After a fold, leaf indices become the next round's codeword indices.
+#[inline(always)]
+pub fn codeword_index_to_leaf_index(index: usize) -> usize {
+    codeword_index_to_leaf_index_with(index, DEFAULT_SYMBOLS_PER_LEAF)
+}
+
+/// [`codeword_index_to_leaf_index`] for trees built with `symbols_per_leaf` symbols in each leaf.
+#[inline(always)]
+pub fn codeword_index_to_leaf_index_with(index: usize, symbols_per_leaf: usize) -> usize {
+    index / symbols_per_leaf
+}
+
+/// Codeword index of the even symbol in `leaf`; its sibling symbol sits at the next index.
+#[inline(always)]
+pub fn leaf_index_to_codeword_index(leaf: usize) -> usize {
+    leaf << 1
 }
 
 /// Hash arbitrary bytes using Keccak-256.
@@ -79,30 +326,31 @@ pub fn hash_concatenation(data1: &Hash, data2: &Hash) -> Hash {
     Hash(Keccak256::digest(val))
 }
 
-/// Build every layer of a Merkle tree from a power-of-two set of leaf hashes.
+/// Build every layer of a Merkle tree from a power-of-two set of leaf hashes. Name `H` with a
+/// turbofish, `merklize::<Keccak256Hasher>` for the default tree.
 #[instrument(skip_all, name = "merklize", level = "debug")]
-pub fn merklize(leaf_hashes: Vec<Hash>) -> MerkleTree {
-    assert!(
-        leaf_hashes.len().is_power_of_two(),
-        "Leaf hashes are not power of 2, cannot make Merkle Tree"
-    );
+pub fn merklize<H: MerkleHasher>(leaf_hashes: Vec<H::Digest>) -> MerkleTree<H> {
+    MerkleTree::from_leaves(leaf_hashes)
+}
 
-    let tree_depth = leaf_hashes.len().trailing_zeros() as usize;
-    let mut layers: Vec<Vec<Hash>> = Vec::with_capacity(tree_depth + 1);
-    layers.push(leaf_hashes);
+/// Root of the tree [`merklize`] builds over [`compute_leaf_hashes`]`(symbols)`, keeping only the
+/// layer being hashed rather than every layer.
+pub fn merkle_root_from_symbols(symbols: &[BinaryField128b]) -> anyhow::Result<Hash> {
+    ensure!(
+        symbols.len() >= DEFAULT_SYMBOLS_PER_LEAF && symbols.len().is_power_of_two(),
+        "{} symbols do not fill a power-of-two number of leaves",
+        symbols.len()
+    );
 
-    for _ in 0..tree_depth {
-        let parent_layer = build_parent_layer(layers.last().unwrap());
-        layers.push(parent_layer);
+    let mut layer = compute_leaf_hashes::<Keccak256Hasher>(symbols);
+    while layer.len() > 1 {
+        layer = build_parent_layer::<Keccak256Hasher>(&layer);
     }
-
-    layers.reverse();
-
-    MerkleTree { data: layers }
+    Ok(layer[0])
 }
 
 /// Return the sibling hashes from a leaf up to (but excluding) the root.
-pub fn get_merkle_path(tree: &[Vec<Hash>], leaf_index: usize) -> Vec<Hash> {
+pub fn get_merkle_path<D: Copy>(tree: &[Vec<D>], leaf_index: usize) -> Vec<D> {
     let leaf_depth = tree
         .len()
         .checked_sub(1)
@@ -125,66 +373,352 @@ pub fn get_merkle_path(tree: &[Vec<Hash>], leaf_index: usize) -> Vec<Hash> {
     path
 }
 
+/// Batched [`get_merkle_path`]: walks the tree bottom-up once, collecting every leaf's sibling per layer.
+pub fn get_merkle_paths<D: Copy>(tree: &[Vec<D>], leaf_indices: &[usize]) -> Vec<Vec<D>> {
+    let leaf_depth = tree
+        .len()
+        .checked_sub(1)
+        .expect("Merkle tree cannot be empty");
+
+    assert!(
+        leaf_indices.iter().all(|&i| i < tree[leaf_depth].len()),
+        "Leaf index out of bounds"
+    );
+
+    let mut indices = leaf_indices.to_vec();
+    let mut paths = vec![Vec::with_capacity(leaf_depth); indices.len()];
+
+    for depth in (1..=leaf_depth).rev() {
+        let layer = &tree[depth];
+        for (path, index) in paths.iter_mut().zip(indices.iter_mut()) {
+            path.push(layer[*index ^ 1]);
+            *index >>= 1;
+        }
+    }
+
+    paths
+}
+
 /// Recompute the Merkle root from a leaf hash and its path, asserting equality.
-pub fn verify_merkle_path(
-    commitment: &VectorCommitment,
-    leaf_hash: Hash,
+pub fn verify_merkle_path<H: MerkleHasher>(
+    commitment: &VectorCommitment<H>,
+    leaf_hash: H::Digest,
     leaf_index: usize,
-    merkle_path: &[Hash],
+    merkle_path: &[H::Digest],
 ) -> anyhow::Result<()> {
-    let mut hash = leaf_hash;
+    verify_merkle_path_with::<H>(
+        &commitment.root,
+        commitment.depth,
+        leaf_hash,
+        leaf_index,
+        merkle_path,
+    )
+}
+
+/// Collapse pairs of field elements into leaf hashes. Name `H` with a turbofish, as for
+/// [`merklize`].
+#[instrument(skip_all, name = "compute_leaf_hashes", level = "debug")]
+pub fn compute_leaf_hashes<H: MerkleHasher>(vals: &[BinaryField128b]) -> Vec<H::Digest> {
+    compute_leaf_hashes_with::<H>(vals, DEFAULT_SYMBOLS_PER_LEAF)
+}
+
+/// Collapse runs of `symbols_per_leaf` field elements into leaf hashes.
+pub fn compute_leaf_hashes_with<H: MerkleHasher>(
+    vals: &[BinaryField128b],
+    symbols_per_leaf: usize,
+) -> Vec<H::Digest> {
+    assert!(symbols_per_leaf > 0, "Leaves must hold at least one symbol");
+    assert_eq!(
+        vals.len() % symbols_per_leaf,
+        0,
+        "Leaf construction requires a multiple of {symbols_per_leaf} field elements"
+    );
+
+    if parallelism_available() {
+        vals.par_chunks_exact(symbols_per_leaf)
+            .with_min_len(min_grain())
+            .map(H::hash_leaf)
+            .collect()
+    } else {
+        vals.chunks_exact(symbols_per_leaf)
+            .map(H::hash_leaf)
+            .collect()
+    }
+}
 
+fn build_parent_layer<H: MerkleHasher>(child_layer: &[H::Digest]) -> Vec<H::Digest> {
+    assert_eq!(
+        child_layer.len() & 1,
+        0,
+        "Child layer must contain an even number of nodes"
+    );
+    let hash_pair = |pair: &[H::Digest]| H::hash_concatenation(&pair[0], &pair[1]);
+    if parallelism_available() {
+        child_layer.par_chunks_exact(2).map(hash_pair).collect()
+    } else {
+        child_layer.chunks_exact(2).map(hash_pair).collect()
+    }
+}
+
+/// [`verify_merkle_path`] for a tree built by an arbitrary [`MerkleHasher`].
+pub fn verify_merkle_path_with<H: MerkleHasher>(
+    root: &H::Digest,
+    depth: usize,
+    leaf_hash: H::Digest,
+    leaf_index: usize,
+    merkle_path: &[H::Digest],
+) -> anyhow::Result<()> {
     ensure!(
-        merkle_path.len() == commitment.depth,
+        merkle_path.len() == depth,
         "Merkle path length doesn't match claimed depth."
     );
 
-    for d in 0..merkle_path.len() {
-        let is_left_child = ((leaf_index >> d) & 1) == 0;
-        hash = if is_left_child {
-            hash_concatenation(&hash, &merkle_path[d])
-        } else {
-            hash_concatenation(&merkle_path[d], &hash)
-        };
-    }
+    let hash = merkle_path
+        .iter()
+        .enumerate()
+        .fold(leaf_hash, |hash, (d, sibling)| {
+            if (leaf_index >> d) & 1 == 0 {
+                H::hash_concatenation(&hash, sibling)
+            } else {
+                H::hash_concatenation(sibling, &hash)
+            }
+        });
 
     ensure!(
-        hash == commitment.root,
+        hash == *root,
         "Path at index {leaf_index} failed to verify."
     );
     Ok(())
 }
 
-/// Collapse pairs of field elements into leaf hashes.
-#[instrument(skip_all, name = "compute_leaf_hashes", level = "debug")]
-pub fn compute_leaf_hashes(vals: &[BinaryField128b]) -> Vec<Hash> {
-    assert_eq!(
-        vals.len() & 1,
-        0,
-        "Leaf construction requires an even number of field elements"
-    );
+/// The `2^cap_height` nodes of layer `cap_height`, committed in place of the root so that
+/// openings stop `cap_height` levels short of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleCap {
+    pub nodes: Vec<Hash>,
+    /// Depth of the leaves below the root.
+    pub depth: usize,
+}
 
-    vals.par_chunks_exact(2)
-        .map(|pair| {
-            let mut hasher = Keccak256::new();
-            hasher.update(pair[0].val().to_le_bytes());
-            hasher.update(pair[1].val().to_le_bytes());
+impl MerkleCap {
+    pub fn cap_height(&self) -> usize {
+        self.nodes.len().trailing_zeros() as usize
+    }
+}
 
-            Hash(hasher.finalize())
-        })
-        .collect()
+/// Openings of several leaves against a [`MerkleCap`] in which every node below the cap is sent
+/// at most once: siblings that another opened leaf already determines are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerkleMultiProof {
+    /// Missing siblings layer by layer from the leaves up, each layer in increasing index order.
+    pub siblings: Vec<Hash>,
 }
 
-fn build_parent_layer(child_layer: &[Hash]) -> Vec<Hash> {
-    assert_eq!(
-        child_layer.len() & 1,
-        0,
-        "Child layer must contain an even number of nodes"
+impl MerkleMultiProof {
+    /// Prunes the authentication `paths` of `leaf_indices`, given in matching order and with
+    /// repeats, in a tree of depth `depth` to the multi-proof [`MerkleTree::get_multi_proof`] gives
+    /// at cap height 0, without access to the tree.
+    pub fn from_paths(
+        leaf_indices: &[usize],
+        paths: &[Vec<Hash>],
+        depth: usize,
+    ) -> anyhow::Result<MerkleMultiProof> {
+        ensure!(
+            leaf_indices.len() == paths.len(),
+            "{} leaf indices for {} paths",
+            leaf_indices.len(),
+            paths.len()
+        );
+        ensure!(
+            paths.iter().all(|path| path.len() == depth),
+            "Every path must have length {depth}"
+        );
+
+        // Each leaf carries a path running through every node above it.
+        let known = leaf_indices
+            .iter()
+            .zip(paths)
+            .map(|(&index, path)| (index, path.as_slice()))
+            .collect();
+        let siblings = missing_siblings(known, depth, |level, _, path| path[level]);
+
+        Ok(MerkleMultiProof { siblings })
+    }
+
+    /// Inverse of [`MerkleMultiProof::from_paths`]: the authentication path of every one of the
+    /// `(leaf_index, leaf_hash)` pairs, in order, rebuilt from the leaves and the pruned siblings.
+    ///
+    /// Nothing is checked against a root; verifying the rebuilt paths does that.
+    pub fn expand_paths(
+        &self,
+        leaves: &[(usize, Hash)],
+        depth: usize,
+    ) -> anyhow::Result<Vec<Vec<Hash>>> {
+        // The sibling of every known node, layer by layer from the leaves up.
+        let mut layer_siblings = vec![BTreeMap::new(); depth];
+        hash_up(
+            leaves,
+            depth,
+            depth,
+            &self.siblings,
+            |level, index, sibling| {
+                layer_siblings[level].insert(index, sibling);
+            },
+        )?;
+
+        Ok(leaves
+            .iter()
+            .map(|&(index, _)| {
+                layer_siblings
+                    .iter()
+                    .enumerate()
+                    .map(|(level, sibling_of)| sibling_of[&(index >> level)])
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+impl MerkleTree {
+    /// The nodes of layer `cap_height`.
+    pub fn cap(&self, cap_height: usize) -> MerkleCap {
+        assert!(
+            cap_height < self.data.len(),
+            "cap height {cap_height} exceeds the tree depth"
+        );
+        MerkleCap {
+            nodes: self.data[cap_height].clone(),
+            depth: self.depth(),
+        }
+    }
+
+    /// Opens every leaf of `leaf_indices`, in any order and with repeats, down to the cap of
+    /// height `cap_height`.
+    pub fn get_multi_proof(&self, leaf_indices: &[usize], cap_height: usize) -> MerkleMultiProof {
+        let leaf_depth = self.depth();
+        assert!(
+            cap_height <= leaf_depth,
+            "cap height exceeds the tree depth"
+        );
+        assert!(
+            leaf_indices
+                .iter()
+                .all(|&i| i < self.data[leaf_depth].len()),
+            "Leaf index out of bounds"
+        );
+
+        let known = leaf_indices.iter().map(|&index| (index, ())).collect();
+        let siblings = missing_siblings(known, leaf_depth - cap_height, |level, index, ()| {
+            self.data[leaf_depth - level][index ^ 1]
+        });
+
+        MerkleMultiProof { siblings }
+    }
+}
+
+/// Siblings missing from the `known` nodes, layer by layer over `levels` layers from the leaves
+/// up, in [`MerkleMultiProof::siblings`] order. `sibling(level, index, data)` supplies the sibling
+/// of node `index` of layer `level`, where `data` is carried up from one of the leaves below it.
+fn missing_siblings<T: Copy>(
+    mut known: Vec<(usize, T)>,
+    levels: usize,
+    sibling: impl Fn(usize, usize, T) -> Hash,
+) -> Vec<Hash> {
+    known.sort_by_key(|&(index, _)| index);
+    known.dedup_by_key(|&mut (index, _)| index);
+
+    let mut siblings = Vec::new();
+    for level in 0..levels {
+        for &(index, data) in &known {
+            if known
+                .binary_search_by_key(&(index ^ 1), |&(index, _)| index)
+                .is_err()
+            {
+                siblings.push(sibling(level, index, data));
+            }
+        }
+        known.iter_mut().for_each(|(index, _)| *index >>= 1);
+        known.dedup_by_key(|&mut (index, _)| index);
+    }
+    siblings
+}
+
+/// Hashes the `(leaf_index, leaf_hash)` pairs of a depth-`depth` tree up `levels` layers, taking
+/// each sibling no other leaf determines from `siblings`, and returns the nodes reached.
+/// `visit(level, index, sibling)` sees the sibling of every known node on the way.
+fn hash_up(
+    leaves: &[(usize, Hash)],
+    depth: usize,
+    levels: usize,
+    siblings: &[Hash],
+    mut visit: impl FnMut(usize, usize, Hash),
+) -> anyhow::Result<BTreeMap<usize, Hash>> {
+    let mut layer = BTreeMap::new();
+    for &(index, hash) in leaves {
+        ensure!(
+            index.checked_shr(depth as u32).unwrap_or(0) == 0,
+            "Leaf index {index} out of bounds for depth {depth}"
+        );
+        if let Some(previous) = layer.insert(index, hash) {
+            ensure!(previous == hash, "Conflicting openings of leaf {index}");
+        }
+    }
+
+    let mut siblings = siblings.iter();
+    for level in 0..levels {
+        let mut parents = BTreeMap::new();
+        let mut nodes = layer.iter().peekable();
+        while let Some((&index, hash)) = nodes.next() {
+            let parent = match index & 1 {
+                0 => {
+                    let right = match nodes.next_if(|&(&next, _)| next == index | 1) {
+                        Some((_, right)) => {
+                            visit(level, index | 1, *hash);
+                            right
+                        }
+                        None => siblings.next().context("Multi-proof is missing siblings")?,
+                    };
+                    visit(level, index, *right);
+                    hash_concatenation(hash, right)
+                }
+                _ => {
+                    let left = siblings.next().context("Multi-proof is missing siblings")?;
+                    visit(level, index, *left);
+                    hash_concatenation(left, hash)
+                }
+            };
+            parents.insert(index >> 1, parent);
+        }
+        layer = parents;
+    }
+
+    ensure!(siblings.next().is_none(), "Multi-proof has unused siblings");
+    Ok(layer)
+}
+
+/// Checks a [`MerkleMultiProof`] of the `(leaf_index, leaf_hash)` pairs against `cap`: hashes up
+/// from the leaves, sharing nodes between queries, until each one resolves to its cap node.
+pub fn verify_multi_proof(
+    cap: &MerkleCap,
+    leaves: &[(usize, Hash)],
+    proof: &MerkleMultiProof,
+) -> anyhow::Result<()> {
+    ensure!(
+        cap.nodes.len().is_power_of_two() && cap.cap_height() <= cap.depth,
+        "cap of {} nodes does not fit a tree of depth {}",
+        cap.nodes.len(),
+        cap.depth
     );
-    child_layer
-        .par_chunks_exact(2)
-        .map(|pair| hash_concatenation(&pair[0], &pair[1]))
-        .collect()
+
+    let levels = cap.depth - cap.cap_height();
+    let layer = hash_up(leaves, cap.depth, levels, &proof.siblings, |_, _, _| {})?;
+    for (index, hash) in layer {
+        ensure!(
+            cap.nodes[index] == hash,
+            "Openings under cap node {index} failed to verify."
+        );
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -205,7 +739,7 @@ mod tests {
             })
             .collect();
 
-        let _merkle_tree = merklize(leaf_hashes);
+        let _merkle_tree = merklize::<Keccak256Hasher>(leaf_hashes);
     }
 
     #[test]
@@ -220,8 +754,8 @@ mod tests {
             })
             .collect();
 
-        let merkle_tree = merklize(leaf_hashes.clone());
-        let commitment = VectorCommitment {
+        let merkle_tree = merklize::<Keccak256Hasher>(leaf_hashes.clone());
+        let commitment: VectorCommitment = VectorCommitment {
             root: merkle_tree.get_root(),
             depth: 8,
         };
@@ -231,4 +765,215 @@ mod tests {
 
         verify_merkle_path(&commitment, leaf_hashes[idx].clone(), idx, &merkle_path).unwrap();
     }
+
+    #[test]
+    fn vector_commitment_bytes_test() {
+        use rand::thread_rng;
+
+        let commitment: VectorCommitment = VectorCommitment {
+            root: hash_field(&BinaryField128b::random(thread_rng())),
+            depth: 17,
+        };
+
+        let bytes = commitment.to_bytes();
+        assert_eq!(bytes.len(), <VectorCommitment>::BYTES);
+        assert_eq!(<VectorCommitment>::from_bytes(&bytes).unwrap(), commitment);
+        assert!(<VectorCommitment>::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn batched_paths_match_single_test() {
+        use rand::thread_rng;
+
+        let leaf_hashes: Vec<Hash> = (0..1 << 8).map(|i: usize| hash(&i.to_le_bytes())).collect();
+        let tree = merklize::<Keccak256Hasher>(leaf_hashes);
+        let indices: Vec<usize> = (0..16).map(|_| thread_rng().gen_range(0..1 << 8)).collect();
+        let single: Vec<Vec<Hash>> = indices.iter().map(|i| tree.get_merkle_path(*i)).collect();
+        assert_eq!(tree.get_merkle_paths(&indices), single);
+    }
+
+    #[test]
+    fn index_mapping_test() {
+        for leaf in 0..1 << 8 {
+            let index = leaf_index_to_codeword_index(leaf);
+            assert_eq!(codeword_index_to_leaf_index(index), leaf);
+            assert_eq!(codeword_index_to_leaf_index(index | 1), leaf);
+        }
+    }
+
+    fn random_tree(depth: usize) -> MerkleTree {
+        use rand::thread_rng;
+
+        let leaf_hashes: Vec<Hash> = (0..1 << depth)
+            .map(|_| hash_field(&BinaryField128b::random(thread_rng())))
+            .collect();
+        merklize(leaf_hashes)
+    }
+
+    #[test]
+    fn get_merkle_paths_test() {
+        use rand::thread_rng;
+
+        let merkle_tree = random_tree(10);
+        let indices: Vec<usize> = (0..144)
+            .map(|_| thread_rng().gen_range(0..1 << 10))
+            .collect();
+
+        let batched = merkle_tree.get_merkle_paths(&indices);
+        for (idx, path) in indices.iter().zip(batched.iter()) {
+            assert_eq!(*path, merkle_tree.get_merkle_path(*idx));
+        }
+    }
+
+    #[test]
+    fn sha3_512_tree_test() {
+        use rand::thread_rng;
+
+        let symbols: Vec<BinaryField128b> = (0..1 << 11)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+
+        let keccak = MerkleTree::<Keccak256Hasher>::from_symbols(&symbols, 2);
+        assert_eq!(
+            keccak,
+            merklize(compute_leaf_hashes::<Keccak256Hasher>(&symbols))
+        );
+
+        let tree = MerkleTree::<Sha3_512Hasher>::from_symbols(&symbols, 2);
+        assert_eq!(tree.depth(), 10);
+        let root = tree.get_root();
+        assert_eq!(root.0.len(), 64);
+
+        for _ in 0..32 {
+            let idx = thread_rng().gen_range(0..1 << 10);
+            let leaf = Sha3_512Hasher::hash_leaf(&symbols[2 * idx..2 * idx + 2]);
+            let path = tree.get_merkle_path(idx);
+            verify_merkle_path_with::<Sha3_512Hasher>(&root, 10, leaf, idx, &path).unwrap();
+
+            let wrong = Sha3_512Hasher::hash_leaf(&symbols[2 * (idx ^ 1)..2 * (idx ^ 1) + 2]);
+            assert!(
+                verify_merkle_path_with::<Sha3_512Hasher>(&root, 10, wrong, idx, &path).is_err()
+            );
+            assert!(
+                verify_merkle_path_with::<Sha3_512Hasher>(&root, 10, leaf, idx, &path[1..])
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn blake3_tree_test() {
+        use rand::thread_rng;
+
+        fn check<H: MerkleHasher>(symbols: &[BinaryField128b]) -> H::Digest {
+            let tree = MerkleTree::<H>::from_symbols(symbols, 2);
+            assert_eq!(tree, merklize::<H>(compute_leaf_hashes::<H>(symbols)));
+            assert_eq!(tree.depth(), 10);
+            let commitment = VectorCommitment::<H> {
+                root: tree.get_root(),
+                depth: 10,
+            };
+
+            let indices: Vec<usize> = (0..64)
+                .map(|_| thread_rng().gen_range(0..1 << 10))
+                .collect();
+            for (&idx, path) in indices.iter().zip(tree.get_merkle_paths(&indices)) {
+                let leaf = H::hash_leaf(&symbols[2 * idx..2 * idx + 2]);
+                verify_merkle_path(&commitment, leaf, idx, &path).unwrap();
+                assert!(verify_merkle_path(&commitment, leaf, idx ^ 1, &path).is_err());
+            }
+            commitment.root
+        }
+
+        let symbols: Vec<BinaryField128b> = (0..1 << 11)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+
+        let keccak_root = check::<Keccak256Hasher>(&symbols);
+        let blake3_root = check::<Blake3Hasher>(&symbols);
+        assert_ne!(keccak_root.as_ref(), blake3_root.as_ref());
+    }
+
+    #[test]
+    fn capped_multi_proof_test() {
+        use rand::thread_rng;
+
+        let depth = 10;
+        let cap_height = 2;
+        let merkle_tree = random_tree(depth);
+        let cap = merkle_tree.cap(cap_height);
+        assert_eq!(cap.nodes.len(), 1 << cap_height);
+
+        let indices: Vec<usize> = (0..144)
+            .map(|_| thread_rng().gen_range(0..1 << depth))
+            .collect();
+        let leaves: Vec<(usize, Hash)> = indices
+            .iter()
+            .map(|&i| (i, merkle_tree.data[depth][i]))
+            .collect();
+
+        let proof = merkle_tree.get_multi_proof(&indices, cap_height);
+        verify_multi_proof(&cap, &leaves, &proof).unwrap();
+        assert!(proof.siblings.len() < indices.len() * (depth - cap_height));
+
+        // With a single cap node the multi-proof of one leaf is its ordinary path.
+        let single = merkle_tree.get_multi_proof(&indices[..1], 0);
+        assert_eq!(single.siblings, merkle_tree.get_merkle_path(indices[0]));
+
+        let mut tampered = leaves.clone();
+        tampered[7].1 = hash(b"forged leaf");
+        assert!(verify_multi_proof(&cap, &tampered, &proof).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.siblings[3] = hash(b"forged sibling");
+        assert!(verify_multi_proof(&cap, &leaves, &tampered).is_err());
+
+        let mut truncated = proof.clone();
+        truncated.siblings.pop();
+        assert!(verify_multi_proof(&cap, &leaves, &truncated).is_err());
+
+        let mut padded = proof.clone();
+        padded.siblings.push(hash(b"extra"));
+        assert!(verify_multi_proof(&cap, &leaves, &padded).is_err());
+
+        let mut wrong_cap = cap.clone();
+        wrong_cap.nodes.swap(0, 3);
+        assert!(verify_multi_proof(&wrong_cap, &leaves, &proof).is_err());
+    }
+
+    #[test]
+    fn multi_proof_from_paths_test() {
+        use rand::thread_rng;
+
+        let depth = 10;
+        let merkle_tree = random_tree(depth);
+        let indices: Vec<usize> = (0..144)
+            .map(|_| thread_rng().gen_range(0..1 << depth))
+            .collect();
+        let paths = merkle_tree.get_merkle_paths(&indices);
+        let leaves: Vec<(usize, Hash)> = indices
+            .iter()
+            .map(|&i| (i, merkle_tree.data[depth][i]))
+            .collect();
+
+        let pruned = MerkleMultiProof::from_paths(&indices, &paths, depth).unwrap();
+        assert_eq!(pruned, merkle_tree.get_multi_proof(&indices, 0));
+        assert!(pruned.siblings.len() < indices.len() * depth);
+
+        // The rebuilt paths are the naive ones, each verifying against the same root.
+        let expanded = pruned.expand_paths(&leaves, depth).unwrap();
+        assert_eq!(expanded, paths);
+        let root = merkle_tree.get_root();
+        for ((index, leaf), path) in leaves.iter().zip(&expanded) {
+            verify_merkle_path_with::<Keccak256Hasher>(&root, depth, *leaf, *index, path).unwrap();
+        }
+
+        let mut truncated = pruned.clone();
+        truncated.siblings.pop();
+        assert!(truncated.expand_paths(&leaves, depth).is_err());
+        let mut padded = pruned;
+        padded.siblings.push(hash(b"extra"));
+        assert!(padded.expand_paths(&leaves, depth).is_err());
+        assert!(MerkleMultiProof::from_paths(&indices[1..], &paths, depth).is_err());
+    }
 }
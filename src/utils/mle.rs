@@ -1,6 +1,8 @@
+use anyhow::{Context, ensure};
 use binius_field::{
     BinaryField, BinaryField1b, BinaryField128b, ExtensionField, Field, TowerField,
 };
+use itertools::multizip;
 use rayon::{
     iter::{
         IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
@@ -9,7 +11,13 @@ use rayon::{
 };
 use tracing::instrument;
 
-use crate::utils::TAU;
+use crate::{
+    Result,
+    utils::{
+        TAU,
+        parallel::{min_grain, parallelism_available},
+    },
+};
 
 // We use this struct to represent both the case when coefficients are from an extension field but represent packed elements, and when the coefficients of the MLE are truly in the extension field.
 #[derive(Clone, Debug, Default)]
@@ -21,6 +29,9 @@ where
     pub packing_factor: usize,
     pub variables: usize,
     pub coeffs: Vec<F>,
+    /// Coefficients given before [`PackedMLE::new_padded`] zero-filled up to a power of two;
+    /// `coeffs.len()` when no padding was added.
+    pub unpadded_len: usize,
 }
 
 impl<F> PackedMLE<F>
@@ -28,22 +39,85 @@ where
     F: BinaryField + TowerField,
     BinaryField128b: ExtensionField<F>,
 {
+    /// [`PackedMLE::new`] for untrusted input, rejecting a coefficient count that is not a power
+    /// of two instead of deriving a wrong variable count from it.
+    pub fn try_new(coeffs: Vec<F>, packed: bool) -> Result<PackedMLE<F>> {
+        ensure!(
+            coeffs.len().is_power_of_two(),
+            "MLE needs a power-of-two number of coefficients, got {}",
+            coeffs.len()
+        );
+        Ok(Self::new(coeffs, packed))
+    }
+
+    /// Unchecked constructor for callers that already guarantee a power-of-two `coeffs.len()`;
+    /// see [`PackedMLE::try_new`].
     pub fn new(coeffs: Vec<F>, packed: bool) -> PackedMLE<F> {
+        debug_assert!(
+            coeffs.len().is_power_of_two(),
+            "MLE needs a power-of-two number of coefficients, got {}",
+            coeffs.len()
+        );
         match packed {
             true => PackedMLE {
                 packing_factor: F::N_BITS.trailing_zeros() as usize,
                 variables: (coeffs.len().trailing_zeros() + F::N_BITS.trailing_zeros()) as usize,
+                unpadded_len: coeffs.len(),
                 coeffs,
             },
 
             false => PackedMLE {
                 packing_factor: 0,
                 variables: coeffs.len().trailing_zeros() as usize,
+                unpadded_len: coeffs.len(),
                 coeffs,
             },
         }
     }
 
+    /// Zero-pads `coeffs` up to the next power of two, recording the original length in
+    /// [`PackedMLE::unpadded_len`]. Padded positions hold zero, so every evaluation equals that of
+    /// the original coefficients and the commitment is an ordinary one to the padded polynomial.
+    pub fn new_padded(mut coeffs: Vec<F>, packed: bool) -> PackedMLE<F> {
+        let unpadded_len = coeffs.len();
+        coeffs.resize(unpadded_len.next_power_of_two(), F::ZERO);
+        PackedMLE {
+            unpadded_len,
+            ..Self::new(coeffs, packed)
+        }
+    }
+
+    /// The coefficients given before padding.
+    pub fn unpadded_coeffs(&self) -> &[F] {
+        &self.coeffs[..self.unpadded_len]
+    }
+
+    /// Collects a parallel iterator of coefficients straight into the MLE, as [`PackedMLE::new`] would.
+    pub fn from_par_iter<I>(iter: I, packed: bool) -> PackedMLE<F>
+    where
+        I: IntoParallelIterator<Item = F>,
+    {
+        Self::new(iter.into_par_iter().collect(), packed)
+    }
+
+    /// Packs `bits` into coefficients of `F::N_BITS` bits each, lowest bit first, zero-padding the
+    /// tail up to a power-of-two number of coefficients.
+    pub fn from_bits(bits: &[BinaryField1b]) -> PackedMLE<F> {
+        let bits_per_coeff = F::N_BITS;
+        let len = bits.len().div_ceil(bits_per_coeff).next_power_of_two();
+        Self::from_par_iter(
+            (0..len).into_par_iter().map(|i| {
+                let start = (i * bits_per_coeff).min(bits.len());
+                let end = (start + bits_per_coeff).min(bits.len());
+                let mut chunk = bits[start..end].to_vec();
+                chunk.resize(bits_per_coeff, BinaryField1b::ZERO);
+                <F as ExtensionField<BinaryField1b>>::from_bases(&chunk)
+                    .expect("failed to pack bits")
+            }),
+            true,
+        )
+    }
+
     #[inline(always)]
     //Indexes the vector as an unpacked vector.
     pub fn packed_idx(&self, idx: usize) -> BinaryField1b {
@@ -54,15 +128,74 @@ where
             .unwrap()
     }
 
+    /// Base element `sub_idx` of coefficient `coeff_idx`, embedded in `BinaryField128b`: the
+    /// hypercube value at `(coeff_idx << packing_factor) | sub_idx`.
+    ///
+    /// An unpacked MLE has a single sub-element per coefficient, the coefficient itself.
+    pub fn sub_element(&self, coeff_idx: usize, sub_idx: usize) -> BinaryField128b {
+        assert!(
+            coeff_idx < self.coeffs.len(),
+            "coefficient index out of bounds"
+        );
+        assert!(
+            sub_idx < 1 << self.packing_factor,
+            "sub-element index out of bounds"
+        );
+
+        match self.packing_factor {
+            0 => self.coeffs[coeff_idx].into(),
+            _ => self
+                .packed_idx((coeff_idx << self.packing_factor) | sub_idx)
+                .into(),
+        }
+    }
+
     pub fn repack_for_fri(self) -> PackedMLE<BinaryField128b> {
-        PackedMLE::<BinaryField128b>::new(
+        let degree = <BinaryField128b as ExtensionField<F>>::DEGREE;
+        let repack = |base_elems: &[F]| BinaryField128b::from_bases(base_elems).unwrap();
+        let coeffs = if parallelism_available() {
             self.coeffs
-                .par_chunks(<BinaryField128b as ExtensionField<F>>::DEGREE)
-                .map(|base_elems| BinaryField128b::from_bases(base_elems).unwrap())
-                .collect(),
-            false,
-        )
+                .par_chunks(degree)
+                .with_min_len(min_grain())
+                .map(repack)
+                .collect()
+        } else {
+            self.coeffs.chunks(degree).map(repack).collect()
+        };
+
+        PackedMLE::<BinaryField128b>::new(coeffs, false)
+    }
+
+    /// Packs every `2^tau` consecutive hypercube bits into one `BinaryField128b`, lowest bit
+    /// first, so for `tau < TAU` each symbol lies in a proper subfield. [`Self::repack_for_fri`]
+    /// is the `tau = TAU` case.
+    pub fn repack_for_tau(&self, tau: usize) -> PackedMLE<BinaryField128b> {
+        assert!(
+            tau <= TAU && tau <= self.variables,
+            "cannot pack 2^{tau} bits per symbol"
+        );
+        let repack = |i: usize| {
+            let bits: Vec<BinaryField1b> = (0..1 << tau)
+                .map(|k| self.packed_idx((i << tau) | k))
+                .collect();
+            BinaryField128b::from_bases(&bits).unwrap()
+        };
+        if parallelism_available() {
+            PackedMLE::<BinaryField128b>::from_par_iter(
+                (0..1 << (self.variables - tau))
+                    .into_par_iter()
+                    .with_min_len(min_grain())
+                    .map(repack),
+                false,
+            )
+        } else {
+            PackedMLE::<BinaryField128b>::new(
+                (0..1 << (self.variables - tau)).map(repack).collect(),
+                false,
+            )
+        }
     }
+
     pub fn get_bound_elem(&self, idx: usize, eq: &LagrangeBases) -> BinaryField128b {
         assert!(idx < 1 << (self.variables - eq.vars), "idx out of bounds");
         let mut res = BinaryField128b::ZERO;
@@ -78,31 +211,35 @@ where
     }
 
     pub fn fold_as_unpacked_hi(&self, eq: &LagrangeBases) -> PackedMLE<BinaryField128b> {
-        PackedMLE::<BinaryField128b>::new(
-            (0..1 << (self.variables - eq.vars))
-                .into_par_iter()
-                .map(|i| {
-                    assert!(i < 1 << (self.variables - eq.vars), "idx out of bounds");
-                    let mut res = BinaryField128b::ZERO;
-                    let shift = self.variables - eq.vars;
-                    for sub_cube in 0..eq.vals.len() {
-                        res += self.packed_idx(i | (sub_cube << shift)) * eq.idx(sub_cube);
-                    }
-                    res
-                })
-                .collect(),
-            false,
-        )
+        let len = 1 << (self.variables - eq.vars);
+        let bind = |i: usize| {
+            assert!(i < len, "idx out of bounds");
+            let mut res = BinaryField128b::ZERO;
+            let shift = self.variables - eq.vars;
+            for sub_cube in 0..eq.vals.len() {
+                res += self.packed_idx(i | (sub_cube << shift)) * eq.idx(sub_cube);
+            }
+            res
+        };
+        let fold = if parallelism_available() {
+            (0..len).into_par_iter().map(bind).collect()
+        } else {
+            (0..len).map(bind).collect()
+        };
+
+        PackedMLE::<BinaryField128b>::new(fold, false)
     }
 
     pub fn fold_as_unpacked_lo(&self, eq: &LagrangeBases) -> PackedMLE<BinaryField128b> {
-        PackedMLE::<BinaryField128b>::new(
-            (0..1 << (self.variables - eq.vars))
-                .into_par_iter()
-                .map(|i| self.get_bound_elem(i, eq))
-                .collect(),
-            false,
-        )
+        let len = 1 << (self.variables - eq.vars);
+        let bind = |i: usize| self.get_bound_elem(i, eq);
+        let fold = if parallelism_available() {
+            (0..len).into_par_iter().map(bind).collect()
+        } else {
+            (0..len).map(bind).collect()
+        };
+
+        PackedMLE::<BinaryField128b>::new(fold, false)
     }
 
     pub fn batch_rows(&self, eq: &LagrangeBases) -> PackedMLE<BinaryField128b> {
@@ -127,20 +264,27 @@ where
 
     pub fn fold_lo(&self, r: &BinaryField128b) -> PackedMLE<BinaryField128b> {
         let half_len = self.coeffs.len() >> 1;
-        let fold = (0..half_len)
-            .into_par_iter()
-            .map(|i| *r * (self.coeffs[i << 1] + self.coeffs[(i << 1) | 1]) + self.coeffs[i << 1])
-            .collect();
+        let fold_pair =
+            |i: usize| *r * (self.coeffs[i << 1] + self.coeffs[(i << 1) | 1]) + self.coeffs[i << 1];
+        let fold = if parallelism_available() {
+            (0..half_len).into_par_iter().map(fold_pair).collect()
+        } else {
+            (0..half_len).map(fold_pair).collect()
+        };
 
         PackedMLE::<BinaryField128b>::new(fold, false)
     }
 
+    /// [`PackedMLE::fold_lo`] over the highest variable: index `i` pairs with `i + half_len`.
     pub fn fold_hi(&self, r: &BinaryField128b) -> PackedMLE<BinaryField128b> {
         let half_len = self.coeffs.len() >> 1;
-        let fold = (0..half_len)
-            .into_par_iter()
-            .map(|i| *r * (self.coeffs[i] + self.coeffs[i | (1 << i)] + self.coeffs[i]))
-            .collect();
+        let fold_pair =
+            |i: usize| *r * (self.coeffs[i] + self.coeffs[i + half_len]) + self.coeffs[i];
+        let fold = if parallelism_available() {
+            (0..half_len).into_par_iter().map(fold_pair).collect()
+        } else {
+            (0..half_len).map(fold_pair).collect()
+        };
 
         PackedMLE::<BinaryField128b>::new(fold, false)
     }
@@ -166,6 +310,36 @@ where
     pub fn len(&self) -> usize {
         self.coeffs.len()
     }
+
+    /// Number of variables left after dropping the highest ones the polynomial is constant in,
+    /// i.e. those over which the coefficients merely repeat. Variables packed inside a
+    /// coefficient are never dropped.
+    pub fn effective_variables(&self) -> usize {
+        let mut len = self.coeffs.len();
+        while len > 1 && self.coeffs[..len / 2] == self.coeffs[len / 2..len] {
+            len /= 2;
+        }
+        self.variables - (self.coeffs.len() / len).trailing_zeros() as usize
+    }
+
+    /// The restriction to the lowest `variables` variables, equal to the whole polynomial when it
+    /// is constant in the rest; see [`PackedMLE::effective_variables`].
+    pub fn truncated(&self, variables: usize) -> PackedMLE<F> {
+        assert!(
+            (self.packing_factor..=self.variables).contains(&variables),
+            "cannot keep {variables} of {} variables with {} packed per coefficient",
+            self.variables,
+            self.packing_factor
+        );
+        PackedMLE {
+            packing_factor: self.packing_factor,
+            variables,
+            coeffs: self.coeffs[..1 << (variables - self.packing_factor)].to_vec(),
+            unpadded_len: self
+                .unpadded_len
+                .min(1 << (variables - self.packing_factor)),
+        }
+    }
 }
 
 pub struct LagrangeBases {
@@ -220,17 +394,23 @@ impl LagrangeBases {
 
     pub fn fold_lo(&mut self, r: &BinaryField128b) {
         let half_len = self.vals.len() >> 1;
-        let fold: Vec<BinaryField128b> = (0..half_len)
-            .into_par_iter()
-            .map(|i| *r * (self.vals[i << 1] + self.vals[(i << 1) | 1]) + self.vals[i << 1])
-            .collect();
+        let fold_pair =
+            |i: usize| *r * (self.vals[i << 1] + self.vals[(i << 1) | 1]) + self.vals[i << 1];
+        let fold: Vec<BinaryField128b> = if parallelism_available() {
+            (0..half_len).into_par_iter().map(fold_pair).collect()
+        } else {
+            (0..half_len).map(fold_pair).collect()
+        };
 
         self.vals = fold;
         self.vars -= 1;
     }
 
+    /// Row-batches every entry of this table, read as a [`ColumnView`] limb: entry `i` becomes
+    /// the sum of `eq`'s values over the set bits of `self.idx(i)`. Used to fold the tensor
+    /// batching into the sum-check equality table, matching [`compute_row_batch`] on the claim.
     #[instrument(skip_all, name = "row batch eq", level = "debug")]
-    pub fn row_batch(&mut self, eq: &LagrangeBases) -> LagrangeBases {
+    pub fn row_batch(&self, eq: &LagrangeBases) -> LagrangeBases {
         let vals = (0..self.vals.len())
             .into_par_iter()
             .map(|i| {
@@ -263,7 +443,60 @@ impl LagrangeBases {
     }
 }
 
-fn compute_eq(r: &[BinaryField128b]) -> Vec<BinaryField128b> {
+/// Product domain `{lo_0, hi_0} x ... x {lo_n, hi_n}` on which an MLE's values are given.
+///
+/// Each coordinate is mapped to the hypercube by `x -> (x - lo_i) / (hi_i - lo_i)`, sending `lo_i`
+/// to 0 and `hi_i` to 1, so a domain evaluation becomes an ordinary multilinear evaluation claim.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AffineDomain {
+    pub lo: Vec<BinaryField128b>,
+    pub hi: Vec<BinaryField128b>,
+    inv_widths: Vec<BinaryField128b>,
+}
+
+impl AffineDomain {
+    pub fn new(lo: Vec<BinaryField128b>, hi: Vec<BinaryField128b>) -> Result<AffineDomain> {
+        ensure!(
+            lo.len() == hi.len(),
+            "domain has {} low points but {} high points",
+            lo.len(),
+            hi.len()
+        );
+
+        let inv_widths = lo
+            .iter()
+            .zip(hi.iter())
+            .enumerate()
+            .map(|(i, (lo, hi))| {
+                (*hi - *lo)
+                    .invert()
+                    .with_context(|| format!("domain coordinate {i} has equal endpoints"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(AffineDomain { lo, hi, inv_widths })
+    }
+
+    pub fn vars(&self) -> usize {
+        self.lo.len()
+    }
+
+    /// Maps a point of the domain's ambient space to the equivalent hypercube evaluation point.
+    pub fn lift_point(&self, domain_point: &[BinaryField128b]) -> Result<Vec<BinaryField128b>> {
+        ensure!(
+            domain_point.len() == self.vars(),
+            "domain point has {} coordinates, domain has {}",
+            domain_point.len(),
+            self.vars()
+        );
+
+        Ok(multizip((domain_point, &self.lo, &self.inv_widths))
+            .map(|(x, lo, inv_width)| (*x - *lo) * *inv_width)
+            .collect())
+    }
+}
+
+pub fn compute_eq(r: &[BinaryField128b]) -> Vec<BinaryField128b> {
     let mut bases: Vec<BinaryField128b> = vec![BinaryField128b::ZERO; 1 << r.len()];
     let mut size = 1;
     bases[0] = BinaryField128b::ONE;
@@ -272,13 +505,18 @@ fn compute_eq(r: &[BinaryField128b]) -> Vec<BinaryField128b> {
         let (bases_left, bases_right) = bases.split_at_mut(size);
         let (bases_right, _) = bases_right.split_at_mut(size);
 
-        bases_left
-            .par_iter_mut()
-            .zip_eq(bases_right.par_iter_mut())
-            .for_each(|(x, y)| {
-                *y = *x * *r;
-                *x -= *y;
-            });
+        let split = |(x, y): (&mut BinaryField128b, &mut BinaryField128b)| {
+            *y = *x * *r;
+            *x -= *y;
+        };
+        if parallelism_available() {
+            bases_left
+                .par_iter_mut()
+                .zip_eq(bases_right.par_iter_mut())
+                .for_each(split)
+        } else {
+            bases_left.iter_mut().zip(bases_right).for_each(split)
+        }
 
         size *= 2;
     }
@@ -296,15 +534,104 @@ pub fn compute_dot_product(
         .sum()
 }
 
-//Assumes input is in column view.
-pub fn compute_row_batch(scalars: &[BinaryField128b], vals: &[BinaryField128b]) -> BinaryField128b {
-    let row_view = switch_view(vals);
+/// Number of `BinaryField128b` limbs of a ring-switching tensor-algebra element.
+pub const ALGEBRA_LIMBS: usize = 128;
+
+/// Tensor-algebra element in column view: bit `i` of limb `j` is entry `(i, j)` of its 128x128
+/// bit matrix. The prover's partial evaluations (`EvalProof::upper_partial_evals`) use this view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnView(Vec<BinaryField128b>);
+
+/// Tensor-algebra element in row view: bit `j` of limb `i` is entry `(i, j)`, the transpose of
+/// its [`ColumnView`]. Row batching combines the limbs of this view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowView(Vec<BinaryField128b>);
+
+impl ColumnView {
+    pub fn new(limbs: Vec<BinaryField128b>) -> Result<ColumnView> {
+        ensure!(
+            limbs.len() == ALGEBRA_LIMBS,
+            "algebra element needs {ALGEBRA_LIMBS} limbs, got {}",
+            limbs.len()
+        );
+        Ok(ColumnView(limbs))
+    }
 
-    row_view
-        .iter()
-        .zip(scalars.iter())
-        .map(|(val, scalar)| *scalar * *val)
-        .sum()
+    /// Column view of the `2^tau` partial evaluations of a ring switch at split `tau <= TAU`.
+    /// The committed symbols then lie in the subfield spanned by the first `2^tau` basis
+    /// elements, so the missing limbs are zero.
+    pub fn from_partial_evals(partial_evals: &[BinaryField128b]) -> Result<ColumnView> {
+        ensure!(
+            partial_evals.len().is_power_of_two() && partial_evals.len() <= ALGEBRA_LIMBS,
+            "{} partial evaluations do not fill a power-of-two prefix of {ALGEBRA_LIMBS} limbs",
+            partial_evals.len()
+        );
+        let mut limbs = partial_evals.to_vec();
+        limbs.resize(ALGEBRA_LIMBS, BinaryField128b::ZERO);
+        Ok(ColumnView(limbs))
+    }
+
+    pub fn limbs(&self) -> &[BinaryField128b] {
+        &self.0
+    }
+
+    pub fn to_row_view(&self) -> RowView {
+        RowView(switch_view(&self.0))
+    }
+}
+
+impl RowView {
+    pub fn new(limbs: Vec<BinaryField128b>) -> Result<RowView> {
+        ensure!(
+            limbs.len() == ALGEBRA_LIMBS,
+            "algebra element needs {ALGEBRA_LIMBS} limbs, got {}",
+            limbs.len()
+        );
+        Ok(RowView(limbs))
+    }
+
+    pub fn limbs(&self) -> &[BinaryField128b] {
+        &self.0
+    }
+
+    pub fn to_column_view(&self) -> ColumnView {
+        ColumnView(switch_view(&self.0))
+    }
+
+    /// Sum of the rows weighted by `scalars`.
+    pub fn batch(&self, scalars: &[BinaryField128b]) -> BinaryField128b {
+        compute_dot_product(scalars, &self.0)
+    }
+}
+
+/// Row-batches a column-view algebra element by `scalars`, typically the equality table of the
+/// tensor batching point: transposes it to a [`RowView`] and combines the rows.
+///
+/// ```
+/// use binius_field::{BinaryField128b, Field};
+/// use fri_binius::utils::mle::{ALGEBRA_LIMBS, ColumnView, compute_row_batch};
+///
+/// let column = ColumnView::new(vec![BinaryField128b::ONE; ALGEBRA_LIMBS]).unwrap();
+/// let scalars = vec![BinaryField128b::ONE; ALGEBRA_LIMBS];
+/// assert_eq!(
+///     compute_row_batch(&scalars, &column),
+///     column.to_row_view().batch(&scalars)
+/// );
+/// ```
+///
+/// A row view is rejected, as it would otherwise be transposed a second time:
+///
+/// ```compile_fail
+/// use binius_field::{BinaryField128b, Field};
+/// use fri_binius::utils::mle::{ALGEBRA_LIMBS, ColumnView, compute_row_batch};
+///
+/// let row = ColumnView::new(vec![BinaryField128b::ONE; ALGEBRA_LIMBS])
+///     .unwrap()
+///     .to_row_view();
+/// compute_row_batch(&[BinaryField128b::ONE; ALGEBRA_LIMBS], &row);
+/// ```
+pub fn compute_row_batch(scalars: &[BinaryField128b], vals: &ColumnView) -> BinaryField128b {
+    vals.to_row_view().batch(scalars)
 }
 
 //Switches view of an algebra element from column to row and vice versa.
@@ -325,3 +652,155 @@ pub fn switch_view(vals: &[BinaryField128b]) -> Vec<BinaryField128b> {
 
     row_view
 }
+
+#[cfg(test)]
+mod tests {
+    use binius_field::BinaryField64b;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn fold_hi_lo_test() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let coeffs: Vec<BinaryField64b> = (0..1 << 4)
+            .map(|_| BinaryField64b::random(&mut rng))
+            .collect();
+        let mle = PackedMLE::new(coeffs, true);
+        let point: Vec<BinaryField128b> = (0..mle.variables)
+            .map(|_| BinaryField128b::random(&mut rng))
+            .collect();
+        let expected = mle.get_bound_elem(0, &LagrangeBases::gen_from_point(&point));
+        let table = mle.fold_as_unpacked_hi(&LagrangeBases::new());
+
+        let lo = point.iter().fold(table.clone(), |t, r| t.fold_lo(r));
+        let hi = point.iter().rev().fold(table.clone(), |t, r| t.fold_hi(r));
+        assert_eq!(lo.coeffs, vec![expected]);
+        assert_eq!(hi.coeffs, vec![expected]);
+
+        // Binding the top half from above and the bottom half from below meets in the middle.
+        let (bottom, top) = point.split_at(point.len() / 2);
+        let top_folded = top.iter().rev().fold(table, |t, r| t.fold_hi(r));
+        let mixed = bottom.iter().fold(top_folded, |t, r| t.fold_lo(r));
+        assert_eq!(mixed.coeffs, vec![expected]);
+    }
+
+    #[test]
+    fn effective_variables_test() {
+        let coeffs: Vec<BinaryField64b> = (0..1 << 4)
+            .map(|i| BinaryField64b::random(StdRng::seed_from_u64(i)))
+            .collect();
+        let mle = PackedMLE::new(coeffs.clone(), true);
+        assert_eq!(mle.effective_variables(), mle.variables);
+
+        let repeated = PackedMLE::new(coeffs.repeat(4), true);
+        assert_eq!(repeated.variables, 12);
+        assert_eq!(repeated.effective_variables(), 10);
+        assert_eq!(repeated.truncated(10).coeffs, coeffs);
+
+        let constant = PackedMLE::new(vec![coeffs[0]; 8], true);
+        assert_eq!(constant.effective_variables(), 6);
+    }
+
+    #[test]
+    fn try_new_test() {
+        let coeffs: Vec<BinaryField64b> = (0..4)
+            .map(|i| BinaryField64b::random(StdRng::seed_from_u64(i)))
+            .collect();
+
+        let err = PackedMLE::try_new(coeffs[..3].to_vec(), true).unwrap_err();
+        assert!(err.to_string().contains("got 3"), "{err}");
+        assert!(PackedMLE::<BinaryField64b>::try_new(vec![], false).is_err());
+
+        let mle = PackedMLE::try_new(coeffs.clone(), true).unwrap();
+        assert_eq!(mle.variables, 2 + 6);
+        assert_eq!(mle.coeffs, PackedMLE::new(coeffs, true).coeffs);
+    }
+
+    #[test]
+    fn from_par_iter_test() {
+        let coeffs: Vec<BinaryField64b> = (0..1 << 10)
+            .map(|i| BinaryField64b::random(StdRng::seed_from_u64(i)))
+            .collect();
+
+        let collected = PackedMLE::new(coeffs.clone(), true);
+        let streamed = PackedMLE::from_par_iter(
+            (0..1u64 << 10)
+                .into_par_iter()
+                .map(|i| BinaryField64b::random(StdRng::seed_from_u64(i))),
+            true,
+        );
+
+        assert_eq!(streamed.coeffs, collected.coeffs);
+        assert_eq!(streamed.variables, collected.variables);
+        assert_eq!(streamed.packing_factor, collected.packing_factor);
+    }
+
+    #[test]
+    fn sub_element_test() {
+        let coeffs: Vec<BinaryField64b> = (0..1 << 4)
+            .map(|i| BinaryField64b::random(StdRng::seed_from_u64(i)))
+            .collect();
+
+        let packed = PackedMLE::new(coeffs.clone(), true);
+        for (i, coeff) in coeffs.iter().enumerate() {
+            let rebuilt = (0..64).fold(0u64, |acc, j| {
+                acc | ((packed.sub_element(i, j).val() as u64) << j)
+            });
+            assert_eq!(rebuilt, packed.idx(i).val());
+            assert_eq!(rebuilt, coeff.val());
+        }
+
+        let unpacked = PackedMLE::new(coeffs.clone(), false);
+        for (i, coeff) in coeffs.iter().enumerate() {
+            assert_eq!(unpacked.sub_element(i, 0), BinaryField128b::from(*coeff));
+        }
+    }
+
+    #[test]
+    fn from_bits_test() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let bits: Vec<BinaryField1b> = (0..1000).map(|_| BinaryField1b::random(&mut rng)).collect();
+
+        // 1000 bits need 16 coefficients of 64 bits; the last 24 bits are padding.
+        let packed = PackedMLE::<BinaryField64b>::from_bits(&bits);
+        assert_eq!(packed.coeffs.len(), 16);
+        assert_eq!(packed.variables, 10);
+        for (i, bit) in bits.iter().enumerate() {
+            assert_eq!(packed.packed_idx(i), *bit);
+        }
+        assert!((bits.len()..1 << 10).all(|i| packed.packed_idx(i) == BinaryField1b::ZERO));
+
+        // A non power-of-two coefficient count is padded with zero coefficients.
+        let packed = PackedMLE::<BinaryField64b>::from_bits(&bits[..64 * 5]);
+        assert_eq!(packed.coeffs.len(), 8);
+        assert!(
+            packed.coeffs[5..]
+                .iter()
+                .all(|coeff| *coeff == BinaryField64b::ZERO)
+        );
+    }
+
+    #[test]
+    fn algebra_view_test() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let limbs: Vec<BinaryField128b> = (0..ALGEBRA_LIMBS)
+            .map(|_| BinaryField128b::random(&mut rng))
+            .collect();
+        let scalars: Vec<BinaryField128b> = (0..ALGEBRA_LIMBS)
+            .map(|_| BinaryField128b::random(&mut rng))
+            .collect();
+
+        let column = ColumnView::new(limbs.clone()).unwrap();
+        let row = column.to_row_view();
+        assert_eq!(row.limbs(), switch_view(&limbs));
+        assert_eq!(row.to_column_view(), column);
+        assert_eq!(
+            compute_row_batch(&scalars, &column),
+            compute_dot_product(&scalars, row.limbs())
+        );
+
+        assert!(ColumnView::new(limbs[..ALGEBRA_LIMBS - 1].to_vec()).is_err());
+        assert!(RowView::new(Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::{Context, Result, ensure};
+use binius_field::BinaryField128b;
+
+use super::merkle::{Hash, hash, hash_concatenation, hash_field};
+
+/// Append-only Merkle Mountain Range over field symbols.
+///
+/// `layers[h]` holds every node of height `h` from left to right, so the peaks are the trailing
+/// nodes of the layers whose bit is set in the leaf count.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MmrCommitment {
+    layers: Vec<Vec<Hash>>,
+}
+
+/// Membership proof of a single leaf against the bagged peaks of an [`MmrCommitment`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MmrProof {
+    pub leaf_index: usize,
+    pub leaf_count: usize,
+    /// Sibling hashes from the leaf up to (but excluding) its peak.
+    pub path: Vec<Hash>,
+    /// Every peak at the time of proving, highest first.
+    pub peaks: Vec<Hash>,
+}
+
+impl MmrCommitment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends symbols as new leaves, merging equal-height subtrees as they complete.
+    pub fn append(&mut self, symbols: &[BinaryField128b]) {
+        for symbol in symbols {
+            let mut node = hash_field(symbol);
+            let mut height = 0;
+
+            loop {
+                if self.layers.len() == height {
+                    self.layers.push(Vec::new());
+                }
+                let layer = &mut self.layers[height];
+                layer.push(node);
+
+                if layer.len() & 1 == 1 {
+                    break;
+                }
+                node = hash_concatenation(&layer[layer.len() - 2], &layer[layer.len() - 1]);
+                height += 1;
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.first().map_or(0, Vec::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Peak hashes ordered from the highest (leftmost) subtree to the lowest.
+    pub fn peaks(&self) -> Vec<Hash> {
+        let leaf_count = self.len();
+        (0..self.layers.len())
+            .rev()
+            .filter(|height| (leaf_count >> height) & 1 == 1)
+            .map(|height| *self.layers[height].last().unwrap())
+            .collect()
+    }
+
+    pub fn root(&self) -> Hash {
+        bag_peaks(&self.peaks())
+    }
+
+    pub fn get_proof(&self, index: usize) -> Result<MmrProof> {
+        let leaf_count = self.len();
+        ensure!(
+            index < leaf_count,
+            "Leaf index {index} out of bounds for {leaf_count} leaves"
+        );
+
+        let (_, height) = locate_peak(leaf_count, index).context("locate peak of leaf")?;
+        let path = (0..height)
+            .map(|d| self.layers[d][(index >> d) ^ 1])
+            .collect();
+
+        Ok(MmrProof {
+            leaf_index: index,
+            leaf_count,
+            path,
+            peaks: self.peaks(),
+        })
+    }
+}
+
+/// Checks that `symbol` sits at `proof.leaf_index` of the range committed to by `root`.
+pub fn verify_mmr_proof(root: &Hash, symbol: BinaryField128b, proof: &MmrProof) -> Result<()> {
+    let (peak_pos, height) = locate_peak(proof.leaf_count, proof.leaf_index)
+        .context("leaf index out of bounds for claimed leaf count")?;
+
+    ensure!(
+        proof.peaks.len() == proof.leaf_count.count_ones() as usize,
+        "Peak count doesn't match claimed leaf count."
+    );
+    ensure!(
+        proof.path.len() == height,
+        "MMR path length doesn't match the height of its peak."
+    );
+
+    let mut node = hash_field(&symbol);
+    for (d, sibling) in proof.path.iter().enumerate() {
+        node = match (proof.leaf_index >> d) & 1 {
+            0 => hash_concatenation(&node, sibling),
+            _ => hash_concatenation(sibling, &node),
+        };
+    }
+
+    ensure!(
+        node == proof.peaks[peak_pos],
+        "Leaf {} does not hash to its peak.",
+        proof.leaf_index
+    );
+    ensure!(
+        bag_peaks(&proof.peaks) == *root,
+        "Peaks do not bag to root."
+    );
+    Ok(())
+}
+
+/// Folds the peaks left to right into a single root; the empty range hashes the empty string.
+fn bag_peaks(peaks: &[Hash]) -> Hash {
+    match peaks.split_first() {
+        Some((first, rest)) => rest
+            .iter()
+            .fold(*first, |acc, peak| hash_concatenation(&acc, peak)),
+        None => hash(&[]),
+    }
+}
+
+/// Position (highest first) and height of the peak whose subtree contains `index`.
+fn locate_peak(leaf_count: usize, index: usize) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    (0..usize::BITS as usize)
+        .rev()
+        .filter(|height| (leaf_count >> height) & 1 == 1)
+        .enumerate()
+        .find_map(|(pos, height)| {
+            offset += 1 << height;
+            (index < offset).then_some((pos, height))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use binius_field::Field;
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn mmr_append_and_prove_test() {
+        let symbols: Vec<BinaryField128b> = (0..37)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+
+        let mut mmr = MmrCommitment::new();
+        mmr.append(&symbols[..5]);
+        let early_root = mmr.root();
+        let early_proof = mmr.get_proof(2).unwrap();
+
+        mmr.append(&symbols[5..16]);
+        mmr.append(&symbols[16..]);
+        assert_eq!(mmr.len(), symbols.len());
+
+        verify_mmr_proof(&early_root, symbols[2], &early_proof).unwrap();
+
+        let root = mmr.root();
+        for index in [0, 2, 15, 16, 31, 32, 36] {
+            let proof = mmr.get_proof(index).unwrap();
+            verify_mmr_proof(&root, symbols[index], &proof).unwrap();
+        }
+
+        let proof = mmr.get_proof(2).unwrap();
+        assert!(verify_mmr_proof(&root, symbols[3], &proof).is_err());
+        assert!(verify_mmr_proof(&early_root, symbols[2], &proof).is_err());
+        assert!(mmr.get_proof(symbols.len()).is_err());
+    }
+}
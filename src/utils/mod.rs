@@ -1,7 +1,10 @@
+pub mod bytes;
 pub mod channel;
 pub mod code;
 pub mod merkle;
 pub mod mle;
+pub mod mmr;
+pub mod parallel;
 
-/// Base 2 log of the extension degree of the extension field used for soundness. 
+/// Base 2 log of the extension degree of the extension field used for soundness.
 pub const TAU: usize = 7;
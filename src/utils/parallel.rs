@@ -0,0 +1,99 @@
+use std::{cell::Cell, panic, sync::OnceLock};
+
+use rayon::{ThreadPoolBuildError, ThreadPoolBuilder};
+use tracing::warn;
+
+static POOL_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+thread_local! {
+    static FORCE_SERIAL: Cell<bool> = const { Cell::new(false) };
+    static MIN_GRAIN: Cell<usize> = const { Cell::new(1) };
+}
+
+/// Whether the hot loops (`compute_eq`, `sum_check_round`, the folds, encoding, repacking and
+/// the Merkle builders) may use rayon on this thread.
+///
+/// Rayon's global pool, the one those loops run on, is probed once; on targets where it cannot
+/// spawn workers (some embedded or WASM builds) they fall back to serial iteration instead of
+/// panicking in the global pool.
+pub fn parallelism_available() -> bool {
+    !FORCE_SERIAL.get() && *POOL_AVAILABLE.get_or_init(|| probe_pool(build_global_pool))
+}
+
+/// Builds rayon's global pool, or, if it was already built (by an earlier rayon call or the
+/// embedding application), checks that it came up.
+fn build_global_pool() -> std::result::Result<(), ThreadPoolBuildError> {
+    ThreadPoolBuilder::new().build_global().or_else(|err| {
+        // A global pool that failed to build is retried, and panics, on first use.
+        match panic::catch_unwind(rayon::current_num_threads) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(err),
+        }
+    })
+}
+
+/// Interprets one attempt at building a pool, logging why parallelism is unavailable on failure.
+pub fn probe_pool<T, B>(build: B) -> bool
+where
+    B: FnOnce() -> std::result::Result<T, ThreadPoolBuildError>,
+{
+    match build() {
+        Ok(_) => true,
+        Err(err) => {
+            warn!("rayon thread pool unavailable, falling back to serial iteration: {err}");
+            false
+        }
+    }
+}
+
+/// Runs `f` with the serial fallback forced on the current thread.
+pub fn with_serial_fallback<R>(f: impl FnOnce() -> R) -> R {
+    let previous = FORCE_SERIAL.replace(true);
+    let result = f();
+    FORCE_SERIAL.set(previous);
+    result
+}
+
+/// Fewest items rayon may hand a single task in the repack and leaf-hashing loops on this
+/// thread: 1, rayon's own default, unless raised by [`with_min_grain`].
+pub fn min_grain() -> usize {
+    MIN_GRAIN.get()
+}
+
+/// Runs `f` with the repack and leaf-hashing loops splitting into tasks of at least `grain`
+/// items, coarsening the granularity where per-task overhead dominates on many-core machines.
+pub fn with_min_grain<R>(grain: usize, f: impl FnOnce() -> R) -> R {
+    let previous = MIN_GRAIN.replace(grain.max(1));
+    let result = f();
+    MIN_GRAIN.set(previous);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[test]
+    fn probe_pool_test() {
+        assert!(probe_pool(|| ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()));
+
+        let failing = || {
+            ThreadPoolBuilder::new()
+                .num_threads(1)
+                .spawn_handler(|_| Err(io::Error::other("thread spawning unsupported")))
+                .build()
+        };
+        assert!(!probe_pool(failing));
+
+        assert!(with_serial_fallback(|| !parallelism_available()));
+        assert!(parallelism_available());
+
+        assert_eq!(with_min_grain(64, min_grain), 64);
+        assert_eq!(with_min_grain(0, min_grain), 1);
+        assert_eq!(min_grain(), 1);
+    }
+}
@@ -1,182 +1,5736 @@
-use anyhow::{Ok, ensure};
-use binius_field::{BinaryField, BinaryField128b, ExtensionField, Field};
+use std::{
+    borrow::Cow,
+    fmt,
+    hint::black_box,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Ok, ensure};
+use binius_field::{BinaryField, BinaryField1b, BinaryField128b, ExtensionField, Field};
 use binius_ntt::MultithreadedNTT;
 use itertools::multizip;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use tracing::instrument;
 
 use crate::{
     Result,
-    prover::{EvalProof, FriCommitment},
+    prover::{
+        AggregatedProof, DerivativeProof, EvalProof, FriCommitment, FriMerkleProofs,
+        FriQueriedSymbols, FriRoundMerklePaths, HeterogeneousCommitment, LinearCombinationProof,
+        MultiEvalProof, OracleForm, PointCommitment, PointEqTables, PositionOpening, ProductProof,
+        ProximityProof, PrunedEvalProof, SumProof, Univariate, ZeroOpening, batch_statement,
+        constant_term, proximity_rounds, restrict_point,
+    },
     utils::{
         TAU,
-        channel::Channel,
-        code::{LOG_RATE, fold},
-        merkle::{hash_tuple, verify_merkle_path},
-        mle::{compute_row_batch, switch_view},
+        channel::{Channel, TranscriptScript},
+        code::{
+            FoldTwiddles, LOG_RATE, RATE, coset_index_mask, fold, query_count, twiddle_fingerprint,
+        },
+        merkle::{
+            DEFAULT_SYMBOLS_PER_LEAF, Hash, Keccak256Hasher, MerkleHasher, VectorCommitment,
+            codeword_index_to_leaf_index, hash_concatenation, hash_tuple, verify_merkle_path,
+            verify_merkle_path_with,
+        },
+        mle::{
+            AffineDomain, ColumnView, compute_dot_product, compute_eq, compute_row_batch,
+            switch_view,
+        },
     },
 };
+
+/// Why [`verify`] rejected a proof, for callers that branch on the failure rather than its text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The oracle of sum-check round `round` does not sum to the running claim.
+    SumCheckMismatch { round: usize },
+    /// The final sum-check claim disagrees with the final folded value under the ring switch.
+    RingSwitchBinding,
+    /// The Merkle path of query `query` does not open against round `round`'s oracle.
+    MerklePath { round: usize, query: usize },
+    /// The pair opened by query `query` in round `round` disagrees with the previous fold.
+    QueryInconsistent { round: usize, query: usize },
+    /// The last folded symbol of query `query` differs from the final folded value.
+    FinalFoldMismatch { query: usize },
+    /// The claimed evaluation disagrees with the proof's partial evaluations.
+    EvalMismatch,
+    /// The prover folded with twiddles other than the verifier's NTT yields.
+    TwiddleMismatch,
+    /// Any other rejection, such as a proof or statement of the wrong shape.
+    Malformed(String),
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::SumCheckMismatch { round } => {
+                write!(f, "Sum of oracle evaluations failed on round {round}")
+            }
+            VerificationError::RingSwitchBinding => write!(
+                f,
+                "Final sum-check claim does not match the final folded value"
+            ),
+            VerificationError::MerklePath { round, query } => write!(
+                f,
+                "Merkle path of query {query} failed to verify in round {round}"
+            ),
+            VerificationError::QueryInconsistent { round, query } => {
+                write!(f, "Symbol not consistent at query {query} in round {round}")
+            }
+            VerificationError::FinalFoldMismatch { query } => write!(
+                f,
+                "Final folded symbol of query {query} does not match the final folded value"
+            ),
+            VerificationError::EvalMismatch => {
+                write!(f, "Evaluation claim does not match the partial evaluations")
+            }
+            VerificationError::TwiddleMismatch => write!(
+                f,
+                "NTT parameter mismatch: prover and verifier NTTs yield different folding twiddles"
+            ),
+            VerificationError::Malformed(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+impl VerificationError {
+    /// Verification phase this error rejects in, as reported by [`verify_diagnostic`].
+    pub fn stage(&self) -> FailureStage {
+        match self {
+            VerificationError::Malformed(_) | VerificationError::TwiddleMismatch => {
+                FailureStage::Statement
+            }
+            VerificationError::EvalMismatch => FailureStage::EvaluationBinding,
+            VerificationError::SumCheckMismatch { .. } | VerificationError::RingSwitchBinding => {
+                FailureStage::SumCheck
+            }
+            VerificationError::MerklePath { .. }
+            | VerificationError::QueryInconsistent { .. }
+            | VerificationError::FinalFoldMismatch { .. } => FailureStage::Proximity,
+        }
+    }
+}
+
+impl From<anyhow::Error> for VerificationError {
+    /// Recovers the structured error raised inside the verifier, or wraps any other failure
+    /// with its full context chain.
+    fn from(err: anyhow::Error) -> Self {
+        err.downcast::<VerificationError>()
+            .unwrap_or_else(|err| VerificationError::Malformed(format!("{err:#}")))
+    }
+}
+
+/// Runs a verifier body written against `anyhow`, returning its failure as the
+/// [`VerificationError`] every public entry point reports.
+fn verifying<T>(body: impl FnOnce() -> Result<T>) -> std::result::Result<T, VerificationError> {
+    body().map_err(VerificationError::from)
+}
+
+/// Verifies `eval_proof` that the polynomial committed in `commitment` evaluates to `eval` at
+/// `eval_point`. Non-generic: any [`FoldTwiddles`] source, such as a `MultithreadedNTT` over
+/// any subfield, is taken through dynamic dispatch.
 #[instrument(skip_all, name = "verify", level = "debug")]
-pub fn verify<P>(
+pub fn verify<H: MerkleHasher>(
+    commitment: &FriCommitment<H>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof<H>,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verify_with_context(
+        commitment,
+        eval_point,
+        eval,
+        &eval_proof,
+        ntt,
+        channel,
+        &VerifyContext::default(),
+    )
+    .map_err(VerificationError::from)
+}
+
+/// [`verify`] on a fresh [`Channel::for_application`] transcript, accepting only proofs made by
+/// [`prove_for_application`](crate::prover::prove_for_application) under the same `domain`.
+pub fn verify_for_application(
+    domain: &str,
     commitment: &FriCommitment,
     eval_point: &[BinaryField128b],
     eval: BinaryField128b,
     eval_proof: EvalProof,
-    ntt: &MultithreadedNTT<P>,
+    ntt: &dyn FoldTwiddles,
+) -> std::result::Result<(), VerificationError> {
+    verify(
+        commitment,
+        eval_point,
+        eval,
+        eval_proof,
+        ntt,
+        &mut Channel::for_application(domain),
+    )
+}
+
+/// Tally of `BinaryField128b` multiplications performed while verifying.
+#[derive(Debug, Default)]
+struct MulCounter(AtomicU64);
+
+impl MulCounter {
+    fn add(&self, mults: usize) {
+        self.0.fetch_add(mults as u64, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Optional inputs and instrumentation threaded through one verification.
+#[derive(Debug, Default)]
+struct VerifyContext<'a> {
+    /// Set only by [`verify_counting_mults`]; every other path skips the tally.
+    mults: Option<&'a MulCounter>,
+    batching_eq: Option<&'a BatchingEq>,
+    options: VerifyOptions,
+    /// Observed in place of the evaluation point, which must open it.
+    point_commitment: Option<&'a PointCommitment>,
+    eq_tables: Option<&'a PointEqTables>,
+}
+
+/// Multiplying entry points of the verifier, each tallying the products it performs when
+/// [`VerifyContext::mults`] is set.
+impl VerifyContext<'_> {
+    fn count_mults(&self, mults: usize) {
+        if let Some(counter) = self.mults {
+            counter.add(mults);
+        }
+    }
+
+    fn mul(&self, a: BinaryField128b, b: BinaryField128b) -> BinaryField128b {
+        self.count_mults(1);
+        a * b
+    }
+
+    /// [`Univariate::evaluate`]: one Horner product per coefficient.
+    fn evaluate(&self, oracle: &Univariate, r: BinaryField128b) -> BinaryField128b {
+        self.count_mults(oracle.coeffs.len());
+        oracle.evaluate(r)
+    }
+
+    /// [`compute_eq_table`]: one product per entry after the first.
+    fn eq_table(&self, r: &[BinaryField128b]) -> Vec<BinaryField128b> {
+        let table = compute_eq_table(r);
+        self.count_mults(table.len() - 1);
+        table
+    }
+
+    /// [`compute_dot_product`]: one product per pair it zips.
+    fn dot_product(
+        &self,
+        scalars: &[BinaryField128b],
+        vals: &[BinaryField128b],
+    ) -> BinaryField128b {
+        self.count_mults(scalars.len().min(vals.len()));
+        compute_dot_product(scalars, vals)
+    }
+
+    /// [`fold`]: the twiddle product and the challenge product.
+    fn fold<T: FoldTwiddles + ?Sized>(
+        &self,
+        r: BinaryField128b,
+        round: usize,
+        idx: usize,
+        val0: BinaryField128b,
+        val1: BinaryField128b,
+        ntt: &T,
+    ) -> BinaryField128b {
+        self.count_mults(2);
+        fold(r, round, idx, val0, val1, ntt)
+    }
+}
+
+/// Switches for [`verify_with_options`]; the default matches [`verify`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// Skips recomputing `eval` from the proof's partial evaluations.
+    ///
+    /// Unsound unless the partial evaluations are already known to combine to `eval`, e.g.
+    /// because an upstream reduction checked that relation: with it set, nothing ties the proof
+    /// to the claimed evaluation beyond the transcript.
+    pub skip_eval_reconstruction: bool,
+}
+
+/// Equality table of a tensor batching point, computed once and reused by
+/// [`verify_with_batching_eq`] across proofs whose transcripts draw the same point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchingEq {
+    point: Vec<BinaryField128b>,
+    table: Vec<BinaryField128b>,
+}
+
+impl BatchingEq {
+    pub fn new(point: &[BinaryField128b]) -> BatchingEq {
+        BatchingEq {
+            point: point.to_vec(),
+            table: compute_eq_table(point),
+        }
+    }
+
+    pub fn point(&self) -> &[BinaryField128b] {
+        &self.point
+    }
+
+    pub fn table(&self) -> &[BinaryField128b] {
+        &self.table
+    }
+}
+
+/// [`verify`] reusing `batching_eq` instead of recomputing the batching equality table; fails if
+/// the transcript draws a batching point other than the one `batching_eq` was built for.
+pub fn verify_with_batching_eq(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
     channel: &mut Channel,
-) -> Result<()>
-where
-    BinaryField128b: ExtensionField<P>,
-    P: BinaryField,
-{
-    channel.observe_fri_commitment(commitment);
-    channel.observe_field_elems(eval_point)?;
-    channel.observe_field_elem(eval)?;
+    batching_eq: &BatchingEq,
+) -> std::result::Result<(), VerificationError> {
+    let ctx = VerifyContext {
+        batching_eq: Some(batching_eq),
+        ..Default::default()
+    };
+    verify_with_context(
+        commitment,
+        eval_point,
+        eval,
+        &eval_proof,
+        ntt,
+        channel,
+        &ctx,
+    )
+    .map_err(VerificationError::from)
+}
 
-    let (left, right) = eval_point.split_at(TAU);
+/// [`verify`] with the checks adjusted by `options`.
+pub fn verify_with_options(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+    options: VerifyOptions,
+) -> std::result::Result<(), VerificationError> {
+    let ctx = VerifyContext {
+        options,
+        ..Default::default()
+    };
+    verify_with_context(
+        commitment,
+        eval_point,
+        eval,
+        &eval_proof,
+        ntt,
+        channel,
+        &ctx,
+    )
+    .map_err(VerificationError::from)
+}
 
-    let left_eq = compute_eq_table(left);
+/// [`verify`] at `eq_tables.point()`, reusing its precomputed eq table.
+pub fn verify_with_eq_tables(
+    commitment: &FriCommitment,
+    eq_tables: &PointEqTables,
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    let ctx = VerifyContext {
+        eq_tables: Some(eq_tables),
+        ..Default::default()
+    };
+    verify_with_context(
+        commitment,
+        eq_tables.point(),
+        eval,
+        &eval_proof,
+        ntt,
+        channel,
+        &ctx,
+    )
+    .map_err(VerificationError::from)
+}
 
-    let mut derived_eval = BinaryField128b::ZERO;
+/// [`verify`] for a proof from
+/// [`prove_with_point_commitment`](crate::prover::prove_with_point_commitment): the transcript
+/// binds to `point_commitment`, and `eval_point` is accepted only as its opening.
+pub fn verify_with_point_commitment(
+    commitment: &FriCommitment,
+    point_commitment: &PointCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    let ctx = VerifyContext {
+        point_commitment: Some(point_commitment),
+        ..Default::default()
+    };
+    verify_with_context(
+        commitment,
+        eval_point,
+        eval,
+        &eval_proof,
+        ntt,
+        channel,
+        &ctx,
+    )
+    .map_err(VerificationError::from)
+}
+
+/// [`verify`], additionally returning how many `BinaryField128b` multiplications it performed
+/// across the equality tables, row batching, sum-check evaluations and folds.
+///
+/// The count is a machine-independent cost measure; it covers the work done up to the point a
+/// rejected proof fails.
+pub fn verify_counting_mults(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> (std::result::Result<(), VerificationError>, u64) {
+    let mults = MulCounter::default();
+    let ctx = VerifyContext {
+        mults: Some(&mults),
+        ..Default::default()
+    };
+    let result = verify_with_context(
+        commitment,
+        eval_point,
+        eval,
+        &eval_proof,
+        ntt,
+        channel,
+        &ctx,
+    )
+    .map_err(VerificationError::from);
+    (result, mults.get())
+}
 
-    for i in 0..1 << TAU {
-        derived_eval += left_eq[i] * eval_proof.upper_partial_evals[i];
+/// Operation counts of one [`verify`] call together with this machine's measured cost per
+/// operation, from [`estimate_verify_time`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VerifyTimeModel {
+    /// Leaf and Merkle node hashes over the query phase.
+    pub hashes: u64,
+    /// `BinaryField128b` multiplications, as tallied by [`verify_counting_mults`].
+    pub mults: u64,
+    pub nanos_per_hash: f64,
+    pub nanos_per_mult: f64,
+}
+
+impl VerifyTimeModel {
+    pub fn estimate(&self) -> Duration {
+        Duration::from_secs_f64(
+            (self.hashes as f64 * self.nanos_per_hash + self.mults as f64 * self.nanos_per_mult)
+                / 1e9,
+        )
     }
 
-    ensure!(derived_eval == eval);
+    /// The whole estimate expressed in hash compressions.
+    pub fn hash_equivalents(&self) -> f64 {
+        self.hashes as f64 + self.mults as f64 * self.nanos_per_mult / self.nanos_per_hash
+    }
 
-    let tensor_batching_point = channel.get_random_points(TAU)?;
+    /// The whole estimate expressed in `BinaryField128b` multiplications.
+    pub fn mult_equivalents(&self) -> f64 {
+        self.mults as f64 + self.hashes as f64 * self.nanos_per_hash / self.nanos_per_mult
+    }
+}
 
-    let batching_eq = compute_eq_table(&tensor_batching_point);
-    let mut sum_check_claim = compute_row_batch(&batching_eq, &eval_proof.upper_partial_evals);
+/// Predicts how long [`verify`] takes on a proof with `num_queries` queries over `rounds`
+/// folding rounds of a commitment of Merkle depth `depth`, by timing `hash_concatenation` and
+/// `BinaryField128b` multiplication on this machine and weighting the operation counts.
+///
+/// Only hashes and multiplications are modelled, so the estimate is rough and runs low when
+/// transcript hashing or allocation dominate, as on small proofs.
+pub fn estimate_verify_time(num_queries: usize, rounds: usize, depth: usize) -> VerifyTimeModel {
+    const CALIBRATION_OPS: u32 = 1 << 14;
 
-    let rounds = right.len();
+    let mut hash = Hash(Default::default());
+    let start = Instant::now();
+    for _ in 0..CALIBRATION_OPS {
+        hash = hash_concatenation(black_box(&hash), black_box(&hash));
+    }
+    black_box(hash);
+    let hash_time = start.elapsed();
 
-    ensure!(rounds == eval_proof.sum_check_oracles.len());
+    let mut product = BinaryField128b::new(3);
+    let factor = black_box(BinaryField128b::new(0x1234_5678_9abc_def0));
+    let start = Instant::now();
+    for _ in 0..CALIBRATION_OPS {
+        product = black_box(product * factor);
+    }
+    black_box(product);
+    let mult_time = start.elapsed();
 
-    let mut random_point = Vec::new();
-    for round in 0..rounds {
-        let oracle = &eval_proof.sum_check_oracles[round];
+    VerifyTimeModel {
+        hashes: verify_hashes(num_queries, rounds, depth),
+        mults: verify_mults(num_queries, rounds),
+        nanos_per_hash: (hash_time.as_nanos() as f64 / CALIBRATION_OPS as f64).max(f64::EPSILON),
+        nanos_per_mult: (mult_time.as_nanos() as f64 / CALIBRATION_OPS as f64).max(f64::EPSILON),
+    }
+}
 
-        ensure!(
-            oracle.evaluate(BinaryField128b::ZERO) + oracle.evaluate(BinaryField128b::ONE)
-                == sum_check_claim,
-            "Sum of oracle evaluations failed on round {round}"
-        );
+/// Hashes of the query phase: per query and round, one leaf hash and one per path node, where
+/// the oracle of round `round` is `round` levels shallower than the commitment.
+fn verify_hashes(num_queries: usize, rounds: usize, depth: usize) -> u64 {
+    (0..rounds)
+        .map(|round| (1 + depth.saturating_sub(round)) as u64)
+        .sum::<u64>()
+        * num_queries as u64
+}
 
-        channel.observe_field_elems(&oracle.coeffs)?;
+/// Multiplications [`verify_counting_mults`] tallies on an accepted proof with sum-check oracles
+/// in coefficient form.
+fn verify_mults(num_queries: usize, rounds: usize) -> u64 {
+    let tensor = 1usize << TAU;
+    // Evaluation binding: the eq table of the first TAU coordinates and its dot product.
+    let binding = eq_table_mults(TAU) + tensor;
+    // Tensor batching: the batching eq table and the row batch of the partial evaluations.
+    let batching = eq_table_mults(TAU) + tensor.min(BinaryField128b::N_BITS);
+    // Three Horner evaluations of a three-coefficient oracle per sum-check round.
+    let sum_check = 9 * rounds;
+    // Ring-switch binding: two products per algebra limb and round, the final row batch and
+    // the product with the final folded value.
+    let ring_switch = 2 * BinaryField128b::N_BITS * rounds + BinaryField128b::N_BITS + 1;
+    // One twiddle and one challenge product per query fold.
+    let folds = 2 * num_queries * rounds;
+    (binding + batching + sum_check + ring_switch + folds) as u64
+}
 
-        let r = channel.get_random_point()?;
+fn verify_with_context<N, H>(
+    commitment: &FriCommitment<H>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: &EvalProof<H>,
+    ntt: &N,
+    channel: &mut Channel,
+    ctx: &VerifyContext,
+) -> Result<()>
+where
+    N: FoldTwiddles + ?Sized,
+    H: MerkleHasher,
+{
+    let (random_point, current_queries) = replay_commit_phase_with_context(
+        commitment, eval_point, eval, eval_proof, ntt, channel, ctx,
+    )?;
+    let folded_symbols = check_queries(
+        commitment,
+        eval_proof.into(),
+        &random_point,
+        current_queries,
+        ntt,
+        ctx,
+    )?;
+    check_final_symbols(&folded_symbols, eval_proof.final_folded_value)
+}
 
-        let current_oracle = &eval_proof.fri_oracles[round];
-        channel.observe_vector_commitment(current_oracle);
-        sum_check_claim = oracle.evaluate(r);
-        random_point.push(r);
+/// Query-phase data of an [`EvalProof`] or a [`ProximityProof`].
+struct FriQueryData<'a, H: MerkleHasher = Keccak256Hasher> {
+    oracles: &'a [VectorCommitment<H>],
+    queried_symbols: &'a FriQueriedSymbols,
+    merkle_paths: &'a FriMerkleProofs<H>,
+}
+
+impl<'a, H: MerkleHasher> From<&'a EvalProof<H>> for FriQueryData<'a, H> {
+    fn from(proof: &'a EvalProof<H>) -> Self {
+        FriQueryData {
+            oracles: &proof.fri_oracles,
+            queried_symbols: &proof.fri_queried_symbols,
+            merkle_paths: &proof.fri_merkle_paths,
+        }
     }
+}
 
-    channel.observe_field_elem(eval_proof.final_folded_value)?;
+impl<'a> From<&'a ProximityProof> for FriQueryData<'a> {
+    fn from(proof: &'a ProximityProof) -> Self {
+        FriQueryData {
+            oracles: &proof.fri_oracles,
+            queried_symbols: &proof.fri_queried_symbols,
+            merkle_paths: &proof.fri_merkle_paths,
+        }
+    }
+}
 
-    let mut current_queries: Vec<usize> = channel
-        .gen_queries(right.len() + LOG_RATE)?
-        .iter()
-        .map(|i| i >> 1)
-        .collect();
+/// Follows every query through the folding rounds, checking Merkle membership and fold
+/// consistency against the committed and folded oracles, and returns each query's final fold.
+fn check_queries<N, H>(
+    commitment: &FriCommitment<H>,
+    fri: FriQueryData<H>,
+    random_point: &[BinaryField128b],
+    current_queries: Vec<usize>,
+    ntt: &N,
+    ctx: &VerifyContext,
+) -> Result<Vec<BinaryField128b>>
+where
+    N: FoldTwiddles + ?Sized,
+    H: MerkleHasher,
+{
+    check_path_lengths(commitment, fri.merkle_paths, random_point.len())?;
+    walk_queries(
+        commitment,
+        fri,
+        random_point,
+        current_queries,
+        ntt,
+        ctx,
+        |round, i, merkle, check| {
+            ensure!(
+                check.consistent,
+                VerificationError::QueryInconsistent { round, query: i }
+            );
+            merkle.map_err(|_| VerificationError::MerklePath { round, query: i }.into())
+        },
+    )
+}
+
+/// Checks that every Merkle path of the `rounds` query rounds has the depth of its round's tree,
+/// one level shallower per fold, before any path is hashed.
+fn check_path_lengths<H: MerkleHasher>(
+    commitment: &FriCommitment<H>,
+    merkle_paths: &[FriRoundMerklePaths<H>],
+    rounds: usize,
+) -> Result<()> {
+    ensure!(
+        merkle_paths.len() == rounds,
+        "Proof has Merkle paths for {} rounds, expected {rounds}",
+        merkle_paths.len()
+    );
+    for (round, paths) in merkle_paths.iter().enumerate() {
+        let depth = commitment.vector_commitment.depth.checked_sub(round);
+        for (query, path) in paths.iter().enumerate() {
+            ensure!(
+                Some(path.len()) == depth,
+                VerificationError::MerklePath { round, query }
+            );
+        }
+    }
+    Ok(())
+}
 
-    
-    let mut folded_symbols = Vec::new();
+/// Checks that the proof carries queried symbols and Merkle paths for each of the `rounds`
+/// folding rounds, so no round indexes past the end of either.
+fn check_query_round_counts<H: MerkleHasher>(fri: &FriQueryData<H>, rounds: usize) -> Result<()> {
+    for (name, len) in [
+        ("rounds of queried symbols", fri.queried_symbols.len()),
+        ("rounds of Merkle paths", fri.merkle_paths.len()),
+    ] {
+        ensure!(
+            len == rounds,
+            "Proof has {len} {name}, expected {rounds}, one per folding round"
+        );
+    }
+    Ok(())
+}
+
+/// Query-phase traversal shared by [`check_queries`] and [`verify_detailed`]: hands every
+/// query's check in every round to `on_check` together with its Merkle verification result, and
+/// stops at the first error `on_check` returns.
+fn walk_queries<N, C, H>(
+    commitment: &FriCommitment<H>,
+    fri: FriQueryData<H>,
+    random_point: &[BinaryField128b],
+    current_queries: Vec<usize>,
+    ntt: &N,
+    ctx: &VerifyContext,
+    mut on_check: C,
+) -> Result<Vec<BinaryField128b>>
+where
+    N: FoldTwiddles + ?Sized,
+    C: FnMut(usize, usize, Result<()>, QueryCheck) -> Result<()>,
+    H: MerkleHasher,
+{
+    check_query_round_counts(&fri, random_point.len())?;
+    let mut walk = QueryWalk::new(current_queries);
+    for (round, r) in random_point.iter().enumerate() {
+        walk.round(commitment, &fri, round, *r, ntt, ctx, &mut on_check)?;
+    }
+    Ok(walk.folded_symbols.current)
+}
+
+/// Queries and their folded symbols carried from one folding round of the query phase to the next.
+struct QueryWalk {
+    queries: Vec<usize>,
+    folded_symbols: FoldBuffers,
+}
+
+impl QueryWalk {
+    fn new(queries: Vec<usize>) -> QueryWalk {
+        QueryWalk {
+            folded_symbols: FoldBuffers::new(queries.len()),
+            queries,
+        }
+    }
+
+    /// Checks every query of folding round `round`, whose challenge is `r`.
+    #[allow(clippy::too_many_arguments)]
+    fn round<N, C, H>(
+        &mut self,
+        commitment: &FriCommitment<H>,
+        fri: &FriQueryData<H>,
+        round: usize,
+        r: BinaryField128b,
+        ntt: &N,
+        ctx: &VerifyContext,
+        on_check: &mut C,
+    ) -> Result<()>
+    where
+        N: FoldTwiddles + ?Sized,
+        C: FnMut(usize, usize, Result<()>, QueryCheck) -> Result<()>,
+        H: MerkleHasher,
+    {
+        ensure!(
+            fri.queried_symbols[round].len() == self.queries.len()
+                && fri.merkle_paths[round].len() == self.queries.len(),
+            "round {round} opens {} symbols and {} paths for {} queries",
+            fri.queried_symbols[round].len(),
+            fri.merkle_paths[round].len(),
+            self.queries.len()
+        );
+        let folded_symbols = &mut self.folded_symbols;
 
-    for round in 0..rounds {
         // Choose the commitment: root for round 0, previous oracle thereafter.
         let oracle = match round {
             0 => &commitment.vector_commitment,
-            _ => &eval_proof.fri_oracles[round - 1],
+            _ => &fri.oracles[round - 1],
         };
+        check_coset_offset(commitment)?;
+        // Leaves are stored in the committed layout; the twiddles are those of the domain index.
+        let num_pairs = 1usize.checked_shl(oracle.depth as u32).unwrap_or(0);
+        let coset_mask = coset_index_mask(num_pairs, commitment.coset_offset);
 
-        folded_symbols = multizip((
-            current_queries.iter_mut(),             // queries we mutate in-place
-            &eval_proof.fri_queried_symbols[round], // (s0, s1) pairs
-            &eval_proof.fri_merkle_paths[round],    // Merkle paths
+        for (i, (query, &(s0, s1), merkle_path)) in multizip((
+            self.queries.iter_mut(),     // queries we mutate in-place
+            &fri.queried_symbols[round], // (s0, s1) pairs
+            &fri.merkle_paths[round],    // Merkle paths
         ))
         .enumerate()
-        .map(|(i, (query, &(s0, s1), merkle_path))| {
-            let hash = hash_tuple(&(s0, s1));
+        {
+            let hash = H::hash_leaf(&[s0, s1]);
 
-            match round {
+            let consistent = match round {
                 // First round: no consistency check yet.
-                0 => (),
+                0 => true,
                 // Later rounds: check consistency, then step up the tree.
                 _ => {
                     let expected = match *query & 1 {
                         1 => s1,
                         _ => s0,
                     };
-                    ensure!(
-                        folded_symbols[i] == expected,
-                        "Symbol not consistent at query {i} in round {round}"
-                    );
-                    *query >>= 1; // move to parent index for next round
+                    *query = codeword_index_to_leaf_index(*query); // move to parent index for next round
+                    folded_symbols.current[i] == expected
                 }
-            }
+            };
 
             // Membership proof against the chosen oracle
-            verify_merkle_path(oracle, hash, *query, merkle_path)?;
+            let merkle =
+                verify_merkle_path_with::<H>(&oracle.root, oracle.depth, hash, *query, merkle_path);
 
-            // Fold this pair for use in the next round
-            Ok(fold(random_point[round], round, *query, s0, s1, ntt))
-        })
-        .collect::<anyhow::Result<Vec<_>>>()?;
+            // Fold this pair for use in the next round.
+            let folded = ctx.fold(r, round, *query ^ coset_mask, s0, s1, ntt);
+            folded_symbols.next.push(folded);
+
+            let check = QueryCheck {
+                leaf_index: *query,
+                merkle_path_ok: merkle.is_ok(),
+                consistent,
+                folded,
+            };
+            on_check(round, i, merkle, check)?;
+        }
+
+        folded_symbols.advance();
+        Ok(())
+    }
+}
+
+/// Outcome of one query in one folding round, as collected by [`verify_detailed`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueryCheck {
+    /// Leaf opened in this round's oracle.
+    pub leaf_index: usize,
+    pub merkle_path_ok: bool,
+    /// Whether the opened pair agrees with the previous round's fold; always true in round 0.
+    pub consistent: bool,
+    /// The opened pair folded with this round's challenge.
+    pub folded: BinaryField128b,
+}
+
+impl QueryCheck {
+    pub fn is_ok(&self) -> bool {
+        self.merkle_path_ok && self.consistent
     }
+}
+
+/// Every query-phase check of a proof, recorded instead of aborting at the first failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// `rounds[round][i]` is query `i`'s check in folding round `round`.
+    pub rounds: Vec<Vec<QueryCheck>>,
+    /// Whether each query's last fold equals the proof's final folded value.
+    pub final_symbols_ok: Vec<bool>,
+}
 
-    for symbol in folded_symbols {
-        assert_eq!(symbol, eval_proof.final_folded_value)
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.rounds.iter().flatten().all(QueryCheck::is_ok)
+            && self.final_symbols_ok.iter().all(|ok| *ok)
     }
 
-    Ok(())
+    /// `(round, query)` of every failed check.
+    pub fn failures(&self) -> Vec<(usize, usize)> {
+        self.rounds
+            .iter()
+            .enumerate()
+            .flat_map(|(round, checks)| {
+                checks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, check)| !check.is_ok())
+                    .map(move |(i, _)| (round, i))
+            })
+            .collect()
+    }
 }
 
-pub fn compute_eq_table(r: &[BinaryField128b]) -> Vec<BinaryField128b> {
-    //Initialize eq with (1- r[0]) and r[0]
-    let mut eq = [BinaryField128b::ONE - r[0], r[0]].to_vec();
-    //Iterate over the length of the r vector
-    for k in 1..r.len() {
-        let temp = eq;
-        //initialize table of double size with zero
+/// Debugging counterpart of [`verify`]: replays the commit phase as usual, then records the
+/// outcome of every query in every folding round rather than stopping at the first failure.
+///
+/// Errors only when the commit phase itself is rejected, since the queries derive from it.
+pub fn verify_detailed(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: &EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<VerifyReport, VerificationError> {
+    verifying(|| {
+        let ctx = VerifyContext::default();
+        let (random_point, queries) = replay_commit_phase_with_context(
+            commitment, eval_point, eval, eval_proof, ntt, channel, &ctx,
+        )?;
 
-        eq = vec![BinaryField128b::ZERO; temp.len() * 2];
+        let mut rounds = vec![Vec::with_capacity(queries.len()); random_point.len()];
+        let folded_symbols = walk_queries(
+            commitment,
+            eval_proof.into(),
+            &random_point,
+            queries,
+            ntt,
+            &ctx,
+            |round, _, _, check| {
+                rounds[round].push(check);
+                Ok(())
+            },
+        )?;
 
-        for i in 0..temp.len() {
-            eq[i | (1 << k)] = temp[i] * r[k];
-            eq[i] = temp[i] - eq[i | (1 << k)];
-        }
+        Ok(VerifyReport {
+            rounds,
+            final_symbols_ok: folded_symbols
+                .iter()
+                .map(|symbol| *symbol == eval_proof.final_folded_value)
+                .collect(),
+        })
+    })
+}
+
+/// Checks every query's final fold against the claimed constant of the last folded codeword.
+fn check_final_symbols(
+    folded_symbols: &[BinaryField128b],
+    final_folded_value: BinaryField128b,
+) -> Result<()> {
+    for (i, symbol) in folded_symbols.iter().enumerate() {
+        ensure!(
+            *symbol == final_folded_value,
+            VerificationError::FinalFoldMismatch { query: i }
+        );
     }
-    eq
+    Ok(())
 }
 
-pub fn compute_eq_tower_ind(
-    r_init: &[BinaryField128b],
-    r_sum: &[BinaryField128b],
-    eq_batch: &[BinaryField128b],
-) -> BinaryField128b {
-    assert_eq!(r_init.len(), r_sum.len());
+/// Runs a standalone FRI low-degree test against `commitment`, accepting only if the committed
+/// codeword is close to a Reed–Solomon codeword. No evaluation claim is involved.
+pub fn verify_proximity(
+    commitment: &FriCommitment,
+    proof: &ProximityProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        ensure!(
+            commitment.symbols_per_leaf == DEFAULT_SYMBOLS_PER_LEAF,
+            "FRI queries open symbol pairs; commitment uses {} symbols per leaf",
+            commitment.symbols_per_leaf
+        );
+        check_coset_offset(commitment)?;
+        let rounds = proximity_rounds(commitment)?;
+        ensure!(
+            proof.fri_oracles.len() == rounds
+                && proof.fri_queried_symbols.len() == rounds
+                && proof.fri_merkle_paths.len() == rounds,
+            "Proximity proof does not cover {rounds} folding rounds"
+        );
 
-    let mut eval = vec![BinaryField128b::ZERO; 128];
+        channel.observe_fri_commitment(commitment);
 
-    eval[0] = BinaryField128b::ONE;
+        let mut random_point = Vec::with_capacity(rounds);
+        for (round, oracle) in proof.fri_oracles.iter().enumerate() {
+            random_point.push(channel.get_random_point()?);
 
-    for i in 0..r_init.len() {
-        for _ in 0..128 {
-            let temp = eval[i] * r_init[i];
-            eval[i] += temp
+            let previous_oracle = match round {
+                0 => &commitment.vector_commitment,
+                _ => &proof.fri_oracles[round - 1],
+            };
+            ensure!(
+                oracle.root != previous_oracle.root,
+                "FRI oracle of round {round} repeats the previous root; proof is degenerate or malicious"
+            );
+            channel.observe_vector_commitment(oracle);
         }
 
-        eval = switch_view(&eval);
+        channel.observe_field_elem(proof.final_folded_value)?;
+        let queries = channel
+            .gen_queries(rounds + LOG_RATE)?
+            .into_iter()
+            .map(codeword_index_to_leaf_index)
+            .collect();
 
-        for _ in 0..128 {
-            let temp = eval[i] * r_sum[i];
-            eval[i] += temp
+        let folded_symbols = check_queries(
+            commitment,
+            proof.into(),
+            &random_point,
+            queries,
+            ntt,
+            &VerifyContext::default(),
+        )?;
+        check_final_symbols(&folded_symbols, proof.final_folded_value)
+    })
+}
+
+/// Per-query folded symbols of the previous round (`current`) and the round being checked
+/// (`next`), swapped between rounds so both allocations are reused rather than rebuilt.
+struct FoldBuffers {
+    current: Vec<BinaryField128b>,
+    next: Vec<BinaryField128b>,
+}
+
+impl FoldBuffers {
+    fn new(num_queries: usize) -> FoldBuffers {
+        FoldBuffers {
+            current: Vec::with_capacity(num_queries),
+            next: Vec::with_capacity(num_queries),
         }
+    }
 
-        eval = switch_view(&eval);
+    fn advance(&mut self) {
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.next.clear();
     }
+}
+
+/// Checks `eval = p(point)` and `derivative = ∂p/∂x_{var_index}(point)` against a
+/// [`DerivativeProof`], verifying both restricted openings on the same transcript.
+pub fn verify_with_derivative(
+    commitment: &FriCommitment,
+    point: &[BinaryField128b],
+    var_index: usize,
+    eval: BinaryField128b,
+    derivative: BinaryField128b,
+    proof: DerivativeProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        let point_at_zero = restrict_point(point, var_index, BinaryField128b::ZERO)?;
+        let point_at_one = restrict_point(point, var_index, BinaryField128b::ONE)?;
+
+        ensure!(
+            derivative == proof.eval_at_one - proof.eval_at_zero,
+            "Derivative claim does not match the restricted openings"
+        );
+        ensure!(
+            eval == proof.eval_at_zero + point[var_index] * derivative,
+            "Evaluation claim does not match the restricted openings"
+        );
+
+        verify(
+            commitment,
+            &point_at_zero,
+            proof.eval_at_zero,
+            proof.proof_at_zero,
+            ntt,
+            channel,
+        )?;
+        Ok(verify(
+            commitment,
+            &point_at_one,
+            proof.eval_at_one,
+            proof.proof_at_one,
+            ntt,
+            channel,
+        )?)
+    })
+}
+
+/// [`verify`] an opening of a polynomial in `eval_point.len()` variables whose commitment, from
+/// [`commit_effective`](crate::prover::commit_effective), holds only its lowest
+/// `commitment.variables`: the polynomial is constant in the rest, so the proof opens the
+/// committed truncation at the leading coordinates of `eval_point`.
+pub fn verify_effective(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        ensure!(
+            eval_point.len() >= commitment.variables,
+            "{}-coordinate point for a commitment in {} variables",
+            eval_point.len(),
+            commitment.variables
+        );
+        Ok(verify(
+            commitment,
+            &eval_point[..commitment.variables],
+            eval,
+            eval_proof,
+            ntt,
+            channel,
+        )?)
+    })
+}
+
+/// [`verify`] against a commitment given only by its root bytes and shape parameters.
+pub fn verify_from_root(
+    root: [u8; 32],
+    depth: usize,
+    packing_factor: usize,
+    variables: usize,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    let commitment = FriCommitment::from_parts(root, depth, packing_factor, variables);
+    verify(&commitment, eval_point, eval, eval_proof, ntt, channel)
+}
+
+/// Verifies the openings of [`prove_heterogeneous`](crate::prover::prove_heterogeneous), each at
+/// its polynomial's original-size point, as one [`verify_multi`] of the stacked polynomial.
+pub fn verify_heterogeneous(
+    commitment: &HeterogeneousCommitment,
+    points: &[Vec<BinaryField128b>],
+    evals: &[BinaryField128b],
+    proof: &MultiEvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        ensure!(
+            points.len() == commitment.variables.len() && evals.len() == points.len(),
+            "expected {} points and evaluations, got {} and {}",
+            commitment.variables.len(),
+            points.len(),
+            evals.len()
+        );
+
+        let padded_points = points
+            .iter()
+            .enumerate()
+            .map(|(i, point)| commitment.padded_point(i, point))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(verify_multi(
+            &commitment.commitment,
+            &padded_points,
+            evals,
+            proof,
+            ntt,
+            channel,
+        )?)
+    })
+}
 
-    compute_row_batch(eq_batch, &eval)
+/// Checks `eval = p(0, ..., 0)` against a [`ZeroOpening`] of the committed codeword.
+///
+/// Sound only against an honest committer. Unlike [`verify`] this runs no proximity test, and
+/// none could help: FRI only shows the commitment is close to a codeword, so a committer may
+/// corrupt position 0 and still pass every full opening. Open through [`verify`] at the zero
+/// point when the committer is not trusted.
+pub fn verify_at_zero(
+    commitment: &FriCommitment,
+    eval: BinaryField128b,
+    opening: &ZeroOpening,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        ensure!(
+            commitment.symbols_per_leaf == DEFAULT_SYMBOLS_PER_LEAF,
+            "zero openings reveal a symbol pair; commitment uses {} symbols per leaf",
+            commitment.symbols_per_leaf
+        );
+        ensure!(
+            commitment.coset_offset == 0,
+            "zero openings read the coset-0 block, which the commitment places at coset offset {}",
+            commitment.coset_offset
+        );
+        verify_merkle_path(
+            &commitment.vector_commitment,
+            hash_tuple(&opening.symbols),
+            codeword_index_to_leaf_index(0),
+            &opening.merkle_path,
+        )?;
+        ensure!(
+            constant_term(opening.symbols.0) == eval,
+            "Evaluation claim does not match the committed constant term"
+        );
+        Ok(())
+    })
+}
+
+/// Replays the statement and sum-check transcript of `eval_proof`, checking every sum-check round,
+/// and returns the folding challenges together with the round-0 leaf indices to query.
+pub(crate) fn replay_commit_phase<P, H>(
+    commitment: &FriCommitment<H>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: &EvalProof<H>,
+    ntt: &MultithreadedNTT<P>,
+    channel: &mut Channel,
+) -> Result<(Vec<BinaryField128b>, Vec<usize>)>
+where
+    BinaryField128b: ExtensionField<P>,
+    P: BinaryField,
+    H: MerkleHasher,
+{
+    replay_commit_phase_with_context(
+        commitment,
+        eval_point,
+        eval,
+        eval_proof,
+        ntt,
+        channel,
+        &VerifyContext::default(),
+    )
+}
+
+fn replay_commit_phase_with_context<N, H>(
+    commitment: &FriCommitment<H>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: &EvalProof<H>,
+    ntt: &N,
+    channel: &mut Channel,
+    ctx: &VerifyContext,
+) -> Result<(Vec<BinaryField128b>, Vec<usize>)>
+where
+    N: FoldTwiddles + ?Sized,
+    H: MerkleHasher,
+{
+    let random_point = replay_sum_check(commitment, eval_point, eval, eval_proof, channel, ctx)?;
+    let queries = finish_commit_phase(eval_proof, random_point.len(), ntt, channel)?;
+    Ok((random_point, queries))
+}
+
+/// Checks the prover's folding twiddles and draws the round-0 leaf indices to query.
+fn finish_commit_phase<N, H>(
+    eval_proof: &EvalProof<H>,
+    rounds: usize,
+    ntt: &N,
+    channel: &mut Channel,
+) -> Result<Vec<usize>>
+where
+    N: FoldTwiddles + ?Sized,
+    H: MerkleHasher,
+{
+    // An undersized NTT has no twiddles for the outer rounds, and a codeword of more than
+    // `2^usize::BITS` symbols has no addressable twiddle index.
+    ensure!(
+        rounds + LOG_RATE <= ntt.log_domain_size().min(usize::BITS as usize),
+        VerificationError::TwiddleMismatch
+    );
+    ensure!(
+        eval_proof.twiddle_fingerprint == twiddle_fingerprint(ntt, rounds),
+        VerificationError::TwiddleMismatch
+    );
+
+    channel.observe_field_elem(eval_proof.final_folded_value)?;
+
+    let queries = channel
+        .gen_queries(rounds + LOG_RATE)?
+        .into_iter()
+        .map(codeword_index_to_leaf_index)
+        .collect();
+
+    Ok(queries)
+}
+
+/// Checks the statement's shape against the commitment and returns the ring-switching split `tau`.
+fn check_statement<H: MerkleHasher>(
+    commitment: &FriCommitment<H>,
+    eval_point: &[BinaryField128b],
+    upper_partial_evals: &[BinaryField128b],
+) -> Result<usize> {
+    ensure!(
+        eval_point.len() == commitment.variables(),
+        "Evaluation point has {} coordinates, commitment has {} variables",
+        eval_point.len(),
+        commitment.variables()
+    );
+
+    ensure!(
+        commitment.symbols_per_leaf == DEFAULT_SYMBOLS_PER_LEAF,
+        "FRI queries open symbol pairs; commitment uses {} symbols per leaf",
+        commitment.symbols_per_leaf
+    );
+    check_coset_offset(commitment)?;
+
+    // The split is read from the commitment so a verifier built with another default cannot
+    // silently split the point elsewhere; a BinaryField128b symbol packs at most 2^TAU bits.
+    let tau = commitment.tau();
+    ensure!(
+        tau <= TAU,
+        "Commitment uses tau = {tau}, ring switching into BinaryField128b allows at most {TAU}"
+    );
+    ensure!(
+        eval_point.len() > tau,
+        "Evaluation point has {} coordinates, need more than tau = {tau}",
+        eval_point.len()
+    );
+    ensure!(
+        upper_partial_evals.len() == 1 << tau,
+        "Proof carries {} partial evaluations, expected {}",
+        upper_partial_evals.len(),
+        1 << tau
+    );
+
+    Ok(tau)
+}
+
+/// Checks that the commitment's coset-0 block is one of the `RATE` codeword blocks.
+fn check_coset_offset<H: MerkleHasher>(commitment: &FriCommitment<H>) -> Result<()> {
+    ensure!(
+        commitment.coset_offset < RATE,
+        "Commitment places the coset-0 block at coset offset {}, must be below RATE = {RATE}",
+        commitment.coset_offset
+    );
+    Ok(())
+}
+
+/// Checks `eval` against the proof's partial evaluations, `Σ_i eq(left, i) · partial_i`.
+fn check_eval_binding(
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    upper_partial_evals: &[BinaryField128b],
+    tau: usize,
+    ctx: &VerifyContext,
+) -> Result<()> {
+    let left = &eval_point[..tau];
+
+    let left_eq = match ctx.eq_tables {
+        Some(tables) => {
+            ensure!(
+                tables.point() == eval_point,
+                "Eq tables were built for a different evaluation point"
+            );
+            Cow::Borrowed(tables.left_eq.as_slice())
+        }
+        None => Cow::Owned(ctx.eq_table(left)),
+    };
+
+    let mut derived_eval = BinaryField128b::ZERO;
+
+    for i in 0..1 << tau {
+        derived_eval += ctx.mul(left_eq[i], upper_partial_evals[i]);
+    }
+
+    ensure!(derived_eval == eval, VerificationError::EvalMismatch);
+
+    Ok(())
+}
+
+/// The prover's row-batched eq table after every sum-check fold: `Σ_i batching_eq[i] · c_i`,
+/// where `Σ_i c_i ⊗ β_i = Π_j (r_j ⊗ right_j + (1 + r_j) ⊗ (1 + right_j))` in the tensor algebra
+/// `F128 ⊗ F128` and `β_i` is the F_2 basis
+/// [`row_batch`](crate::utils::mle::LagrangeBases::row_batch) decomposes into.
+///
+/// Each factor equals `1 ⊗ (1 + right_j) + r_j ⊗ 1`, so a round costs one product per limb for
+/// each term.
+fn folded_tensored_eq(
+    right: &[BinaryField128b],
+    random_point: &[BinaryField128b],
+    batching_eq: &[BinaryField128b],
+    ctx: &VerifyContext,
+) -> BinaryField128b {
+    let bits = |elem: BinaryField128b| {
+        <BinaryField128b as ExtensionField<BinaryField1b>>::iter_bases(&elem)
+            .map(|bit| bit == BinaryField1b::ONE)
+            .collect::<Vec<bool>>()
+    };
+    let basis: Vec<BinaryField128b> = (0..BinaryField128b::N_BITS)
+        .map(|i| {
+            let mut unit = vec![BinaryField1b::ZERO; BinaryField128b::N_BITS];
+            unit[i] = BinaryField1b::ONE;
+            BinaryField128b::from_bases(&unit).expect("one base element per bit")
+        })
+        .collect();
+
+    // 1 ⊗ 1, with limbs indexed by the basis of the right factor.
+    let mut limbs: Vec<BinaryField128b> = bits(BinaryField128b::ONE)
+        .into_iter()
+        .map(|bit| match bit {
+            true => BinaryField128b::ONE,
+            false => BinaryField128b::ZERO,
+        })
+        .collect();
+
+    for (r, right) in random_point.iter().zip(right) {
+        let factor = BinaryField128b::ONE + *right;
+        let mut next: Vec<BinaryField128b> = limbs.iter().map(|limb| ctx.mul(*r, *limb)).collect();
+        for (limb, beta) in limbs.iter().zip(&basis) {
+            for (m, bit) in bits(ctx.mul(*beta, factor)).into_iter().enumerate() {
+                if bit {
+                    next[m] += *limb;
+                }
+            }
+        }
+        limbs = next;
+    }
+
+    ctx.dot_product(batching_eq, &limbs)
+}
+
+/// Binds the sum-check to the FRI oracles: the last round's claim must be the final folded value,
+/// the repacked polynomial at the folding challenges, times the [`folded_tensored_eq`].
+fn check_ring_switch_binding(
+    sum_check_claim: BinaryField128b,
+    final_folded_value: BinaryField128b,
+    right: &[BinaryField128b],
+    random_point: &[BinaryField128b],
+    batching_eq: &[BinaryField128b],
+    ctx: &VerifyContext,
+) -> Result<()> {
+    let tensored_eq = folded_tensored_eq(right, random_point, batching_eq, ctx);
+    ensure!(
+        sum_check_claim == ctx.mul(final_folded_value, tensored_eq),
+        VerificationError::RingSwitchBinding
+    );
+    Ok(())
+}
+
+/// Checks `eval` against the proof's partial evaluations and replays every sum-check round,
+/// observing each round's FRI oracle, and returns the folding challenges.
+fn replay_sum_check<H: MerkleHasher>(
+    commitment: &FriCommitment<H>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: &EvalProof<H>,
+    channel: &mut Channel,
+    ctx: &VerifyContext,
+) -> Result<Vec<BinaryField128b>> {
+    let tau = check_statement(commitment, eval_point, &eval_proof.upper_partial_evals)?;
+    if let Some(point_commitment) = ctx.point_commitment {
+        point_commitment.check_opening(eval_point)?;
+    }
+    if !ctx.options.skip_eval_reconstruction {
+        check_eval_binding(eval_point, eval, &eval_proof.upper_partial_evals, tau, ctx)?;
+    }
+
+    channel.observe_fri_commitment(commitment);
+    match ctx.point_commitment {
+        Some(point_commitment) => channel.observe_point_commitment(point_commitment),
+        None => channel.observe_field_elems(eval_point)?,
+    }
+    channel.observe_field_elem(eval)?;
+
+    let right = &eval_point[tau..];
+
+    channel.observe_field_elems(&eval_proof.upper_partial_evals)?;
+    let tensor_batching_point = channel.get_random_points(TAU)?;
+
+    let batching_eq = match ctx.batching_eq {
+        Some(cached) => {
+            ensure!(
+                cached.point == tensor_batching_point,
+                "Cached batching eq table was built for a different batching point"
+            );
+            Cow::Borrowed(cached.table())
+        }
+        None => Cow::Owned(ctx.eq_table(&tensor_batching_point)),
+    };
+    // The row batch of `compute_row_batch`, through the context so that it is counted.
+    let sum_check_claim = ctx.dot_product(
+        &batching_eq,
+        ColumnView::from_partial_evals(&eval_proof.upper_partial_evals)?
+            .to_row_view()
+            .limbs(),
+    );
+
+    check_round_counts(eval_proof, right.len())?;
+    let (random_point, sum_check_claim) =
+        replay_rounds(commitment, eval_proof, sum_check_claim, channel, ctx)?;
+
+    check_ring_switch_binding(
+        sum_check_claim,
+        eval_proof.final_folded_value,
+        right,
+        &random_point,
+        &batching_eq,
+        ctx,
+    )?;
+
+    Ok(random_point)
+}
+
+/// Checks that `eval_proof` carries one sum-check oracle and FRI oracle per folding round, so
+/// the replay indexes no short vector. The query data is counted by [`walk_queries`], which is
+/// what reads it; callers replaying the commit phase alone may leave it empty.
+fn check_round_counts<H: MerkleHasher>(eval_proof: &EvalProof<H>, rounds: usize) -> Result<()> {
+    for (name, len) in [
+        ("sum-check oracles", eval_proof.sum_check_oracles.len()),
+        ("FRI oracles", eval_proof.fri_oracles.len()),
+    ] {
+        ensure!(
+            len == rounds,
+            "Proof has {len} {name}, expected {rounds}, one per folding round"
+        );
+    }
+    Ok(())
+}
+
+/// Replays every sum-check round of `eval_proof` from `sum_check_claim`, observing each round's
+/// FRI oracle, and returns the folding challenges with the final claim.
+fn replay_rounds<H: MerkleHasher>(
+    commitment: &FriCommitment<H>,
+    eval_proof: &EvalProof<H>,
+    mut sum_check_claim: BinaryField128b,
+    channel: &mut Channel,
+    ctx: &VerifyContext,
+) -> Result<(Vec<BinaryField128b>, BinaryField128b)> {
+    let rounds = eval_proof.sum_check_oracles.len();
+    let mut random_point = Vec::with_capacity(rounds);
+    for round in 0..rounds {
+        let oracle = &eval_proof.sum_check_oracles[round];
+
+        let (sum, coeffs) = oracle_sum_and_coeffs(eval_proof, round, ctx)?;
+        ensure!(
+            sum == sum_check_claim,
+            VerificationError::SumCheckMismatch { round }
+        );
+
+        channel.observe_oracle_coeffs(&coeffs)?;
+
+        let r = channel.get_random_point()?;
+
+        check_fresh_oracle(commitment, eval_proof, round)?;
+        channel.observe_vector_commitment(&eval_proof.fri_oracles[round]);
+        sum_check_claim = oracle_at(eval_proof.oracle_form, oracle, r, ctx);
+        random_point.push(r);
+    }
+    Ok((random_point, sum_check_claim))
+}
+
+/// Round `round`'s oracle summed over `{0, 1}`, and its coefficients as the transcript absorbs them.
+fn oracle_sum_and_coeffs<'p, H: MerkleHasher>(
+    eval_proof: &'p EvalProof<H>,
+    round: usize,
+    ctx: &VerifyContext,
+) -> Result<(BinaryField128b, Cow<'p, Vec<BinaryField128b>>)> {
+    let oracle = &eval_proof.sum_check_oracles[round];
+    Ok(match eval_proof.oracle_form {
+        OracleForm::Coeffs => (
+            ctx.evaluate(oracle, BinaryField128b::ZERO)
+                + ctx.evaluate(oracle, BinaryField128b::ONE),
+            Cow::Borrowed(&oracle.coeffs),
+        ),
+        OracleForm::Evals => {
+            let &[e0, e1, e_inf] = oracle.coeffs.as_slice() else {
+                anyhow::bail!(
+                    "Sum-check oracle of round {round} has {} evaluations, expected 3",
+                    oracle.coeffs.len()
+                );
+            };
+            // The transcript absorbs coefficients in either form; these need additions only.
+            (e0 + e1, Cow::Owned(vec![e0, e0 + e1 + e_inf, e_inf]))
+        }
+    })
+}
+
+/// The oracle evaluated at the round challenge `r`: the next round's sum-check claim.
+fn oracle_at(
+    form: OracleForm,
+    oracle: &Univariate,
+    r: BinaryField128b,
+    ctx: &VerifyContext,
+) -> BinaryField128b {
+    match form {
+        OracleForm::Coeffs => ctx.evaluate(oracle, r),
+        OracleForm::Evals => {
+            // p(r) = p(0) + r (p(0) + p(1)) + p(∞) (r^2 + r) in characteristic 2.
+            let (e0, e1, e_inf) = (oracle.coeffs[0], oracle.coeffs[1], oracle.coeffs[2]);
+            e0 + ctx.mul(r, e0 + e1) + ctx.mul(e_inf, ctx.mul(r, r) + r)
+        }
+    }
+}
+
+/// Each fold halves the codeword, so its tree is shallower and its root differs from the
+/// previous one with overwhelming probability; an equal root means a reused, not folded, oracle.
+fn check_fresh_oracle<H: MerkleHasher>(
+    commitment: &FriCommitment<H>,
+    eval_proof: &EvalProof<H>,
+    round: usize,
+) -> Result<()> {
+    let previous_oracle = match round {
+        0 => &commitment.vector_commitment,
+        _ => &eval_proof.fri_oracles[round - 1],
+    };
+    ensure!(
+        eval_proof.fri_oracles[round].root != previous_oracle.root,
+        "FRI oracle of round {round} repeats the previous root; proof is degenerate or malicious"
+    );
+    Ok(())
+}
+
+/// [`verify`] with the sum-check and query checks of each round run together: round `N`'s
+/// sum-check step is followed at once by round `N`'s query checks, so a bad round fails before
+/// later rounds are examined.
+///
+/// Queries depend on the whole transcript, so the challenges and queries are derived first by
+/// replaying every observation exactly as [`verify`] does; only the checks are reordered, and
+/// none of them feeds the transcript, so soundness is unchanged.
+pub fn verify_interleaved(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: &EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        let ctx = VerifyContext::default();
+        let tau = check_statement(commitment, eval_point, &eval_proof.upper_partial_evals)?;
+        check_eval_binding(eval_point, eval, &eval_proof.upper_partial_evals, tau, &ctx)?;
+
+        let rounds = eval_point.len() - tau;
+        check_round_counts(eval_proof, rounds)?;
+
+        // Derive every challenge and the queries without checking anything.
+        channel.observe_fri_commitment(commitment);
+        channel.observe_field_elems(eval_point)?;
+        channel.observe_field_elem(eval)?;
+        channel.observe_field_elems(&eval_proof.upper_partial_evals)?;
+        let tensor_batching_point = channel.get_random_points(TAU)?;
+
+        let mut sums = Vec::with_capacity(rounds);
+        let mut random_point = Vec::with_capacity(rounds);
+        for round in 0..rounds {
+            let (sum, coeffs) = oracle_sum_and_coeffs(eval_proof, round, &ctx)?;
+            channel.observe_oracle_coeffs(&coeffs)?;
+            random_point.push(channel.get_random_point()?);
+            channel.observe_vector_commitment(&eval_proof.fri_oracles[round]);
+            sums.push(sum);
+        }
+        let queries = finish_commit_phase(eval_proof, rounds, ntt, channel)?;
+
+        // Check round by round.
+        let batching_eq = compute_eq_table(&tensor_batching_point);
+        let mut sum_check_claim = compute_row_batch(
+            &batching_eq,
+            &ColumnView::from_partial_evals(&eval_proof.upper_partial_evals)?,
+        );
+        let fri = FriQueryData::from(eval_proof);
+        check_query_round_counts(&fri, rounds)?;
+        check_path_lengths(commitment, fri.merkle_paths, rounds)?;
+        let mut walk = QueryWalk::new(queries);
+        let mut strict = |round: usize, i: usize, merkle: Result<()>, check: QueryCheck| {
+            ensure!(
+                check.consistent,
+                VerificationError::QueryInconsistent { round, query: i }
+            );
+            merkle.map_err(|_| VerificationError::MerklePath { round, query: i }.into())
+        };
+        for (round, &r) in random_point.iter().enumerate() {
+            ensure!(
+                sums[round] == sum_check_claim,
+                VerificationError::SumCheckMismatch { round }
+            );
+            sum_check_claim = oracle_at(
+                eval_proof.oracle_form,
+                &eval_proof.sum_check_oracles[round],
+                r,
+                &ctx,
+            );
+            check_fresh_oracle(commitment, eval_proof, round)?;
+
+            walk.round(commitment, &fri, round, r, ntt, &ctx, &mut strict)?;
+        }
+        check_ring_switch_binding(
+            sum_check_claim,
+            eval_proof.final_folded_value,
+            &eval_point[tau..],
+            &random_point,
+            &batching_eq,
+            &ctx,
+        )?;
+
+        check_final_symbols(&walk.folded_symbols.current, eval_proof.final_folded_value)
+    })
+}
+
+/// Verifies a [`LinearCombinationProof`] that `Σ_i coeffs[i] · p_i(point) = target` for the
+/// polynomials committed in `commitments`.
+///
+/// The combined codeword is never committed: its round-0 query symbols are recombined from
+/// openings of every commitment with the public `coeffs`, and folding continues from there.
+pub fn verify_linear_combination(
+    commitments: &[FriCommitment],
+    coeffs: &[BinaryField128b],
+    point: &[BinaryField128b],
+    target: BinaryField128b,
+    proof: &LinearCombinationProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        let ctx = VerifyContext::default();
+        let first = commitments.first().context("no commitments to combine")?;
+        ensure!(
+            coeffs.len() == commitments.len()
+                && proof.partial_evals.len() == commitments.len()
+                && proof.committed_symbols.len() == commitments.len()
+                && proof.committed_paths.len() == commitments.len(),
+            "Proof and coefficients do not cover {} commitments",
+            commitments.len()
+        );
+        let tau = check_statement(first, point, &proof.combined.upper_partial_evals)?;
+        for (j, commitment) in commitments.iter().enumerate() {
+            check_statement(commitment, point, &proof.combined.upper_partial_evals)
+                .with_context(|| format!("commitment {j}"))?;
+            ensure!(
+                commitment.coset_offset == 0,
+                "Combined openings fold the default codeword layout; commitment {j} uses coset offset {}",
+                commitment.coset_offset
+            );
+            ensure!(
+                commitment.tau == TAU,
+                "Combined openings split the point at TAU = {TAU}, commitment {j} uses tau = {}",
+                commitment.tau
+            );
+        }
+
+        // The combined partial evaluations must be the public combination of the individual ones.
+        let mut combined_partials = vec![BinaryField128b::ZERO; 1 << tau];
+        for (j, (partials, c)) in proof.partial_evals.iter().zip(coeffs).enumerate() {
+            ensure!(
+                partials.len() == 1 << tau,
+                "Polynomial {j} has {} partial evaluations, expected {}",
+                partials.len(),
+                1 << tau
+            );
+            for (combined, partial) in combined_partials.iter_mut().zip(partials) {
+                *combined += *c * *partial;
+            }
+        }
+        ensure!(
+            combined_partials == proof.combined.upper_partial_evals,
+            "Combined partial evaluations are not the coefficients' combination"
+        );
+        check_eval_binding(
+            point,
+            target,
+            &proof.combined.upper_partial_evals,
+            tau,
+            &ctx,
+        )?;
+
+        let rounds = point.len() - tau;
+        let combined = &proof.combined;
+        ensure!(
+            rounds == combined.sum_check_oracles.len()
+                && rounds == combined.fri_oracles.len()
+                && rounds == combined.fri_queried_symbols.len()
+                && rounds == combined.fri_merkle_paths.len(),
+            "Proof does not cover {rounds} sum-check rounds"
+        );
+
+        for commitment in commitments {
+            channel.observe_fri_commitment(commitment);
+        }
+        channel.observe_field_elems(coeffs)?;
+        channel.observe_field_elems(point)?;
+        channel.observe_field_elem(target)?;
+        for partials in &proof.partial_evals {
+            channel.observe_field_elems(partials)?;
+        }
+
+        let batching_eq = compute_eq_table(&channel.get_random_points(TAU)?);
+        let mut sum_check_claim = BinaryField128b::ZERO;
+        for (partials, c) in proof.partial_evals.iter().zip(coeffs) {
+            sum_check_claim +=
+                *c * compute_row_batch(&batching_eq, &ColumnView::from_partial_evals(partials)?);
+        }
+
+        let (random_point, sum_check_claim) =
+            replay_rounds(first, combined, sum_check_claim, channel, &ctx)?;
+        check_ring_switch_binding(
+            sum_check_claim,
+            combined.final_folded_value,
+            &point[tau..],
+            &random_point,
+            &batching_eq,
+            &ctx,
+        )?;
+        let queries = finish_commit_phase(combined, rounds, ntt, channel)?;
+        check_path_lengths(first, &combined.fri_merkle_paths, rounds)?;
+
+        // Round 0: open every commitment and fold the recombined pair.
+        let mut walk = QueryWalk::new(queries);
+        for (j, (commitment, symbols, paths)) in multizip((
+            commitments,
+            &proof.committed_symbols,
+            &proof.committed_paths,
+        ))
+        .enumerate()
+        {
+            ensure!(
+                symbols.len() == walk.queries.len() && paths.len() == walk.queries.len(),
+                "Commitment {j} is opened at {} queries, expected {}",
+                symbols.len(),
+                walk.queries.len()
+            );
+            check_path_lengths(commitment, std::slice::from_ref(paths), 1)
+                .with_context(|| format!("commitment {j}"))?;
+            for (query, pair, path) in multizip((&walk.queries, symbols, paths)) {
+                verify_merkle_path(
+                    &commitment.vector_commitment,
+                    hash_tuple(pair),
+                    *query,
+                    path,
+                )
+                .with_context(|| format!("commitment {j}"))?;
+            }
+        }
+        for (i, query) in walk.queries.iter().enumerate() {
+            let (mut s0, mut s1) = (BinaryField128b::ZERO, BinaryField128b::ZERO);
+            for (symbols, c) in proof.committed_symbols.iter().zip(coeffs) {
+                s0 += ctx.mul(*c, symbols[i].0);
+                s1 += ctx.mul(*c, symbols[i].1);
+            }
+            walk.folded_symbols
+                .next
+                .push(ctx.fold(random_point[0], 0, *query, s0, s1, ntt));
+        }
+        walk.folded_symbols.advance();
+
+        let fri = FriQueryData::from(combined);
+        let mut strict = |round: usize, i: usize, merkle: Result<()>, check: QueryCheck| {
+            ensure!(
+                check.consistent,
+                VerificationError::QueryInconsistent { round, query: i }
+            );
+            merkle.map_err(|_| VerificationError::MerklePath { round, query: i }.into())
+        };
+        for (round, r) in random_point.iter().enumerate().skip(1) {
+            walk.round(first, &fri, round, *r, ntt, &ctx, &mut strict)?;
+        }
+
+        check_final_symbols(&walk.folded_symbols.current, combined.final_folded_value)
+    })
+}
+
+/// Verifies a [`SumProof`] that `Σ_x p(x) = sum` over the boolean hypercube for the polynomial
+/// committed in `commitment`: replays the sum-check down to a claim on `p(r)`, then checks the
+/// opening at `r` with [`verify`].
+pub fn verify_sum(
+    commitment: &FriCommitment,
+    sum: BinaryField128b,
+    proof: SumProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        ensure!(
+            proof.round_oracles.len() == commitment.variables,
+            "{} sum-check rounds for a {}-variable polynomial",
+            proof.round_oracles.len(),
+            commitment.variables
+        );
+
+        channel.observe_fri_commitment(commitment);
+        channel.observe_field_elem(sum)?;
+
+        let mut claim = sum;
+        let mut point = Vec::with_capacity(commitment.variables);
+        for (round, oracle) in proof.round_oracles.iter().enumerate() {
+            ensure!(
+                oracle.coeffs.len() == 2,
+                "round {round} polynomial has {} coefficients, expected 2",
+                oracle.coeffs.len()
+            );
+            ensure!(
+                oracle.evaluate(BinaryField128b::ZERO) + oracle.evaluate(BinaryField128b::ONE)
+                    == claim,
+                "sum-check round {round} does not reduce the claim"
+            );
+            channel.observe_oracle_coeffs(&oracle.coeffs)?;
+
+            let r = channel.get_random_point()?;
+            claim = oracle.evaluate(r);
+            point.push(r);
+        }
+
+        Ok(verify(
+            commitment,
+            &point,
+            claim,
+            proof.opening,
+            ntt,
+            channel,
+        )?)
+    })
+}
+
+/// Verifies a [`MultiEvalProof`] that `evals[k] = f(points[k])` for every `k`, with `f`
+/// committed in `commitment`: checks each point's evaluation against its partial evaluations,
+/// then the shared sum-check on the combined claim and the FRI queries once.
+pub fn verify_multi(
+    commitment: &FriCommitment,
+    points: &[Vec<BinaryField128b>],
+    evals: &[BinaryField128b],
+    multi_proof: &MultiEvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        let MultiEvalProof {
+            upper_partial_evals,
+            proof,
+        } = multi_proof;
+        ensure!(
+            !points.is_empty()
+                && points.len() == evals.len()
+                && points.len() == upper_partial_evals.len(),
+            "{} points for {} evaluations and {} sets of partial evaluations",
+            points.len(),
+            evals.len(),
+            upper_partial_evals.len()
+        );
+
+        let ctx = VerifyContext::default();
+        let mut tau = TAU;
+        for (point, &eval, partial_evals) in multizip((points, evals, upper_partial_evals)) {
+            tau = check_statement(commitment, point, partial_evals)?;
+            check_eval_binding(point, eval, partial_evals, tau, &ctx)?;
+        }
+        check_round_counts(proof, commitment.variables - tau)?;
+
+        channel.observe_fri_commitment(commitment);
+        for (point, &eval) in points.iter().zip(evals) {
+            channel.observe_field_elems(point)?;
+            channel.observe_field_elem(eval)?;
+        }
+        for partial_evals in upper_partial_evals {
+            channel.observe_field_elems(partial_evals)?;
+        }
+
+        let tensor_batching_point = channel.get_random_points(TAU)?;
+        let batching_eq = compute_eq_table(&tensor_batching_point);
+        let alphas = channel.get_random_points(points.len())?;
+
+        let mut sum_check_claim = BinaryField128b::ZERO;
+        for (partial_evals, &alpha) in upper_partial_evals.iter().zip(&alphas) {
+            sum_check_claim += alpha
+                * compute_row_batch(
+                    &batching_eq,
+                    &ColumnView::from_partial_evals(partial_evals)?,
+                );
+        }
+
+        let (random_point, sum_check_claim) =
+            replay_rounds(commitment, proof, sum_check_claim, channel, &ctx)?;
+        let tensored_eq = points
+            .iter()
+            .zip(&alphas)
+            .map(|(point, &alpha)| {
+                alpha * folded_tensored_eq(&point[tau..], &random_point, &batching_eq, &ctx)
+            })
+            .sum::<BinaryField128b>();
+        ensure!(
+            sum_check_claim == proof.final_folded_value * tensored_eq,
+            VerificationError::RingSwitchBinding
+        );
+
+        let queries = finish_commit_phase(proof, random_point.len(), ntt, channel)?;
+        let folded_symbols =
+            check_queries(commitment, proof.into(), &random_point, queries, ntt, &ctx)?;
+        check_final_symbols(&folded_symbols, proof.final_folded_value)
+    })
+}
+
+/// Verifies a [`prove_batch`](crate::prover::prove_batch) proof that `evals[i]` is the
+/// evaluation at `eval_point` of the `i`-th polynomial of a
+/// [`commit_batch`](crate::prover::commit_batch) commitment.
+pub fn verify_batch(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    evals: &[BinaryField128b],
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    let (point, claim) = batch_statement(commitment, eval_point, evals, channel)?;
+    verify(commitment, &point, claim, eval_proof, ntt, channel)
+}
+
+/// Verifies a [`ProductProof`] that `(p · q)(point) = v` for `p` committed in `commitment_p` and
+/// `q` in `commitment_q`: replays the cubic sum-check, checks its final claim against
+/// `eq(point, r) p(r) q(r)`, then both openings at `r` with [`verify`].
+pub fn verify_product(
+    commitment_p: &FriCommitment,
+    commitment_q: &FriCommitment,
+    point: &[BinaryField128b],
+    v: BinaryField128b,
+    proof: ProductProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        let variables = commitment_p.variables;
+        ensure!(
+            commitment_q.variables == variables
+                && point.len() == variables
+                && proof.round_oracles.len() == variables,
+            "{} sum-check rounds for a product of {variables}- and {}-variable polynomials at a \
+             {}-coordinate point",
+            proof.round_oracles.len(),
+            commitment_q.variables,
+            point.len()
+        );
+
+        channel.observe_fri_commitment(commitment_p);
+        channel.observe_fri_commitment(commitment_q);
+        channel.observe_field_elems(point)?;
+        channel.observe_field_elem(v)?;
+
+        let mut claim = v;
+        let mut challenges = Vec::with_capacity(variables);
+        for (round, oracle) in proof.round_oracles.iter().enumerate() {
+            ensure!(
+                oracle.coeffs.len() == 4,
+                "round {round} polynomial has {} coefficients, expected 4",
+                oracle.coeffs.len()
+            );
+            ensure!(
+                oracle.evaluate(BinaryField128b::ZERO) + oracle.evaluate(BinaryField128b::ONE)
+                    == claim,
+                VerificationError::SumCheckMismatch { round }
+            );
+            channel.observe_oracle_coeffs(&oracle.coeffs)?;
+
+            let r = channel.get_random_point()?;
+            claim = oracle.evaluate(r);
+            challenges.push(r);
+        }
+
+        let eq_at_r = point
+            .iter()
+            .zip(&challenges)
+            .map(|(&a, &b)| a * b + (BinaryField128b::ONE + a) * (BinaryField128b::ONE + b))
+            .product::<BinaryField128b>();
+        ensure!(
+            claim == eq_at_r * proof.p_eval * proof.q_eval,
+            "Final product claim does not match the claimed evaluations of p and q"
+        );
+
+        channel.observe_field_elem(proof.p_eval)?;
+        channel.observe_field_elem(proof.q_eval)?;
+        verify(
+            commitment_p,
+            &challenges,
+            proof.p_eval,
+            proof.p_opening,
+            ntt,
+            channel,
+        )
+        .context("opening of p failed")?;
+        verify(
+            commitment_q,
+            &challenges,
+            proof.q_eval,
+            proof.q_opening,
+            ntt,
+            channel,
+        )
+        .context("opening of q failed")?;
+        Ok(())
+    })
+}
+
+/// Verifies a proof assembled from parts delivered separately: the statement, sum-check and
+/// oracle data of `sum_check_part` with the query symbols and Merkle paths of `query_part`.
+///
+/// The queries are re-derived by replaying `sum_check_part`'s transcript, so query data from
+/// another proving run opens the wrong leaves or the wrong oracles and is rejected, as is query
+/// data covering fewer queries than the transcript asks for.
+pub fn verify_assembled(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    sum_check_part: &EvalProof,
+    query_part: &EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        let (random_point, queries) = replay_commit_phase_with_context(
+            commitment,
+            eval_point,
+            eval,
+            sum_check_part,
+            ntt,
+            channel,
+            &VerifyContext::default(),
+        )
+        .context("sum-check part")?;
+
+        let rounds = random_point.len();
+        ensure!(
+            query_part.fri_queried_symbols.len() == rounds
+                && query_part.fri_merkle_paths.len() == rounds,
+            "Query part covers {} rounds, transcript has {rounds}",
+            query_part.fri_queried_symbols.len()
+        );
+        for (round, (symbols, paths)) in query_part
+            .fri_queried_symbols
+            .iter()
+            .zip(&query_part.fri_merkle_paths)
+            .enumerate()
+        {
+            ensure!(
+                symbols.len() == queries.len() && paths.len() == queries.len(),
+                "Query part opens {} queries in round {round}, transcript draws {}",
+                symbols.len(),
+                queries.len()
+            );
+        }
+
+        let fri = FriQueryData {
+            oracles: &sum_check_part.fri_oracles,
+            queried_symbols: &query_part.fri_queried_symbols,
+            merkle_paths: &query_part.fri_merkle_paths,
+        };
+        let folded_symbols = check_queries(
+            commitment,
+            fri,
+            &random_point,
+            queries,
+            ntt,
+            &VerifyContext::default(),
+        )
+        .context("query part is not from the proving run of the sum-check part")?;
+        check_final_symbols(&folded_symbols, sum_check_part.final_folded_value)
+    })
+}
+
+/// Checks [`PositionOpening`]s of the codeword symbols at `indices` against `commitment`'s
+/// Merkle root, from [`ProverContext::open_positions`](crate::prover::ProverContext::open_positions).
+pub fn verify_positions(
+    commitment: &FriCommitment,
+    indices: &[usize],
+    openings: &[PositionOpening],
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        ensure!(
+            commitment.symbols_per_leaf == DEFAULT_SYMBOLS_PER_LEAF,
+            "Position openings hash symbol pairs; commitment uses {} symbols per leaf",
+            commitment.symbols_per_leaf
+        );
+        ensure!(
+            indices.len() == openings.len(),
+            "{} openings for {} positions",
+            openings.len(),
+            indices.len()
+        );
+
+        let depth = commitment.vector_commitment.depth;
+        for (&index, opening) in indices.iter().zip(openings) {
+            let leaf = codeword_index_to_leaf_index(index);
+            ensure!(
+                leaf.checked_shr(depth as u32).unwrap_or(0) == 0,
+                "Position {index} out of bounds for a tree of depth {depth}"
+            );
+            let pair = match index & 1 {
+                0 => (opening.value, opening.leaf_sibling),
+                _ => (opening.leaf_sibling, opening.value),
+            };
+            verify_merkle_path(
+                &commitment.vector_commitment,
+                hash_tuple(&pair),
+                leaf,
+                &opening.merkle_path,
+            )
+            .with_context(|| format!("opening of position {index}"))?;
+        }
+        Ok(())
+    })
+}
+
+/// [`verify`] with the proof's components held in separate buffers, as delivered over separate
+/// channels. Only the commit-phase parts, a few kilobytes, are copied; the query symbols and
+/// Merkle paths are read in place. `twiddle_fingerprint` and `oracle_form` are the proof's own,
+/// checked and interpreted exactly as [`verify`] does.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_parts(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    upper_partial_evals: &[BinaryField128b],
+    sum_check_oracles: &[Univariate],
+    fri_oracles: &[VectorCommitment],
+    final_folded_value: BinaryField128b,
+    fri_queried_symbols: &FriQueriedSymbols,
+    fri_merkle_paths: &FriMerkleProofs,
+    twiddle_fingerprint: Hash,
+    oracle_form: OracleForm,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        let commit_phase = EvalProof {
+            upper_partial_evals: upper_partial_evals.to_vec(),
+            sum_check_oracles: sum_check_oracles.to_vec(),
+            final_folded_value,
+            fri_oracles: fri_oracles.to_vec(),
+            fri_queried_symbols: Vec::new(),
+            fri_merkle_paths: Vec::new(),
+            twiddle_fingerprint,
+            oracle_form,
+        };
+        let ctx = VerifyContext::default();
+        let (random_point, queries) = replay_commit_phase_with_context(
+            commitment,
+            eval_point,
+            eval,
+            &commit_phase,
+            ntt,
+            channel,
+            &ctx,
+        )?;
+
+        ensure!(
+            fri_queried_symbols.len() == random_point.len()
+                && fri_merkle_paths.len() == random_point.len(),
+            "Query parts cover {} and {} rounds, transcript has {}",
+            fri_queried_symbols.len(),
+            fri_merkle_paths.len(),
+            random_point.len()
+        );
+        let fri = FriQueryData {
+            oracles: fri_oracles,
+            queried_symbols: fri_queried_symbols,
+            merkle_paths: fri_merkle_paths,
+        };
+        let folded_symbols = check_queries(commitment, fri, &random_point, queries, ntt, &ctx)?;
+        check_final_symbols(&folded_symbols, final_folded_value)
+    })
+}
+
+/// Checks a [`PrunedEvalProof`]: each round's multi-proof is expanded into the paths of the
+/// leaves the transcript queries, which are then checked as in [`verify`].
+pub fn verify_pruned(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    pruned: &PrunedEvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        let PrunedEvalProof {
+            proof,
+            fri_multi_proofs,
+        } = pruned;
+        let ctx = VerifyContext::default();
+        let (random_point, queries) = replay_commit_phase_with_context(
+            commitment, eval_point, eval, proof, ntt, channel, &ctx,
+        )?;
+
+        // Expansion allocates per level, so each oracle must sit one level below the previous tree.
+        for (round, oracle) in proof.fri_oracles.iter().enumerate() {
+            ensure!(
+                commitment.vector_commitment.depth.checked_sub(round + 1) == Some(oracle.depth),
+                "FRI oracle of round {round} has depth {}, expected {} levels below the commitment",
+                oracle.depth,
+                round + 1
+            );
+        }
+        let depths = std::iter::once(&commitment.vector_commitment)
+            .chain(&proof.fri_oracles)
+            .map(|oracle| oracle.depth);
+        let fri_merkle_paths = multizip((fri_multi_proofs, &proof.fri_queried_symbols, depths))
+            .enumerate()
+            .map(|(round, (multi_proof, symbols, depth))| {
+                let leaves: Vec<(usize, Hash)> = queries
+                    .iter()
+                    .zip(symbols)
+                    .map(|(query, pair)| (query >> round, hash_tuple(pair)))
+                    .collect();
+                multi_proof
+                    .expand_paths(&leaves, depth)
+                    .with_context(|| format!("Multi-proof of round {round}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(
+            fri_merkle_paths.len() == random_point.len(),
+            "Pruned proof opens {} of {} rounds",
+            fri_merkle_paths.len(),
+            random_point.len()
+        );
+
+        let fri = FriQueryData {
+            oracles: &proof.fri_oracles,
+            queried_symbols: &proof.fri_queried_symbols,
+            merkle_paths: &fri_merkle_paths,
+        };
+        let folded_symbols = check_queries(commitment, fri, &random_point, queries, ntt, &ctx)?;
+        check_final_symbols(&folded_symbols, proof.final_folded_value)
+    })
+}
+
+/// Verifies only the sum-check binding `eval` to the committed coefficients, skipping the FRI
+/// query phase and all Merkle work.
+///
+/// This proves the evaluation is correct only under the assumption that the commitment is an
+/// honestly encoded codeword, e.g. when its proximity is guaranteed by other means; without that
+/// assumption it proves nothing about the committed polynomial.
+pub fn verify_sumcheck_only<H: MerkleHasher>(
+    commitment: &FriCommitment<H>,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: &EvalProof<H>,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    replay_sum_check(
+        commitment,
+        eval_point,
+        eval,
+        eval_proof,
+        channel,
+        &VerifyContext::default(),
+    )
+    .map(|_| ())
+    .map_err(VerificationError::from)
+}
+
+/// Verification phase that first rejected a proof, as localized by [`verify_diagnostic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureStage {
+    /// The point, proof shape or NTT does not fit the commitment.
+    Statement,
+    /// The claimed evaluation disagrees with the proof's partial evaluations: the prover lied
+    /// about the evaluation.
+    EvaluationBinding,
+    /// A sum-check round is inconsistent with the claim it reduces.
+    SumCheck,
+    /// A FRI query failed: the committed word or a folded oracle is not close to a codeword.
+    Proximity,
+}
+
+/// Failure reported by [`verify_diagnostic`]: the phase that rejected and its error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnosis {
+    pub stage: FailureStage,
+    pub error: VerificationError,
+}
+
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} check failed: {}", self.stage, self.error)
+    }
+}
+
+impl std::error::Error for Diagnosis {}
+
+/// [`verify`], reporting the phase of the first rejecting check (see [`VerificationError::stage`])
+/// so an evaluation lie ([`FailureStage::EvaluationBinding`], [`FailureStage::SumCheck`]) can be
+/// told apart from a commitment far from any codeword ([`FailureStage::Proximity`]).
+pub fn verify_diagnostic(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: &EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), Diagnosis> {
+    verify_with_context(
+        commitment,
+        eval_point,
+        eval,
+        eval_proof,
+        ntt,
+        channel,
+        &VerifyContext::default(),
+    )
+    .map_err(|err| {
+        let error = VerificationError::from(err);
+        Diagnosis {
+            stage: error.stage(),
+            error,
+        }
+    })
+}
+
+/// Verifies `eval_proof` query by query, following each query through every round before moving
+/// to the next, and stops at the first failing check, reported as the [`VerificationError`]
+/// naming its round and query.
+///
+/// The sum-check transcript is still replayed first since it fixes the queries; every check of
+/// [`verify`] is performed on accept.
+pub fn verify_fail_fast(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: &EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    let (random_point, queries) = replay_commit_phase_with_context(
+        commitment,
+        eval_point,
+        eval,
+        eval_proof,
+        ntt,
+        channel,
+        &VerifyContext::default(),
+    )?;
+    let rounds = random_point.len();
+
+    verifying(|| {
+        ensure!(
+            eval_proof.fri_oracles.len() == rounds
+                && eval_proof.fri_queried_symbols.len() == rounds
+                && eval_proof.fri_merkle_paths.len() == rounds,
+            "Proof does not carry query data for {rounds} rounds"
+        );
+        for round in 0..rounds {
+            ensure!(
+                eval_proof.fri_queried_symbols[round].len() == queries.len()
+                    && eval_proof.fri_merkle_paths[round].len() == queries.len(),
+                "Round {round} does not carry {} queries",
+                queries.len()
+            );
+        }
+        check_path_lengths(commitment, &eval_proof.fri_merkle_paths, rounds)
+    })?;
+
+    for (query, &leaf_index) in queries.iter().enumerate() {
+        verify_query(
+            commitment,
+            eval_proof,
+            &random_point,
+            query,
+            leaf_index,
+            ntt,
+        )?;
+    }
+
+    std::result::Result::Ok(())
+}
+
+/// Follows a single query through every round of an already shape-checked proof.
+fn verify_query(
+    commitment: &FriCommitment,
+    eval_proof: &EvalProof,
+    random_point: &[BinaryField128b],
+    query: usize,
+    leaf_index: usize,
+    ntt: &dyn FoldTwiddles,
+) -> std::result::Result<(), VerificationError> {
+    let mut index = leaf_index;
+    let mut folded = None;
+
+    for (round, &r) in random_point.iter().enumerate() {
+        let oracle = match round {
+            0 => &commitment.vector_commitment,
+            _ => &eval_proof.fri_oracles[round - 1],
+        };
+        let (s0, s1) = eval_proof.fri_queried_symbols[round][query];
+
+        if let Some(folded) = folded {
+            let expected = match index & 1 {
+                1 => s1,
+                _ => s0,
+            };
+            if folded != expected {
+                return Err(VerificationError::QueryInconsistent { round, query });
+            }
+            index = codeword_index_to_leaf_index(index);
+        }
+
+        verify_merkle_path(
+            oracle,
+            hash_tuple(&(s0, s1)),
+            index,
+            &eval_proof.fri_merkle_paths[round][query],
+        )
+        .map_err(|_| VerificationError::MerklePath { round, query })?;
+
+        let num_pairs = 1usize.checked_shl(oracle.depth as u32).unwrap_or(0);
+        let coset_mask = coset_index_mask(num_pairs, commitment.coset_offset);
+        folded = Some(fold(r, round, index ^ coset_mask, s0, s1, ntt));
+    }
+
+    match folded {
+        Some(folded) if folded != eval_proof.final_folded_value => {
+            Err(VerificationError::FinalFoldMismatch { query })
+        }
+        _ => std::result::Result::Ok(()),
+    }
+}
+
+/// Soundness achieved by a proof's parameters, with every error bound expressed in bits of security.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoundnessReport {
+    pub num_queries: usize,
+    pub log_rate: usize,
+    pub rounds: usize,
+    /// `-log2` of the probability that a word outside the unique decoding radius passes every query.
+    pub query_bits: f64,
+    /// `-log2` of the sum-check and folding error accumulated over the 128-bit field.
+    pub commit_bits: f64,
+    /// `-log2` of the combined soundness error.
+    pub security_bits: f64,
+}
+
+impl SoundnessReport {
+    /// FRI soundness bound of [DP24] for `rounds` folds of a rate `2^-log_rate` code.
+    pub fn new(num_queries: usize, log_rate: usize, rounds: usize) -> SoundnessReport {
+        // A query rejects a word at distance >= (1 - rho) / 2 with probability at least that distance.
+        let rate = (-(log_rate as f64)).exp2();
+        let query_bits = -(num_queries as f64) * ((1.0 + rate) / 2.0).log2();
+
+        // Each round costs a degree-2 sum-check error plus a folding error of |domain| / |F|.
+        let domain_size = ((rounds + log_rate) as f64).exp2();
+        let commit_bits = 128.0 - (rounds.max(1) as f64 * (2.0 + domain_size)).log2();
+
+        let security_bits = -((-query_bits).exp2() + (-commit_bits).exp2()).log2();
+
+        SoundnessReport {
+            num_queries,
+            log_rate,
+            rounds,
+            query_bits,
+            commit_bits,
+            security_bits,
+        }
+    }
+}
+
+/// Parameters a verifier insists a proof was made under, checked by [`verify_with_config`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FriConfig {
+    pub tau: usize,
+    pub log_rate: usize,
+    pub soundness_bits: f64,
+}
+
+impl Default for FriConfig {
+    fn default() -> Self {
+        FriConfig {
+            tau: TAU,
+            log_rate: LOG_RATE,
+            soundness_bits: 96.0,
+        }
+    }
+}
+
+/// Exact length of [`EvalProof::to_bytes_with`] for a `variables`-variable opening under `config`
+/// with `num_queries` queries (fewer if the codeword is shorter, as it is then queried
+/// exhaustively), with sum-check oracles in [`OracleForm::Coeffs`] and Merkle digests of `H`.
+pub fn predict_proof_size<H: MerkleHasher>(
+    variables: usize,
+    config: &FriConfig,
+    num_queries: usize,
+) -> usize {
+    const LEN: usize = 8;
+    const FIELD: usize = 16;
+    const FINGERPRINT: usize = 32;
+    let digest = H::DIGEST_BYTES;
+
+    let rounds = variables.saturating_sub(config.tau);
+    let log_len = rounds + config.log_rate;
+    let queries = num_queries.min(1 << log_len);
+
+    let header = 2;
+    let partial_evals = LEN + FIELD * (1 << config.tau);
+    let oracles = LEN + rounds * (LEN + 3 * FIELD);
+    let final_value = FIELD + LEN;
+    let fri_oracles = LEN + rounds * (digest + LEN);
+    let symbols = LEN + rounds * (LEN + queries * 2 * FIELD);
+    // Round `k` opens a tree over 2^(log_len - k - 1) leaves.
+    let paths = LEN
+        + (0..rounds)
+            .map(|k| LEN + queries * (LEN + (log_len - k - 1) * digest))
+            .sum::<usize>();
+
+    header + partial_evals + oracles + final_value + fri_oracles + symbols + paths + FINGERPRINT
+}
+
+/// The [`FriConfig`] meeting `soundness_bits` whose [`predict_proof_size`], at [`query_count`]
+/// queries, fits in `max_bytes`, for a polynomial of `2^log_poly_len` hypercube points
+/// (`log_poly_len` variables).
+///
+/// The prover encodes at the fixed [`LOG_RATE`], so the freedom left is the ring-switching split:
+/// any `tau` from 1 to [`TAU`] below `log_poly_len` can be committed with
+/// [`commit_with_tau`](crate::prover::commit_with_tau). Of the splits that fit, the one with the
+/// smallest predicted proof is returned; `None` means no provable proof fits the budget.
+pub fn find_params_for_size(
+    log_poly_len: usize,
+    max_bytes: usize,
+    soundness_bits: f64,
+) -> Option<FriConfig> {
+    let queries = query_count(soundness_bits, LOG_RATE);
+    (1..=TAU.min(log_poly_len.saturating_sub(1)))
+        .map(|tau| FriConfig {
+            tau,
+            log_rate: LOG_RATE,
+            soundness_bits,
+        })
+        .map(|config| {
+            let size = predict_proof_size::<Keccak256Hasher>(log_poly_len, &config, queries);
+            (size, config)
+        })
+        .filter(|&(size, _)| size <= max_bytes)
+        .min_by_key(|&(size, _)| size)
+        .map(|(_, config)| config)
+}
+
+/// [`verify`], first rejecting any statement that falls short of `config`: a commitment with
+/// another `tau`, a codeword of another rate, or a `channel` drawing fewer queries than
+/// [`query_count`]`(config.soundness_bits, config.log_rate)`. The proof must open exactly the
+/// queries the channel draws, so the channel's query count is the one that counts.
+pub fn verify_with_config(
+    config: &FriConfig,
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        ensure!(
+            commitment.tau() == config.tau,
+            "Commitment uses tau = {}, config requires tau = {}",
+            commitment.tau(),
+            config.tau
+        );
+
+        // Leaves hold symbol pairs, so the tree over a 2^(rounds + log_rate) codeword is one level
+        // shallower than the codeword.
+        let rounds = eval_proof.sum_check_oracles.len();
+        ensure!(
+            commitment.vector_commitment.depth + 1 == rounds + config.log_rate,
+            "Commitment depth {} does not match {rounds} rounds at log rate {}",
+            commitment.vector_commitment.depth,
+            config.log_rate
+        );
+
+        let required = query_count(config.soundness_bits, config.log_rate);
+        let num_queries = channel.num_queries();
+        // A codeword shorter than the query count is queried exhaustively.
+        let exhaustive = 1usize
+            .checked_shl((rounds + config.log_rate) as u32)
+            .is_some_and(|domain_size| domain_size < num_queries);
+        ensure!(
+            num_queries >= required || exhaustive,
+            "Channel draws {num_queries} queries, {required} needed for {} bits of soundness",
+            config.soundness_bits
+        );
+
+        Ok(verify(
+            commitment, eval_point, eval, eval_proof, ntt, channel,
+        )?)
+    })
+}
+
+/// Runs [`verify`] and, on success, reports the soundness the proof's query count and rounds achieve.
+pub fn verify_with_soundness(
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<SoundnessReport, VerificationError> {
+    verifying(|| {
+        let rounds = eval_proof.sum_check_oracles.len();
+        let num_queries = eval_proof
+            .fri_queried_symbols
+            .first()
+            .map_or(0, |queries| queries.len());
+
+        verify(commitment, eval_point, eval, eval_proof, ntt, channel)?;
+
+        Ok(SoundnessReport::new(num_queries, LOG_RATE, rounds))
+    })
+}
+
+/// Verifies `eval_proof` against a prover-emitted [`TranscriptScript`]: the script is first replayed
+/// with an independent sponge, then checked to be exactly the transcript this proof induces.
+pub fn verify_from_script(
+    script: &TranscriptScript,
+    commitment: &FriCommitment,
+    eval_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        script.replay()?;
+
+        let mut channel = Channel::recording();
+        verify(commitment, eval_point, eval, eval_proof, ntt, &mut channel)?;
+
+        let derived = channel
+            .into_script()
+            .context("recording channel lost its script")?;
+        ensure!(
+            derived == *script,
+            "Transcript script does not match the one derived from the proof"
+        );
+
+        Ok(())
+    })
+}
+
+/// Counterpart of [`prove_over_domain`](crate::prover::prove_over_domain): lifts `domain_point` to
+/// the hypercube with the same map before running [`verify`].
+pub fn verify_over_domain(
+    commitment: &FriCommitment,
+    domain: &AffineDomain,
+    domain_point: &[BinaryField128b],
+    eval: BinaryField128b,
+    eval_proof: EvalProof,
+    ntt: &dyn FoldTwiddles,
+    channel: &mut Channel,
+) -> std::result::Result<(), VerificationError> {
+    let eval_point = domain.lift_point(domain_point)?;
+    verify(commitment, &eval_point, eval, eval_proof, ntt, channel)
+}
+
+/// Verifies every proof pooled in `aggregated`, the `i`-th against `claims[i] = (eval_point, eval)`
+/// with its own fresh transcript.
+pub fn verify_aggregate(
+    commitment: &FriCommitment,
+    claims: &[(Vec<BinaryField128b>, BinaryField128b)],
+    aggregated: &AggregatedProof,
+    ntt: &dyn FoldTwiddles,
+) -> std::result::Result<(), VerificationError> {
+    verifying(|| {
+        let proofs = aggregated.expand()?;
+        ensure!(
+            proofs.len() == claims.len(),
+            "Aggregate holds {} proofs for {} claims",
+            proofs.len(),
+            claims.len()
+        );
+
+        for (i, ((eval_point, eval), proof)) in claims.iter().zip(proofs).enumerate() {
+            verify(
+                commitment,
+                eval_point,
+                *eval,
+                proof,
+                ntt,
+                &mut Channel::new(),
+            )
+            .with_context(|| format!("aggregated proof {i} failed to verify"))?;
+        }
+
+        Ok(())
+    })
+}
+
+/// [`verify`] of every `(commitment, eval_point, eval, proof)` claim in parallel, each on a
+/// fresh [`Channel`], sharing one NTT. Results are in the order of `proofs`.
+pub fn verify_many(
+    proofs: &[(
+        FriCommitment,
+        Vec<BinaryField128b>,
+        BinaryField128b,
+        EvalProof,
+    )],
+    ntt: &dyn FoldTwiddles,
+) -> Vec<std::result::Result<(), VerificationError>> {
+    proofs
+        .par_iter()
+        .map(|(commitment, eval_point, eval, proof)| {
+            verify(
+                commitment,
+                eval_point,
+                *eval,
+                proof.clone(),
+                ntt,
+                &mut Channel::new(),
+            )
+        })
+        .collect()
+}
+
+/// Multiplications [`compute_eq_table`] spends on `vars` coordinates: one per entry it fills.
+fn eq_table_mults(vars: usize) -> usize {
+    (1 << vars) - 1
+}
+
+/// Equality table of `r`, built in parallel; bit `k` of the index selects `r[k]`.
+pub fn compute_eq_table(r: &[BinaryField128b]) -> Vec<BinaryField128b> {
+    compute_eq(r)
+}
+
+pub fn compute_eq_tower_ind(
+    r_init: &[BinaryField128b],
+    r_sum: &[BinaryField128b],
+    eq_batch: &[BinaryField128b],
+) -> BinaryField128b {
+    assert_eq!(r_init.len(), r_sum.len());
+
+    let mut eval = vec![BinaryField128b::ZERO; 128];
+
+    eval[0] = BinaryField128b::ONE;
+
+    for i in 0..r_init.len() {
+        for _ in 0..128 {
+            let temp = eval[i] * r_init[i];
+            eval[i] += temp
+        }
+
+        eval = switch_view(&eval);
+
+        for _ in 0..128 {
+            let temp = eval[i] * r_sum[i];
+            eval[i] += temp
+        }
+
+        eval = switch_view(&eval);
+    }
+
+    compute_row_batch(
+        eq_batch,
+        &ColumnView::new(eval).expect("eval table has one entry per limb"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use binius_field::{BinaryField32b, BinaryField64b};
+    use binius_ntt::SingleThreadedNTT;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{
+        prover::{
+            FORMAT_VERSION, ProverContext, aggregate_proofs, commit, commit_batch,
+            commit_effective, commit_heterogeneous, commit_with_coset_offset, commit_with_hasher,
+            commit_with_tau, prove, prove_at_zero, prove_batch, prove_for_application,
+            prove_heterogeneous, prove_linear_combination, prove_multi, prove_over_domain,
+            prove_pruned, prove_sum, prove_with_derivative, prove_with_eq_tables,
+            prove_with_point_commitment,
+        },
+        utils::{
+            bytes::ProofEncoding,
+            channel::TranscriptEntry,
+            code::{Code, SubspaceEvalOracle},
+            merkle::{
+                MerkleCap, MerkleTree, Sha3_512Hasher, compute_leaf_hashes, merklize,
+                verify_multi_proof,
+            },
+            mle::{LagrangeBases, PackedMLE},
+        },
+    };
+
+    struct TestInstance {
+        poly: PackedMLE<BinaryField64b>,
+        encoded_poly: Code<BinaryField128b>,
+        merkle_tree: MerkleTree,
+        commitment: FriCommitment,
+        point: Vec<BinaryField128b>,
+        eval: BinaryField128b,
+        ntt: MultithreadedNTT<BinaryField32b>,
+    }
+
+    fn random_point(n: usize) -> Vec<BinaryField128b> {
+        (0..n)
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect()
+    }
+
+    fn random_instance(l: usize) -> TestInstance {
+        let coeffs: Vec<BinaryField64b> = (0..1 << l)
+            .map(|_| BinaryField64b::random(thread_rng()))
+            .collect();
+        instance_of(PackedMLE::new(coeffs, true))
+    }
+
+    /// Commits to `poly` under an NTT sized for it and evaluates it at a random point.
+    fn instance_of(poly: PackedMLE<BinaryField64b>) -> TestInstance {
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(poly.len().ilog2() as usize + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+
+        let (commitment, encoded_poly, merkle_tree) = commit(&poly, &ntt).unwrap();
+
+        let point = random_point(poly.variables);
+        let eval = poly.get_bound_elem(0, &LagrangeBases::gen_from_point(&point));
+
+        TestInstance {
+            poly,
+            encoded_poly,
+            merkle_tree,
+            commitment,
+            point,
+            eval,
+            ntt,
+        }
+    }
+
+    fn prove_at(
+        inst: &TestInstance,
+        point: &[BinaryField128b],
+        channel: &mut Channel,
+    ) -> EvalProof {
+        let eval = inst
+            .poly
+            .get_bound_elem(0, &LagrangeBases::gen_from_point(point));
+        prove(
+            &inst.poly,
+            point,
+            eval,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            channel,
+        )
+        .unwrap()
+    }
+
+    /// A [`ProverContext`] over `inst`'s polynomial and NTT.
+    fn prover_context(inst: &TestInstance) -> ProverContext<'_, BinaryField64b, BinaryField32b> {
+        ProverContext::new(inst.poly.clone(), &inst.ntt).unwrap()
+    }
+
+    fn prove_random(l: usize, channel: &mut Channel) -> (TestInstance, EvalProof) {
+        let inst = random_instance(l);
+        let proof = prove_at(&inst, &inst.point, channel);
+        (inst, proof)
+    }
+
+    #[test]
+    fn verify_from_script_test() {
+        let mut channel = Channel::recording();
+        let (inst, proof) = prove_random(10, &mut channel);
+        let script = channel.into_script().unwrap();
+
+        verify_from_script(
+            &script,
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof,
+            &inst.ntt,
+        )
+        .unwrap();
+
+        let mut tampered = script.clone();
+        let challenge_idx = tampered
+            .entries
+            .iter()
+            .position(|entry| matches!(entry, TranscriptEntry::Challenge(_)))
+            .unwrap();
+        tampered.entries[challenge_idx] = TranscriptEntry::Challenge(BinaryField128b::ONE);
+        assert!(tampered.replay().is_err());
+
+        let mut tampered = script;
+        tampered.entries[0] = TranscriptEntry::Observe(vec![0; 4]);
+        assert!(tampered.replay().is_err());
+    }
+
+    #[test]
+    fn prove_over_domain_test() {
+        let TestInstance {
+            poly,
+            encoded_poly,
+            merkle_tree,
+            commitment,
+            ntt,
+            ..
+        } = random_instance(10);
+
+        let lo = random_point(poly.variables);
+        let hi: Vec<BinaryField128b> = lo.iter().map(|x| *x + BinaryField128b::ONE).collect();
+        let domain = AffineDomain::new(lo.clone(), hi.clone()).unwrap();
+
+        // A domain vertex lifts to the matching hypercube vertex.
+        let vertex: Vec<BinaryField128b> = (0..poly.variables)
+            .map(|i| if (5 >> i) & 1 == 1 { hi[i] } else { lo[i] })
+            .collect();
+        let lifted = domain.lift_point(&vertex).unwrap();
+        let vertex_eval = poly.get_bound_elem(0, &LagrangeBases::gen_from_point(&lifted));
+        assert_eq!(vertex_eval, BinaryField128b::from(poly.packed_idx(5)));
+
+        let domain_point = random_point(poly.variables);
+        let eval = poly.get_bound_elem(
+            0,
+            &LagrangeBases::gen_from_point(&domain.lift_point(&domain_point).unwrap()),
+        );
+
+        let proof = prove_over_domain(
+            &poly,
+            &domain,
+            &domain_point,
+            eval,
+            &encoded_poly,
+            &commitment,
+            &merkle_tree,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        verify_over_domain(
+            &commitment,
+            &domain,
+            &domain_point,
+            eval,
+            proof,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn verify_many_test() {
+        let inst = random_instance(10);
+        let claims: Vec<_> = (0..6)
+            .map(|i| {
+                let point = random_point(inst.poly.variables);
+                let eval = inst
+                    .poly
+                    .get_bound_elem(0, &LagrangeBases::gen_from_point(&point));
+                let mut proof = prove_at(&inst, &point, &mut Channel::new());
+                match i % 3 {
+                    1 => proof.fri_queried_symbols[0][0].0 += BinaryField128b::ONE,
+                    2 => proof.upper_partial_evals[0] += BinaryField128b::ONE,
+                    _ => {}
+                }
+                (inst.commitment.clone(), point, eval, proof)
+            })
+            .collect();
+
+        let results = verify_many(&claims, &inst.ntt);
+        assert_eq!(results.len(), claims.len());
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(result.is_ok(), i % 3 == 0, "claim {i}");
+        }
+        assert!(verify_many(&[], &inst.ntt).is_empty());
+    }
+
+    #[test]
+    fn verify_aggregate_test() {
+        let inst = random_instance(10);
+        let points = [inst.point.clone(), random_point(inst.poly.variables)];
+
+        let claims: Vec<(Vec<BinaryField128b>, BinaryField128b)> = points
+            .iter()
+            .map(|point| {
+                let eval = inst
+                    .poly
+                    .get_bound_elem(0, &LagrangeBases::gen_from_point(point));
+                (point.clone(), eval)
+            })
+            .collect();
+        let proofs: Vec<EvalProof> = points
+            .iter()
+            .map(|point| prove_at(&inst, point, &mut Channel::new()))
+            .collect();
+
+        for ((point, eval), proof) in claims.iter().zip(proofs.iter()) {
+            verify(
+                &inst.commitment,
+                point,
+                *eval,
+                proof.clone(),
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .unwrap();
+        }
+
+        let total_paths: usize = proofs
+            .iter()
+            .flat_map(|proof| proof.fri_merkle_paths.iter())
+            .map(|round_paths| round_paths.len())
+            .sum();
+        let aggregated = aggregate_proofs(proofs);
+        assert!(aggregated.merkle_paths.len() <= total_paths);
+        verify_aggregate(&inst.commitment, &claims, &aggregated, &inst.ntt).unwrap();
+
+        let swapped = [claims[1].clone(), claims[0].clone()];
+        assert!(verify_aggregate(&inst.commitment, &swapped, &aggregated, &inst.ntt).is_err());
+    }
+
+    #[test]
+    fn verify_rejects_point_length_mismatch_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let mut point = inst.point.clone();
+        point.push(BinaryField128b::ONE);
+
+        let result = verify(
+            &inst.commitment,
+            &point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        );
+        assert!(result.is_err());
+
+        let result = verify(
+            &inst.commitment,
+            &inst.point[1..],
+            inst.eval,
+            proof,
+            &inst.ntt,
+            &mut Channel::new(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn prove_checked_test() {
+        let inst = random_instance(10);
+        crate::prover::prove_checked(
+            &inst.poly,
+            &inst.point,
+            inst.eval,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        // A channel that has already absorbed context is checked against that context.
+        let mut channel = Channel::for_application("prove_checked");
+        channel.observe_field_elem(inst.eval).unwrap();
+        crate::prover::prove_checked(
+            &inst.poly,
+            &inst.point,
+            inst.eval,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut channel,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(
+        expected = "prove_checked: proof for a 16-variable opening failed self-verification"
+    )]
+    fn prove_checked_catches_bad_proof_test() {
+        let inst = random_instance(10);
+        // Claiming the wrong value makes the prover emit partial evaluations inconsistent with it.
+        crate::prover::prove_checked(
+            &inst.poly,
+            &inst.point,
+            inst.eval + BinaryField128b::ONE,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn coset_offset_proof_test() {
+        let inst = random_instance(10);
+        for coset_offset in 0..RATE {
+            let (commitment, code, merkle_tree) =
+                commit_with_coset_offset(&inst.poly, &inst.ntt, coset_offset).unwrap();
+            assert_eq!(commitment.coset_offset, coset_offset);
+            assert_eq!(code.index_layout(0).coset, coset_offset);
+
+            let proof = prove(
+                &inst.poly,
+                &inst.point,
+                inst.eval,
+                &code,
+                &commitment,
+                &merkle_tree,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .unwrap();
+            let verify_at = |commitment: &FriCommitment| {
+                verify(
+                    commitment,
+                    &inst.point,
+                    inst.eval,
+                    proof.clone(),
+                    &inst.ntt,
+                    &mut Channel::new(),
+                )
+            };
+            verify_at(&commitment).unwrap();
+
+            // A commitment claiming another layout is rejected.
+            let relabelled = FriCommitment {
+                coset_offset: (coset_offset + 1) % RATE,
+                ..commitment.clone()
+            };
+            assert!(verify_at(&relabelled).is_err());
+        }
+
+        assert!(commit_with_coset_offset(&inst.poly, &inst.ntt, RATE).is_err());
+    }
+
+    #[test]
+    fn verify_reads_tau_from_commitment_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        assert_eq!(inst.commitment.tau(), TAU);
+
+        let mut commitment = inst.commitment.clone();
+        commitment.tau = TAU + 1;
+        let err = verify(
+            &commitment,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("tau = 8"));
+
+        verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        // Packing 2^5 bits per symbol doubles the codeword of the 16-variable polynomial.
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(11 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let (commitment, encoded_poly, merkle_tree) = commit_with_tau(&inst.poly, &ntt, 5).unwrap();
+        assert_eq!(commitment.tau(), 5);
+        let inst = TestInstance {
+            encoded_poly,
+            merkle_tree,
+            commitment,
+            ntt,
+            ..inst
+        };
+        let proof = prove_at(&inst, &inst.point, &mut Channel::new());
+        assert_eq!(proof.upper_partial_evals.len(), 1 << 5);
+        verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let mut wrong_tau = inst.commitment.clone();
+        wrong_tau.tau = TAU;
+        assert!(
+            verify(
+                &wrong_tau,
+                &inst.point,
+                inst.eval,
+                proof,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn sha3_512_proof_test() {
+        let inst = random_instance(10);
+        let (commitment, encoded_poly, merkle_tree) =
+            commit_with_hasher::<_, _, Sha3_512Hasher>(&inst.poly, &inst.ntt).unwrap();
+        assert_eq!(commitment.vector_commitment.root.0.len(), 64);
+
+        let proof = prove(
+            &inst.poly,
+            &inst.point,
+            inst.eval,
+            &encoded_poly,
+            &commitment,
+            &merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert!(
+            proof
+                .fri_merkle_paths
+                .iter()
+                .flatten()
+                .flatten()
+                .all(|node| node.0.len() == 64)
+        );
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), proof.size_in_bytes());
+        assert_eq!(
+            bytes.len(),
+            predict_proof_size::<Sha3_512Hasher>(
+                inst.poly.variables,
+                &FriConfig::default(),
+                proof.fri_queried_symbols[0].len()
+            )
+        );
+        assert_eq!(
+            EvalProof::<Sha3_512Hasher>::from_bytes(&bytes).unwrap(),
+            proof
+        );
+        assert!(<EvalProof>::from_bytes(&bytes).is_err());
+        let columnar = proof.to_bytes_columnar().unwrap();
+        assert_eq!(
+            EvalProof::<Sha3_512Hasher>::from_bytes_columnar(&columnar).unwrap(),
+            proof
+        );
+        let commitment_bytes = commitment.to_bytes();
+        assert_eq!(
+            commitment_bytes.len(),
+            FriCommitment::<Sha3_512Hasher>::BYTES
+        );
+        assert_eq!(
+            FriCommitment::<Sha3_512Hasher>::from_bytes(&commitment_bytes).unwrap(),
+            commitment
+        );
+
+        verify(
+            &commitment,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let mut tampered = proof;
+        tampered.fri_merkle_paths[1][0][0].0[0] ^= 1;
+        assert_eq!(
+            verify(
+                &commitment,
+                &inst.point,
+                inst.eval,
+                tampered,
+                &inst.ntt,
+                &mut Channel::new(),
+            ),
+            Err(VerificationError::MerklePath { round: 1, query: 0 })
+        );
+    }
+
+    #[test]
+    fn verify_rejects_repeated_oracle_root_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+
+        let mut repeated = proof;
+        repeated.fri_oracles[2] = repeated.fri_oracles[1].clone();
+        let err = verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            repeated,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("round 2 repeats the previous root")
+        );
+    }
+
+    #[test]
+    fn verify_from_root_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let commitment = &inst.commitment;
+        let root: [u8; 32] = commitment.vector_commitment.root.0.into();
+
+        verify(
+            commitment,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        verify_from_root(
+            root,
+            commitment.vector_commitment.depth,
+            commitment.packing_factor,
+            commitment.variables,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let mut wrong_root = root;
+        wrong_root[0] ^= 1;
+        assert!(
+            verify_from_root(
+                wrong_root,
+                commitment.vector_commitment.depth,
+                commitment.packing_factor,
+                commitment.variables,
+                &inst.point,
+                inst.eval,
+                proof,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn verify_reports_ntt_mismatch_test() {
+        /// Twiddles of `ntt` shifted by one, as an NTT over another domain basis would yield.
+        struct ShiftedTwiddles<'a>(&'a dyn FoldTwiddles);
+
+        impl FoldTwiddles for ShiftedTwiddles<'_> {
+            fn log_domain_size(&self) -> usize {
+                self.0.log_domain_size()
+            }
+
+            fn get_subspace_eval(&self, round: usize, idx: usize) -> BinaryField128b {
+                self.0.get_subspace_eval(round, idx) + BinaryField128b::ONE
+            }
+        }
+
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let mismatched_ntt = ShiftedTwiddles(&inst.ntt);
+
+        let err = verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof,
+            &mismatched_ntt,
+            &mut Channel::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err, VerificationError::TwiddleMismatch);
+        assert!(err.to_string().contains("NTT parameter mismatch"));
+    }
+
+    #[test]
+    fn verify_rejects_undersized_ntt_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        // Two rounds short of the codeword, so the outer rounds have no twiddles.
+        let undersized_ntt = SingleThreadedNTT::<BinaryField32b>::new(LOG_RATE + 7)
+            .unwrap()
+            .multithreaded();
+
+        let err = verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof,
+            &undersized_ntt,
+            &mut Channel::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err, VerificationError::TwiddleMismatch);
+    }
+
+    #[test]
+    fn prove_with_derivative_test() {
+        let inst = random_instance(10);
+        let var_index = 9;
+
+        let (eval, derivative, proof) = prove_with_derivative(
+            &inst.poly,
+            &inst.point,
+            var_index,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert_eq!(eval, inst.eval);
+
+        // Finite difference of the two sub-cubes split on `var_index`, computed directly.
+        let stride = 1 << var_index;
+        let rest: Vec<BinaryField128b> = inst
+            .point
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != var_index)
+            .map(|(_, x)| *x)
+            .collect();
+        let rest_eq = compute_eq_table(&rest);
+        let direct: BinaryField128b = (0..1 << (inst.poly.variables - 1))
+            .map(|j: usize| {
+                let lo = j & (stride - 1);
+                let idx = ((j - lo) << 1) | lo;
+                let diff = BinaryField128b::from(inst.poly.packed_idx(idx | stride))
+                    - BinaryField128b::from(inst.poly.packed_idx(idx));
+                diff * rest_eq[j]
+            })
+            .sum();
+        assert_eq!(derivative, direct);
+
+        verify_with_derivative(
+            &inst.commitment,
+            &inst.point,
+            var_index,
+            eval,
+            derivative,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        assert!(
+            verify_with_derivative(
+                &inst.commitment,
+                &inst.point,
+                var_index,
+                eval,
+                derivative + BinaryField128b::ONE,
+                proof,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn verify_fail_fast_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        verify_fail_fast(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &proof,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let mut corrupted = proof;
+        corrupted.fri_queried_symbols[0][0].0 += BinaryField128b::ONE;
+        let err = verify_fail_fast(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &corrupted,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, VerificationError::MerklePath { round: 0, query: 0 });
+    }
+
+    #[test]
+    fn verify_fail_fast_stops_at_first_failure_test() {
+        /// Twiddles of `ntt`, logging the round of every lookup.
+        struct RecordingTwiddles<'a> {
+            ntt: &'a dyn FoldTwiddles,
+            rounds: std::sync::Mutex<Vec<usize>>,
+        }
+
+        impl FoldTwiddles for RecordingTwiddles<'_> {
+            fn log_domain_size(&self) -> usize {
+                self.ntt.log_domain_size()
+            }
+
+            fn get_subspace_eval(&self, round: usize, idx: usize) -> BinaryField128b {
+                self.rounds.lock().unwrap().push(round);
+                self.ntt.get_subspace_eval(round, idx)
+            }
+        }
+
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let recording = || RecordingTwiddles {
+            ntt: &inst.ntt,
+            rounds: Default::default(),
+        };
+        // The commit phase looks up the twiddle fingerprint before any query is folded.
+        let fingerprint = recording();
+        twiddle_fingerprint(&fingerprint, proof.sum_check_oracles.len());
+        let fingerprint_lookups = fingerprint.rounds.into_inner().unwrap().len();
+
+        let mut corrupted = proof.clone();
+        corrupted.fri_queried_symbols[1][0].0 += BinaryField128b::ONE;
+        corrupted.fri_queried_symbols[1][0].1 += BinaryField128b::ONE;
+        let twiddles = recording();
+        let err = verify_fail_fast(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &corrupted,
+            &twiddles,
+            &mut Channel::new(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            VerificationError::QueryInconsistent { round: 1, query: 0 }
+        );
+        // Only the round-0 fold of query 0 ran: no later round and no later query was folded.
+        let lookups = twiddles.rounds.into_inner().unwrap();
+        assert_eq!(lookups[fingerprint_lookups..], [0]);
+
+        // Path lengths are checked before any query is walked.
+        let mut truncated = proof;
+        truncated.fri_merkle_paths[2][3].pop();
+        let twiddles = recording();
+        let err = verify_fail_fast(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &truncated,
+            &twiddles,
+            &mut Channel::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err, VerificationError::MerklePath { round: 2, query: 3 });
+        assert_eq!(
+            twiddles.rounds.into_inner().unwrap().len(),
+            fingerprint_lookups
+        );
+    }
+
+    fn compute_eq_table_serial(r: &[BinaryField128b]) -> Vec<BinaryField128b> {
+        //Initialize eq with (1- r[0]) and r[0]
+        let mut eq = [BinaryField128b::ONE - r[0], r[0]].to_vec();
+        //Iterate over the length of the r vector
+        for k in 1..r.len() {
+            let temp = eq;
+            //initialize table of double size with zero
+
+            eq = vec![BinaryField128b::ZERO; temp.len() * 2];
+
+            for i in 0..temp.len() {
+                eq[i | (1 << k)] = temp[i] * r[k];
+                eq[i] = temp[i] - eq[i | (1 << k)];
+            }
+        }
+        eq
+    }
+
+    #[test]
+    fn compute_eq_table_test() {
+        for vars in 1..10 {
+            let point = random_point(vars);
+            assert_eq!(compute_eq_table(&point), compute_eq_table_serial(&point));
+        }
+    }
+
+    #[test]
+    fn soundness_report_test() {
+        let report = SoundnessReport::new(144, LOG_RATE, 30);
+        assert!(report.query_bits > 96.0 && report.query_bits < 98.0);
+        assert!(report.security_bits > 96.0 && report.security_bits <= report.query_bits);
+
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let report = verify_with_soundness(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert_eq!(report.num_queries, 144);
+        assert!(report.security_bits > 96.0);
+    }
+
+    /// Toy compressor: a 32-byte run repeating the 32 bytes right before it costs one byte.
+    fn toy_compressed_len(bytes: &[u8]) -> usize {
+        let (mut len, mut i) = (0, 0);
+        while i < bytes.len() {
+            if i >= 32 && i + 32 <= bytes.len() && bytes[i..i + 32] == bytes[i - 32..i] {
+                i += 32;
+            } else {
+                i += 1;
+            }
+            len += 1;
+        }
+        len
+    }
+
+    /// Row-major counterpart of [`EvalProof::to_bytes_columnar`]: each query's symbols are
+    /// followed by its whole Merkle path.
+    fn to_bytes_row_major(proof: &EvalProof) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for eval in &proof.upper_partial_evals {
+            bytes.extend_from_slice(&eval.val().to_le_bytes());
+        }
+        for coeff in proof.sum_check_oracles.iter().flat_map(|o| &o.coeffs) {
+            bytes.extend_from_slice(&coeff.val().to_le_bytes());
+        }
+        bytes.extend_from_slice(&proof.final_folded_value.val().to_le_bytes());
+        for oracle in &proof.fri_oracles {
+            bytes.extend_from_slice(&oracle.to_bytes());
+        }
+        for (symbols, paths) in proof
+            .fri_queried_symbols
+            .iter()
+            .zip(&proof.fri_merkle_paths)
+        {
+            for ((s0, s1), path) in symbols.iter().zip(paths) {
+                bytes.extend_from_slice(&s0.val().to_le_bytes());
+                bytes.extend_from_slice(&s1.val().to_le_bytes());
+                for hash in path {
+                    bytes.extend_from_slice(&hash.0);
+                }
+            }
+        }
+        bytes.extend_from_slice(&proof.twiddle_fingerprint.0);
+        bytes
+    }
+
+    #[test]
+    fn columnar_bytes_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let bytes = proof.to_bytes_columnar().unwrap();
+        let decoded = <EvalProof>::from_bytes_columnar(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            decoded,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        // Columnar only adds length prefixes, which the compressed sizes are allowed to absorb.
+        let row_major = to_bytes_row_major(&proof);
+        let prefix_bytes = bytes.len() - row_major.len();
+        assert!(toy_compressed_len(&bytes) <= toy_compressed_len(&row_major) + prefix_bytes);
+        assert!(toy_compressed_len(&bytes) < bytes.len());
+
+        assert!(<EvalProof>::from_bytes_columnar(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(<EvalProof>::from_bytes_columnar(&trailing).is_err());
+
+        // Ragged query data has no columnar layout.
+        let mut ragged = proof.clone();
+        ragged.fri_queried_symbols[1].pop();
+        assert!(ragged.to_bytes_columnar().is_err());
+        let mut ragged = proof.clone();
+        ragged.fri_merkle_paths[1][0].pop();
+        assert!(ragged.to_bytes_columnar().is_err());
+
+        // Empty rounds and paths still count against the input, so huge counts are refused
+        // before anything is allocated.
+        let mut stripped = proof.clone();
+        stripped.fri_queried_symbols.clear();
+        stripped.fri_merkle_paths.clear();
+        let stripped = stripped.to_bytes_columnar().unwrap();
+        // Everything up to the three zero counts of the query sections and the fingerprint.
+        let symbols_at = stripped.len() - 3 * 8 - 32;
+        let mut hostile = stripped[..symbols_at].to_vec();
+        hostile.extend_from_slice(&u64::MAX.to_le_bytes());
+        hostile.extend_from_slice(&0u64.to_le_bytes());
+        hostile.extend_from_slice(&[0; 32]);
+        assert!(<EvalProof>::from_bytes_columnar(&hostile).is_err());
+        hostile.truncate(symbols_at);
+        hostile.extend_from_slice(&0u64.to_le_bytes());
+        hostile.extend_from_slice(&0u64.to_le_bytes());
+        hostile.extend_from_slice(&1u64.to_le_bytes());
+        hostile.extend_from_slice(&u64::MAX.to_le_bytes());
+        hostile.extend_from_slice(&0u64.to_le_bytes());
+        hostile.extend_from_slice(&[0; 32]);
+        assert!(<EvalProof>::from_bytes_columnar(&hostile).is_err());
+    }
+
+    #[test]
+    fn verify_counting_mults_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let count = || {
+            verify_counting_mults(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof.clone(),
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+        };
+
+        let (result, mults) = count();
+        result.unwrap();
+        assert!(mults > 0);
+        assert_eq!(count().1, mults);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.upper_partial_evals[0] += BinaryField128b::ONE;
+        let (result, _) = verify_counting_mults(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            bad_proof,
+            &inst.ntt,
+            &mut Channel::new(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn verify_at_zero_test() {
+        let inst = random_instance(10);
+        let (eval, opening) =
+            prove_at_zero(&inst.encoded_poly, &inst.commitment, &inst.merkle_tree).unwrap();
+        verify_at_zero(&inst.commitment, eval, &opening).unwrap();
+
+        let zero_point = vec![BinaryField128b::ZERO; inst.commitment.variables()];
+        assert_eq!(
+            eval,
+            inst.poly
+                .get_bound_elem(0, &LagrangeBases::gen_from_point(&zero_point))
+        );
+        let proof = prove_at(&inst, &zero_point, &mut Channel::new());
+        verify(
+            &inst.commitment,
+            &zero_point,
+            eval,
+            proof,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        assert!(verify_at_zero(&inst.commitment, eval + BinaryField128b::ONE, &opening).is_err());
+        let mut tampered = opening.clone();
+        tampered.symbols.1 += BinaryField128b::ONE;
+        assert!(verify_at_zero(&inst.commitment, eval, &tampered).is_err());
+    }
+
+    #[test]
+    fn verify_sumcheck_only_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        verify_sumcheck_only(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &proof,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        assert!(
+            verify_sumcheck_only(
+                &inst.commitment,
+                &inst.point,
+                inst.eval + BinaryField128b::ONE,
+                &proof,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+
+        // Query data is not inspected at all.
+        let mut stripped = proof.clone();
+        stripped.fri_queried_symbols.clear();
+        stripped.fri_merkle_paths.clear();
+        verify_sumcheck_only(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &stripped,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let mut bad_round = proof;
+        bad_round.sum_check_oracles[1].coeffs[0] += BinaryField128b::ONE;
+        assert!(
+            verify_sumcheck_only(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                &bad_round,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn ring_switch_binding_test() {
+        // The algebra computation matches the prover's table, row-batched then folded.
+        let right = random_point(5);
+        let challenges = random_point(5);
+        let batching_point = random_point(TAU);
+        let batching_eq = LagrangeBases::gen_from_point(&batching_point);
+        let mut tensored_eq = LagrangeBases::gen_from_point(&right).row_batch(&batching_eq);
+        for r in &challenges {
+            tensored_eq.fold_lo(r);
+        }
+        assert_eq!(
+            folded_tensored_eq(
+                &right,
+                &challenges,
+                &batching_eq.vals,
+                &VerifyContext::default()
+            ),
+            tensored_eq.vals[0]
+        );
+
+        // A last round that keeps its sum but not its value, and a lie about the final value,
+        // both pass every round check and only fail the binding.
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let mut forged_round = proof.clone();
+        let last = forged_round.sum_check_oracles.last_mut().unwrap();
+        last.coeffs[1] += BinaryField128b::ONE;
+        last.coeffs[2] += BinaryField128b::ONE;
+        let mut forged_value = proof;
+        forged_value.final_folded_value += BinaryField128b::ONE;
+
+        for forged in [forged_round, forged_value] {
+            let err = verify_sumcheck_only(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                &forged,
+                &mut Channel::new(),
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains("Final sum-check claim"), "{err}");
+        }
+    }
+
+    #[test]
+    fn verification_error_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let check = |eval: BinaryField128b, point: &[BinaryField128b], proof: EvalProof| {
+            verify(
+                &inst.commitment,
+                point,
+                eval,
+                proof,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .unwrap_err()
+        };
+
+        assert_eq!(
+            check(inst.eval + BinaryField128b::ONE, &inst.point, proof.clone()),
+            VerificationError::EvalMismatch
+        );
+
+        let mut tampered = proof.clone();
+        tampered.sum_check_oracles[2].coeffs[1] += BinaryField128b::ONE;
+        assert_eq!(
+            check(inst.eval, &inst.point, tampered),
+            VerificationError::SumCheckMismatch { round: 2 }
+        );
+
+        let mut tampered = proof.clone();
+        tampered.final_folded_value += BinaryField128b::ONE;
+        assert_eq!(
+            check(inst.eval, &inst.point, tampered),
+            VerificationError::RingSwitchBinding
+        );
+
+        let mut tampered = proof.clone();
+        tampered.fri_queried_symbols[0][5].0 += BinaryField128b::ONE;
+        assert_eq!(
+            check(inst.eval, &inst.point, tampered),
+            VerificationError::MerklePath { round: 0, query: 5 }
+        );
+
+        let err = check(inst.eval, &inst.point[1..], proof);
+        assert!(matches!(err, VerificationError::Malformed(_)), "{err}");
+        let err: anyhow::Error = err.into();
+        assert!(err.downcast_ref::<VerificationError>().is_some());
+    }
+
+    #[test]
+    fn forged_final_value_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let mut forged = proof;
+        forged.final_folded_value += BinaryField128b::ONE;
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                forged,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+        }));
+        assert!(result.expect("verify must not panic").is_err());
+
+        let symbol = BinaryField128b::ONE;
+        check_final_symbols(&[symbol; 4], symbol).unwrap();
+        let err =
+            check_final_symbols(&[symbol, symbol, BinaryField128b::ZERO], symbol).unwrap_err();
+        assert!(err.to_string().contains("query 2"), "{err}");
+    }
+
+    #[test]
+    fn verify_with_batching_eq_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+
+        // The batching point is the first TAU challenges the transcript draws.
+        let mut channel = Channel::recording();
+        verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut channel,
+        )
+        .unwrap();
+        let batching_point: Vec<BinaryField128b> = channel
+            .into_script()
+            .unwrap()
+            .entries
+            .into_iter()
+            .filter_map(|entry| match entry {
+                TranscriptEntry::Challenge(challenge) => Some(challenge),
+                TranscriptEntry::Observe(_) | TranscriptEntry::Squeeze(_) => None,
+            })
+            .take(TAU)
+            .collect();
+        let batching_eq = BatchingEq::new(&batching_point);
+        assert_eq!(batching_eq.table(), compute_eq_table(&batching_point));
+
+        verify_with_batching_eq(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+            &batching_eq,
+        )
+        .unwrap();
+
+        let stale = BatchingEq::new(&random_point(TAU));
+        let err = verify_with_batching_eq(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof,
+            &inst.ntt,
+            &mut Channel::new(),
+            &stale,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("different batching point"));
+    }
+
+    #[test]
+    fn heterogeneous_test() {
+        let mles: Vec<PackedMLE<BinaryField64b>> = [10, 12, 14]
+            .into_iter()
+            .map(|variables| {
+                PackedMLE::new(
+                    (0..1 << (variables - 6))
+                        .map(|_| BinaryField64b::random(thread_rng()))
+                        .collect(),
+                    true,
+                )
+            })
+            .collect();
+        // The 14-variable polynomial packs into 8 variables, and three of them stack under 2
+        // selector variables.
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(10 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let (commitment, witness) = commit_heterogeneous(&mles, &ntt).unwrap();
+        assert_eq!(commitment.variables, vec![10, 12, 14]);
+
+        let points: Vec<Vec<BinaryField128b>> =
+            mles.iter().map(|mle| random_point(mle.variables)).collect();
+        let evals: Vec<BinaryField128b> = mles
+            .iter()
+            .zip(&points)
+            .map(|(mle, point)| mle.get_bound_elem(0, &LagrangeBases::gen_from_point(point)))
+            .collect();
+
+        let proof = prove_heterogeneous(
+            &witness,
+            &commitment,
+            &points,
+            &evals,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        verify_heterogeneous(
+            &commitment,
+            &points,
+            &evals,
+            &proof,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let mut wrong_evals = evals.clone();
+        wrong_evals[1] += BinaryField128b::ONE;
+        assert!(
+            verify_heterogeneous(
+                &commitment,
+                &points,
+                &wrong_evals,
+                &proof,
+                &ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn verify_diagnostic_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let diagnose = |point: &[BinaryField128b], eval, proof: &EvalProof| {
+            verify_diagnostic(
+                &inst.commitment,
+                point,
+                eval,
+                proof,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .map_err(|diagnosis| diagnosis.stage)
+        };
+
+        assert_eq!(diagnose(&inst.point, inst.eval, &proof), Ok(()));
+        assert_eq!(
+            diagnose(&inst.point[1..], inst.eval, &proof),
+            Err(FailureStage::Statement)
+        );
+        assert_eq!(
+            diagnose(&inst.point, inst.eval + BinaryField128b::ONE, &proof),
+            Err(FailureStage::EvaluationBinding)
+        );
+
+        let mut bad_round = proof.clone();
+        bad_round.sum_check_oracles[0].coeffs[1] += BinaryField128b::ONE;
+        assert_eq!(
+            diagnose(&inst.point, inst.eval, &bad_round),
+            Err(FailureStage::SumCheck)
+        );
+
+        let mut bad_query = proof.clone();
+        bad_query.fri_queried_symbols[0][0].0 += BinaryField128b::ONE;
+        assert_eq!(
+            diagnose(&inst.point, inst.eval, &bad_query),
+            Err(FailureStage::Proximity)
+        );
+
+        let mut bad_twiddles = proof.clone();
+        bad_twiddles.twiddle_fingerprint = Hash(Default::default());
+        let diagnosis = verify_diagnostic(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &bad_twiddles,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap_err();
+        assert_eq!(diagnosis.error, VerificationError::TwiddleMismatch);
+        assert_eq!(diagnosis.stage, FailureStage::Statement);
+    }
+
+    #[test]
+    fn verify_assembled_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let other_point = random_point(inst.poly.variables);
+        let other = prove_at(&inst, &other_point, &mut Channel::new());
+
+        verify_assembled(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &proof,
+            &proof,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        // Oracles of one run with the queries of another.
+        assert!(
+            verify_assembled(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                &proof,
+                &other,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+
+        let mut truncated = proof.clone();
+        for round in &mut truncated.fri_queried_symbols {
+            round.pop();
+        }
+        assert!(
+            verify_assembled(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                &proof,
+                &truncated,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn fingerprint_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        assert_eq!(proof.fingerprint(), proof.clone().fingerprint());
+
+        // Proving is deterministic for a fixed statement and transcript.
+        let again = prove_at(&inst, &inst.point, &mut Channel::new());
+        assert_eq!(again.fingerprint(), proof.fingerprint());
+
+        let mut tampered = proof.clone();
+        tampered.fri_queried_symbols[1][0].1 += BinaryField128b::ONE;
+        assert_ne!(tampered.fingerprint(), proof.fingerprint());
+
+        let mut tampered = proof.clone();
+        tampered.oracle_form = OracleForm::Evals;
+        assert_ne!(tampered.fingerprint(), proof.fingerprint());
+    }
+
+    #[test]
+    fn dyn_twiddles_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let oracle = SubspaceEvalOracle::<BinaryField32b>::new(10 + LOG_RATE).unwrap();
+        let sources: [&dyn FoldTwiddles; 2] = [&inst.ntt, &oracle];
+
+        for twiddles in sources {
+            assert_eq!(
+                twiddle_fingerprint(twiddles, 9),
+                twiddle_fingerprint(&inst.ntt, 9)
+            );
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof.clone(),
+                twiddles,
+                &mut Channel::new(),
+            )
+            .unwrap();
+
+            let mut tampered = proof.clone();
+            tampered.fri_queried_symbols[2][0].0 += BinaryField128b::ONE;
+            assert!(
+                verify(
+                    &inst.commitment,
+                    &inst.point,
+                    inst.eval,
+                    tampered,
+                    twiddles,
+                    &mut Channel::new(),
+                )
+                .is_err()
+            );
+        }
+
+        // The generic entry points agree with the dynamic one.
+        verify_with_options(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof,
+            &inst.ntt,
+            &mut Channel::new(),
+            VerifyOptions::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn find_params_for_size_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let variables = inst.poly.variables;
+        let actual = proof.to_bytes_with(ProofEncoding::Little).len();
+        let num_queries = proof.fri_queried_symbols[0].len();
+        assert_eq!(
+            predict_proof_size::<Keccak256Hasher>(variables, &FriConfig::default(), num_queries),
+            actual
+        );
+
+        // The returned parameters are ones the prover can actually meet within the budget.
+        let budget = predict_proof_size::<Keccak256Hasher>(
+            variables,
+            &FriConfig::default(),
+            query_count(96.0, LOG_RATE),
+        );
+        let config = find_params_for_size(variables, budget, 96.0).unwrap();
+        assert_eq!(config, FriConfig::default());
+        let proof = prove_at(&inst, &inst.point, &mut Channel::with_security_bits(96));
+        assert!(proof.size_in_bytes() <= budget);
+        verify_with_config(
+            &config,
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof,
+            &inst.ntt,
+            &mut Channel::with_security_bits(96),
+        )
+        .unwrap();
+        assert!(find_params_for_size(variables, budget - 1, 96.0).is_none());
+
+        // The default parameters meet 96 bits with the prover's fixed query count.
+        assert!(num_queries >= query_count(96.0, LOG_RATE));
+
+        assert!(find_params_for_size(variables, 1000, 96.0).is_none());
+        assert!(find_params_for_size(1, usize::MAX, 96.0).is_none());
+
+        // A TAU-variable polynomial cannot be opened at TAU, so only a smaller split fits.
+        let poly = PackedMLE::new(
+            (0..2)
+                .map(|_| BinaryField64b::random(thread_rng()))
+                .collect(),
+            true,
+        );
+        assert_eq!(poly.variables, TAU);
+        let split = FriConfig {
+            tau: TAU - 1,
+            ..FriConfig::default()
+        };
+        let budget =
+            predict_proof_size::<Keccak256Hasher>(TAU, &split, query_count(96.0, LOG_RATE));
+        let config = find_params_for_size(TAU, budget, 96.0).unwrap();
+        assert_eq!(config, split);
+        assert!(find_params_for_size(TAU, budget - 1, 96.0).is_none());
+
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(TAU - config.tau + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let (commitment, encoded_poly, merkle_tree) =
+            commit_with_tau(&poly, &ntt, config.tau).unwrap();
+        let point = random_point(TAU);
+        let eval = poly.get_bound_elem(0, &LagrangeBases::gen_from_point(&point));
+        let inst = TestInstance {
+            poly,
+            encoded_poly,
+            merkle_tree,
+            commitment,
+            point,
+            eval,
+            ntt,
+        };
+        let proof = prove_at(&inst, &inst.point, &mut Channel::with_security_bits(96));
+        assert_eq!(proof.to_bytes_with(ProofEncoding::Little).len(), budget);
+        verify_with_config(
+            &config,
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof,
+            &inst.ntt,
+            &mut Channel::with_security_bits(96),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn format_version_test() {
+        let (_, proof) = prove_random(10, &mut Channel::new());
+        let future = (FORMAT_VERSION + 1).to_le_bytes();
+
+        let mut bytes = proof.to_bytes_with(ProofEncoding::Little);
+        assert_eq!(bytes[..2], FORMAT_VERSION.to_le_bytes());
+        bytes[..2].copy_from_slice(&future);
+        let err = <EvalProof>::from_bytes_with(&bytes, ProofEncoding::Little).unwrap_err();
+        assert!(err.to_string().contains("version"), "{err}");
+
+        let mut columnar = proof.to_bytes_columnar().unwrap();
+        columnar[..2].copy_from_slice(&future);
+        assert!(<EvalProof>::from_bytes_columnar(&columnar).is_err());
+        assert!(<EvalProof>::from_bytes_columnar(&[]).is_err());
+    }
+
+    #[test]
+    fn security_bits_prove_verify_test() {
+        let inst = random_instance(10);
+        let proof = prove_at(&inst, &inst.point, &mut Channel::with_security_bits(128));
+        assert_eq!(
+            proof.fri_queried_symbols[0].len(),
+            query_count(128.0, LOG_RATE)
+        );
+
+        let run = |mut channel: Channel| {
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof.clone(),
+                &inst.ntt,
+                &mut channel,
+            )
+        };
+        run(Channel::with_security_bits(128)).unwrap();
+        assert!(run(Channel::new()).is_err());
+    }
+
+    #[test]
+    fn verify_parts_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let parts = |proof: &EvalProof| {
+            verify_parts(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                &proof.upper_partial_evals,
+                &proof.sum_check_oracles,
+                &proof.fri_oracles,
+                proof.final_folded_value,
+                &proof.fri_queried_symbols,
+                &proof.fri_merkle_paths,
+                proof.twiddle_fingerprint,
+                proof.oracle_form,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+        };
+        let whole = |proof: &EvalProof| {
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof.clone(),
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+        };
+
+        parts(&proof).unwrap();
+        whole(&proof).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.fri_queried_symbols[3][10].0 += BinaryField128b::ONE;
+        assert!(parts(&tampered).is_err() && whole(&tampered).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.sum_check_oracles[1].coeffs[2] += BinaryField128b::ONE;
+        assert!(parts(&tampered).is_err() && whole(&tampered).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.twiddle_fingerprint = Hash(Default::default());
+        assert!(parts(&tampered).is_err() && whole(&tampered).is_err());
+
+        // Oracles delivered in evaluation form are read as such.
+        let evals_proof = proof.clone().into_oracle_form(OracleForm::Evals).unwrap();
+        parts(&evals_proof).unwrap();
+        whole(&evals_proof).unwrap();
+
+        let mut truncated = proof;
+        truncated.fri_merkle_paths.pop();
+        assert!(parts(&truncated).is_err());
+    }
+
+    #[test]
+    fn proof_bytes_round_trip_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        assert_eq!(inst.commitment.variables, 16);
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes, proof.to_bytes_with(ProofEncoding::Little));
+        let decoded = <EvalProof>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            decoded,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        for len in [0, 1, bytes.len() / 2, bytes.len() - 1] {
+            assert!(<EvalProof>::from_bytes(&bytes[..len]).is_err());
+        }
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(<EvalProof>::from_bytes(&trailing).is_err());
+    }
+
+    #[test]
+    fn canonical_query_order_test() {
+        let inst = random_instance(10);
+        let drawn = prove_at(&inst, &inst.point, &mut Channel::new());
+        let canonical_channel = || Channel::new().with_canonical_queries();
+        let canonical = prove_at(&inst, &inst.point, &mut canonical_channel());
+
+        let (_, queries) = replay_commit_phase_with_context(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &drawn,
+            &inst.ntt,
+            &mut Channel::new(),
+            &VerifyContext::default(),
+        )
+        .unwrap();
+        // Lays out the query data as a prover drawing the queries in `order` would have.
+        let relaid = |order: &[usize]| {
+            let mut proof = drawn.clone();
+            for (symbols, drawn_symbols) in proof
+                .fri_queried_symbols
+                .iter_mut()
+                .zip(&drawn.fri_queried_symbols)
+            {
+                *symbols = order.iter().map(|&q| drawn_symbols[q]).collect();
+            }
+            for (paths, drawn_paths) in proof
+                .fri_merkle_paths
+                .iter_mut()
+                .zip(&drawn.fri_merkle_paths)
+            {
+                *paths = order.iter().map(|&q| drawn_paths[q].clone()).collect();
+            }
+            proof
+        };
+        let sorted_from = |draw_order: Vec<usize>| {
+            let mut order = draw_order;
+            order.sort_by_key(|&q| queries[q]);
+            relaid(&order).to_bytes()
+        };
+
+        let forward = sorted_from((0..queries.len()).collect());
+        let reversed = sorted_from((0..queries.len()).rev().collect());
+        assert_eq!(forward, reversed);
+        assert_eq!(forward, canonical.to_bytes());
+        assert_ne!(forward, drawn.to_bytes());
+
+        let verify_under = |proof: &EvalProof, channel: &mut Channel| {
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof.clone(),
+                &inst.ntt,
+                channel,
+            )
+        };
+        verify_under(&canonical, &mut canonical_channel()).unwrap();
+        verify_under(&drawn, &mut Channel::new()).unwrap();
+        assert!(verify_under(&canonical, &mut Channel::new()).is_err());
+    }
+
+    #[test]
+    fn pruned_paths_test() {
+        let inst = random_instance(10);
+        let naive = prove_at(&inst, &inst.point, &mut Channel::new());
+        let pruned = prove_pruned(
+            &inst.poly,
+            &inst.point,
+            inst.eval,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        let mut stripped = naive.clone();
+        stripped.fri_merkle_paths.clear();
+        assert_eq!(pruned.proof, stripped);
+
+        let verify_with = |pruned: &PrunedEvalProof| {
+            verify_pruned(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                pruned,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+        };
+        verify_with(&pruned).unwrap();
+
+        // Every round's pruned openings resolve to the root the naive per-query paths verify to.
+        let (_, queries) = replay_commit_phase_with_context(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &naive,
+            &inst.ntt,
+            &mut Channel::new(),
+            &VerifyContext::default(),
+        )
+        .unwrap();
+        let oracles = std::iter::once(&inst.commitment.vector_commitment).chain(&naive.fri_oracles);
+        for (round, (oracle, multi_proof, symbols, paths)) in multizip((
+            oracles,
+            &pruned.fri_multi_proofs,
+            &naive.fri_queried_symbols,
+            &naive.fri_merkle_paths,
+        ))
+        .enumerate()
+        {
+            let leaves: Vec<(usize, Hash)> = queries
+                .iter()
+                .zip(symbols)
+                .map(|(query, pair)| (query >> round, hash_tuple(pair)))
+                .collect();
+            for (&(index, leaf), path) in leaves.iter().zip(paths) {
+                verify_merkle_path(oracle, leaf, index, path).unwrap();
+            }
+            let cap = MerkleCap {
+                nodes: vec![oracle.root()],
+                depth: oracle.depth,
+            };
+            verify_multi_proof(&cap, &leaves, multi_proof).unwrap();
+        }
+
+        let naive_hashes: usize = naive.fri_merkle_paths.iter().flatten().map(Vec::len).sum();
+        let pruned_hashes: usize = pruned
+            .fri_multi_proofs
+            .iter()
+            .map(|multi_proof| multi_proof.siblings.len())
+            .sum();
+        assert!(2 * pruned_hashes < naive_hashes);
+
+        let mut tampered = pruned.clone();
+        tampered.fri_multi_proofs[1].siblings[0] = Hash(Default::default());
+        assert!(verify_with(&tampered).is_err());
+
+        // The last oracle is observed after every sum-check challenge, so the replay still passes
+        // and the hostile depth reaches the expansion unless it is checked first.
+        let mut hostile = pruned.clone();
+        hostile.proof.fri_oracles.last_mut().unwrap().depth = 1 << 40;
+        assert!(verify_with(&hostile).is_err());
+
+        let mut truncated = pruned;
+        truncated.fri_multi_proofs.pop();
+        assert!(verify_with(&truncated).is_err());
+    }
+
+    #[test]
+    fn path_length_precheck_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let verify_proof = |proof: EvalProof| {
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+        };
+        let depth = inst.commitment.vector_commitment.depth;
+        for (round, paths) in proof.fri_merkle_paths.iter().enumerate() {
+            assert!(paths.iter().all(|path| path.len() == depth - round));
+        }
+
+        // The short path of round 2 is reported ahead of the forged symbol of round 0.
+        let mut malformed = proof.clone();
+        malformed.fri_merkle_paths[2][5].pop();
+        malformed.fri_queried_symbols[0][0].0 += BinaryField128b::ONE;
+        assert_eq!(
+            verify_proof(malformed).unwrap_err(),
+            VerificationError::MerklePath { round: 2, query: 5 }
+        );
+
+        let mut extended = proof;
+        let extra = extended.fri_merkle_paths[2][5][0];
+        extended.fri_merkle_paths[2][5].push(extra);
+        assert_eq!(
+            verify_proof(extended).unwrap_err(),
+            VerificationError::MerklePath { round: 2, query: 5 }
+        );
+    }
+
+    #[test]
+    fn truncated_round_vectors_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let truncations: [fn(&mut EvalProof); 4] = [
+            |proof| {
+                proof.sum_check_oracles.pop();
+            },
+            |proof| {
+                proof.fri_oracles.pop();
+            },
+            |proof| {
+                proof.fri_queried_symbols.pop();
+            },
+            |proof| {
+                proof.fri_merkle_paths.pop();
+            },
+        ];
+        for truncate in truncations {
+            let mut truncated = proof.clone();
+            truncate(&mut truncated);
+            let err = verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                truncated,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .unwrap_err();
+            assert!(matches!(err, VerificationError::Malformed(_)), "{err}");
+        }
+    }
+
+    /// Bits `x` with `sum_k x_k cols[k] = target` over F2, if any.
+    fn solve_gf2(cols: &[u128], target: u128) -> Option<u128> {
+        // Each pivot is reduced by the earlier ones, so reducing in order clears every lead bit.
+        let mut pivots: Vec<(u128, u128)> = Vec::new();
+        let reduce = |pivots: &[(u128, u128)], mut v: u128, mut combo: u128| {
+            for &(pivot, pivot_combo) in pivots {
+                if v & (1 << (127 - pivot.leading_zeros())) != 0 {
+                    v ^= pivot;
+                    combo ^= pivot_combo;
+                }
+            }
+            (v, combo)
+        };
+        for (k, &col) in cols.iter().enumerate() {
+            let (v, combo) = reduce(&pivots, col, 1 << k);
+            if v != 0 {
+                pivots.push((v, combo));
+            }
+        }
+        match reduce(&pivots, target, 0) {
+            (0, combo) => Some(combo),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn forged_partial_evals_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let false_eval = inst.eval + BinaryField128b::ONE;
+
+        // The batching point a transcript that skips the partial evaluations would draw.
+        let mut channel = Channel::new();
+        channel.observe_fri_commitment(&inst.commitment);
+        channel.observe_field_elems(&inst.point).unwrap();
+        channel.observe_field_elem(false_eval).unwrap();
+        let batching_eq = compute_eq_table(&channel.get_random_points(TAU).unwrap());
+        let left_eq = compute_eq_table(&inst.point[..TAU]);
+
+        // Perturb rows `i0` and `i1` of the row view by `c` and `ratio * c`, which row-batch to
+        // zero, choosing `c` so the eval binding moves by exactly one.
+        let (i0, i1) = (0, 1);
+        let ratio = batching_eq[i0] * batching_eq[i1].invert().unwrap();
+        let delta = |c: BinaryField128b| -> Vec<BinaryField128b> {
+            let (c0, c1) = (c.val(), (ratio * c).val());
+            (0..128)
+                .map(|j| BinaryField128b::new((((c0 >> j) & 1) << i0) | (((c1 >> j) & 1) << i1)))
+                .collect()
+        };
+        let eval_shift = |d: &[BinaryField128b]| -> BinaryField128b {
+            left_eq.iter().zip(d).map(|(e, d)| *e * *d).sum()
+        };
+        let cols: Vec<u128> = (0..128)
+            .map(|k| eval_shift(&delta(BinaryField128b::new(1 << k))).val())
+            .collect();
+        let c = solve_gf2(&cols, BinaryField128b::ONE.val()).unwrap();
+
+        let mut forged = proof.clone();
+        for (partial, d) in forged
+            .upper_partial_evals
+            .iter_mut()
+            .zip(delta(BinaryField128b::new(c)))
+        {
+            *partial += d;
+        }
+        let row_batch = |partials: &[BinaryField128b]| {
+            compute_row_batch(
+                &batching_eq,
+                &ColumnView::from_partial_evals(partials).unwrap(),
+            )
+        };
+        // Against that batching point the forgery binds the false eval and the honest claim.
+        check_eval_binding(
+            &inst.point,
+            false_eval,
+            &forged.upper_partial_evals,
+            TAU,
+            &VerifyContext::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            row_batch(&forged.upper_partial_evals),
+            row_batch(&proof.upper_partial_evals)
+        );
+
+        assert!(
+            verify(
+                &inst.commitment,
+                &inst.point,
+                false_eval,
+                forged,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn batch_commitment_test() {
+        let mles: Vec<PackedMLE<BinaryField64b>> = (0..3)
+            .map(|_| {
+                let coeffs = (0..1 << 8)
+                    .map(|_| BinaryField64b::random(thread_rng()))
+                    .collect();
+                PackedMLE::new(coeffs, true)
+            })
+            .collect();
+        assert_eq!(mles[0].variables, 14);
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(10 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+
+        let (stacked, commitment, code, merkle_tree) = commit_batch(&mles, &ntt).unwrap();
+        assert_eq!(commitment.variables, 16);
+
+        let point = random_point(14);
+        let eq = LagrangeBases::gen_from_point(&point);
+        let evals: Vec<BinaryField128b> =
+            mles.iter().map(|mle| mle.get_bound_elem(0, &eq)).collect();
+        let proof = prove_batch(
+            &stacked,
+            &point,
+            &evals,
+            &code,
+            &commitment,
+            &merkle_tree,
+            &ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let verify_evals = |evals: &[BinaryField128b]| {
+            verify_batch(
+                &commitment,
+                &point,
+                evals,
+                proof.clone(),
+                &ntt,
+                &mut Channel::new(),
+            )
+        };
+        verify_evals(&evals).unwrap();
+
+        let mut wrong = evals.clone();
+        wrong[2] += BinaryField128b::ONE;
+        assert!(verify_evals(&wrong).is_err());
+        // The zero-padded fourth block is part of the statement too.
+        assert!(verify_evals(&[evals.clone(), vec![BinaryField128b::ONE]].concat()).is_err());
+        assert!(verify_evals(&evals[..2]).is_err());
+        assert!(commit_batch(&[mles[0].clone(), random_instance(7).poly], &ntt).is_err());
+    }
+
+    #[test]
+    fn application_domain_test() {
+        let inst = random_instance(10);
+        let proof = prove_for_application(
+            "A",
+            &inst.poly,
+            &inst.point,
+            inst.eval,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+        )
+        .unwrap();
+        let verify_under = |domain| {
+            verify_for_application(
+                domain,
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof.clone(),
+                &inst.ntt,
+            )
+        };
+
+        verify_under("A").unwrap();
+        assert!(verify_under("B").is_err());
+        assert!(
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof.clone(),
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn multi_point_opening_test() {
+        let inst = random_instance(9);
+        assert_eq!(inst.poly.variables, 15);
+        let points: Vec<Vec<BinaryField128b>> = (0..4).map(|_| random_point(15)).collect();
+        let evals: Vec<BinaryField128b> = points
+            .iter()
+            .map(|point| {
+                inst.poly
+                    .get_bound_elem(0, &LagrangeBases::gen_from_point(point))
+            })
+            .collect();
+
+        let multi_proof = prove_multi(
+            &inst.poly,
+            &points,
+            &evals,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert_eq!(multi_proof.upper_partial_evals.len(), 4);
+        let verify_proof = |evals: &[BinaryField128b], multi_proof: &MultiEvalProof| {
+            verify_multi(
+                &inst.commitment,
+                &points,
+                evals,
+                multi_proof,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+        };
+        verify_proof(&evals, &multi_proof).unwrap();
+
+        let mut wrong = evals.clone();
+        wrong[3] += BinaryField128b::ONE;
+        assert!(verify_proof(&wrong, &multi_proof).is_err());
+
+        // Partial evaluations shifted consistently with a wrong claim still fail the sum-check.
+        let mut shifted = multi_proof.clone();
+        shifted.upper_partial_evals[1][0] += BinaryField128b::ONE;
+        let mut shifted_evals = evals.clone();
+        shifted_evals[1] += compute_eq_table(&points[1][..TAU])[0];
+        assert!(verify_proof(&shifted_evals, &shifted).is_err());
+
+        assert!(verify_proof(&evals[..3], &multi_proof).is_err());
+
+        // A proof short of FRI oracles is rejected, not indexed past its end.
+        let mut truncated = multi_proof.clone();
+        truncated.proof.fri_oracles.pop();
+        assert!(verify_proof(&evals, &truncated).is_err());
+
+        // Each point is split at TAU, so a commitment with a smaller split is refused up front.
+        let ntt = SingleThreadedNTT::<BinaryField32b>::new(10 + LOG_RATE)
+            .unwrap()
+            .multithreaded();
+        let (commitment, encoded_poly, merkle_tree) = commit_with_tau(&inst.poly, &ntt, 5).unwrap();
+        assert!(
+            prove_multi(
+                &inst.poly,
+                &points,
+                &evals,
+                &encoded_poly,
+                &commitment,
+                &merkle_tree,
+                &ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn proof_size_test() {
+        let (_, proof) = prove_random(10, &mut Channel::new());
+        let breakdown = proof.breakdown();
+        assert_eq!(proof.size_in_bytes(), proof.to_bytes().len());
+        assert_eq!(breakdown.total(), proof.size_in_bytes());
+        assert!(breakdown.fri_merkle_paths > breakdown.fri_queried_symbols);
+
+        let mut empty = proof;
+        empty.fri_queried_symbols.clear();
+        empty.fri_merkle_paths.clear();
+        assert_eq!(empty.size_in_bytes(), empty.to_bytes().len());
+    }
+
+    #[test]
+    fn proof_encoding_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+
+        let big = proof.to_bytes_with(ProofEncoding::Big);
+        let little = proof.to_bytes_with(ProofEncoding::Little);
+        assert_eq!(big.len(), little.len());
+        assert_ne!(big, little);
+
+        let decoded = <EvalProof>::from_bytes_with(&big, ProofEncoding::Big).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(
+            <EvalProof>::from_bytes_with(&little, ProofEncoding::Little).unwrap(),
+            proof
+        );
+        assert_ne!(
+            <EvalProof>::from_bytes_with(&big, ProofEncoding::Little).ok(),
+            Some(proof)
+        );
+
+        // The transcript is unaffected by the wire byte order.
+        verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            decoded,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        assert!(<EvalProof>::from_bytes_with(&big[..big.len() - 1], ProofEncoding::Big).is_err());
+    }
+
+    #[test]
+    fn fold_buffers_test() {
+        let num_queries = 144;
+        let mut buffers = FoldBuffers::new(num_queries);
+        let allocations = [buffers.current.as_ptr(), buffers.next.as_ptr()];
+
+        for round in 0..10 {
+            for _ in 0..num_queries {
+                buffers.next.push(BinaryField128b::new(round));
+            }
+            buffers.advance();
+
+            assert!(
+                buffers
+                    .current
+                    .iter()
+                    .all(|x| *x == BinaryField128b::new(round))
+            );
+            assert!(buffers.next.is_empty());
+            // Both rounds' symbols live in the two original allocations.
+            assert!(allocations.contains(&buffers.current.as_ptr()));
+            assert!(allocations.contains(&buffers.next.as_ptr()));
+        }
+
+        // The restructured loop still accepts and rejects as before.
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        verify(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        let mut bad_query = proof;
+        bad_query.fri_queried_symbols[1][0].1 += BinaryField128b::ONE;
+        assert!(
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                bad_query,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn oracle_form_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let evals_proof = proof.clone().into_oracle_form(OracleForm::Evals).unwrap();
+        assert_ne!(evals_proof.sum_check_oracles, proof.sum_check_oracles);
+        assert_eq!(
+            evals_proof
+                .clone()
+                .into_oracle_form(OracleForm::Coeffs)
+                .unwrap(),
+            proof
+        );
+
+        let oracle = &proof.sum_check_oracles[0];
+        let evals = &evals_proof.sum_check_oracles[0].coeffs;
+        assert_eq!(evals[0], oracle.evaluate(BinaryField128b::ZERO));
+        assert_eq!(evals[1], oracle.evaluate(BinaryField128b::ONE));
+
+        let challenges = |proof: EvalProof| {
+            let mut channel = Channel::recording();
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof,
+                &inst.ntt,
+                &mut channel,
+            )
+            .unwrap();
+            channel.into_script().unwrap()
+        };
+        assert_eq!(challenges(evals_proof.clone()), challenges(proof));
+
+        let bytes = evals_proof.to_bytes_with(ProofEncoding::Little);
+        assert_eq!(
+            <EvalProof>::from_bytes_with(&bytes, ProofEncoding::Little).unwrap(),
+            evals_proof
+        );
+
+        let mut bad = evals_proof;
+        bad.sum_check_oracles[0].coeffs[1] += BinaryField128b::ONE;
+        assert!(
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                bad,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn verify_proximity_test() {
+        let inst = random_instance(10);
+        let proof = prove_proximity(
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        verify_proximity(&inst.commitment, &proof, &inst.ntt, &mut Channel::new()).unwrap();
+
+        // A uniformly random word of the same length is far from every codeword: folding it
+        // does not collapse to a constant, so the final check rejects.
+        let encoding: Vec<BinaryField128b> = (0..inst.encoded_poly.encoding.len())
+            .map(|_| BinaryField128b::random(thread_rng()))
+            .collect();
+        let merkle_tree = merklize(compute_leaf_hashes::<Keccak256Hasher>(&encoding));
+        let commitment = FriCommitment {
+            vector_commitment: VectorCommitment {
+                root: merkle_tree.get_root(),
+                depth: inst.commitment.vector_commitment.depth,
+            },
+            ..inst.commitment.clone()
+        };
+        let far_code = Code {
+            encoding,
+            coset_offset: 0,
+        };
+        let proof = prove_proximity(
+            &far_code,
+            &commitment,
+            &merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert!(verify_proximity(&commitment, &proof, &inst.ntt, &mut Channel::new()).is_err());
+    }
+
+    #[test]
+    fn check_transcript_consistency_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let check = |proof: &EvalProof, channel: &Channel| {
+            proof.check_transcript_consistency(&inst.commitment, &inst.point, inst.eval, channel)
+        };
+        check(&proof, &Channel::new()).unwrap();
+
+        for round in [0, 4, 8] {
+            let mut tampered = proof.clone();
+            tampered.sum_check_oracles[round].coeffs[2] += BinaryField128b::ONE;
+            assert!(
+                check(&tampered, &Channel::new()).is_err(),
+                "tampered oracle of round {round} passed"
+            );
+        }
+
+        // The replay starts from the channel the proof was produced with, not a fresh one.
+        let channel = Channel::for_application("consistency");
+        let proof = prove_at(&inst, &inst.point, &mut channel.clone());
+        check(&proof, &channel).unwrap();
+        check(&proof, &channel).unwrap();
+        assert!(check(&proof, &Channel::new()).is_err());
+    }
+
+    #[test]
+    fn verify_detailed_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let report = verify_detailed(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &proof,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.rounds.len(), proof.fri_queried_symbols.len());
+        assert!(report.rounds.iter().flatten().all(QueryCheck::is_ok));
+
+        let (round, query) = (3, 5);
+        let mut tampered = proof.clone();
+        tampered.fri_queried_symbols[round][query].0 += BinaryField128b::ONE;
+        let report = verify_detailed(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &tampered,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert!(!report.is_ok());
+        assert!(!report.rounds[round][query].merkle_path_ok);
+
+        // Only the tampered query fails: its own path in `round`, then its fold in `round + 1`.
+        let failures = report.failures();
+        assert!(failures.contains(&(round, query)));
+        assert!(failures.iter().all(|(r, i)| *i == query && *r >= round));
+    }
+
+    #[test]
+    fn estimate_verify_time_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let rounds = proof.fri_queried_symbols.len();
+        let num_queries = proof.fri_queried_symbols[0].len();
+        let depth = inst.commitment.vector_commitment.depth;
+
+        let model = estimate_verify_time(num_queries, rounds, depth);
+        let (result, mults) = verify_counting_mults(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        );
+        result.unwrap();
+        assert_eq!(model.mults, mults);
+
+        let hashes: usize = proof
+            .fri_merkle_paths
+            .iter()
+            .flatten()
+            .map(|path| 1 + path.len())
+            .sum();
+        assert_eq!(model.hashes, hashes as u64);
+        assert!(model.estimate() > Duration::ZERO);
+    }
+
+    #[test]
+    fn verify_with_options_test() {
+        let inst = random_instance(10);
+        // The prover does not check the claim, so it proves a wrong one just as well.
+        let wrong_eval = inst.eval + BinaryField128b::ONE;
+        let proof = prove(
+            &inst.poly,
+            &inst.point,
+            wrong_eval,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let verify_wrong = |options| {
+            verify_with_options(
+                &inst.commitment,
+                &inst.point,
+                wrong_eval,
+                proof.clone(),
+                &inst.ntt,
+                &mut Channel::new(),
+                options,
+            )
+        };
+        assert!(verify_wrong(VerifyOptions::default()).is_err());
+        verify_wrong(VerifyOptions {
+            skip_eval_reconstruction: true,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn point_commitment_test() {
+        let inst = random_instance(10);
+        let point_commitment = PointCommitment::new(&inst.point);
+        let proof = prove_with_point_commitment(
+            &inst.poly,
+            &inst.point,
+            &point_commitment,
+            inst.eval,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        verify_with_point_commitment(
+            &inst.commitment,
+            &point_commitment,
+            &inst.point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let mut wrong_point = inst.point.clone();
+        wrong_point[0] += BinaryField128b::ONE;
+        let err = verify_with_point_commitment(
+            &inst.commitment,
+            &point_commitment,
+            &wrong_point,
+            inst.eval,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("does not open"));
+
+        // The transcripts differ, so the proof does not carry over to a plain verify.
+        assert!(
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn verify_with_config_test() {
+        let inst = random_instance(10);
+        let proof = prove_at(&inst, &inst.point, &mut Channel::new());
+        let required = FriConfig::default();
+        assert_eq!(query_count(96.0, LOG_RATE), 142);
+
+        let run = |config: &FriConfig, proof: EvalProof, channel: &mut Channel| {
+            verify_with_config(
+                config,
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof,
+                &inst.ntt,
+                channel,
+            )
+        };
+        run(&required, proof.clone(), &mut Channel::new()).unwrap();
+
+        // An honest proof at 80-bit security, on a channel drawing that many queries.
+        let weak = FriConfig {
+            soundness_bits: 80.0,
+            ..required
+        };
+        let weak_proof = prove_at(&inst, &inst.point, &mut Channel::with_security_bits(80));
+        assert_eq!(
+            weak_proof.fri_queried_symbols[0].len(),
+            query_count(weak.soundness_bits, LOG_RATE)
+        );
+        run(
+            &weak,
+            weak_proof.clone(),
+            &mut Channel::with_security_bits(80),
+        )
+        .unwrap();
+        let err = run(&required, weak_proof, &mut Channel::with_security_bits(80)).unwrap_err();
+        assert!(err.to_string().contains("queries"));
+        // The channel, not the proof, sets the query count: an honest 96-bit proof checked on an
+        // 80-bit channel is rejected before any query is looked at.
+        assert!(
+            run(
+                &required,
+                proof.clone(),
+                &mut Channel::with_security_bits(80)
+            )
+            .unwrap_err()
+            .to_string()
+            .contains("Channel draws")
+        );
+
+        let other_tau = FriConfig {
+            tau: TAU + 1,
+            ..required
+        };
+        assert!(run(&other_tau, proof.clone(), &mut Channel::new()).is_err());
+        let other_rate = FriConfig {
+            log_rate: LOG_RATE + 1,
+            ..required
+        };
+        assert!(run(&other_rate, proof, &mut Channel::new()).is_err());
+    }
+
+    #[test]
+    fn eq_tables_test() {
+        let inst = random_instance(10);
+        let eq_tables = PointEqTables::new(&inst.point).unwrap();
+
+        let from_scratch = prove(
+            &inst.poly,
+            &inst.point,
+            inst.eval,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        let precomputed = prove_with_eq_tables(
+            &inst.poly,
+            &eq_tables,
+            inst.eval,
+            &inst.encoded_poly,
+            &inst.commitment,
+            &inst.merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert_eq!(precomputed, from_scratch);
+
+        verify_with_eq_tables(
+            &inst.commitment,
+            &eq_tables,
+            inst.eval,
+            precomputed,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let mut other_point = inst.point.clone();
+        other_point[0] += BinaryField128b::ONE;
+        let other_tables = PointEqTables::new(&other_point).unwrap();
+        assert!(
+            verify_with_eq_tables(
+                &inst.commitment,
+                &other_tables,
+                inst.eval,
+                from_scratch,
+                &inst.ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn verify_interleaved_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::new());
+        let run_both = |proof: &EvalProof| {
+            let interleaved = verify_interleaved(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof,
+                &inst.ntt,
+                &mut Channel::new(),
+            );
+            let sequential = verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof.clone(),
+                &inst.ntt,
+                &mut Channel::new(),
+            );
+            (interleaved.is_ok(), sequential.is_ok())
+        };
+        assert_eq!(run_both(&proof), (true, true));
+
+        let mut corruptions: Vec<EvalProof> = Vec::new();
+        let mut tampered = proof.clone();
+        tampered.upper_partial_evals[3] += BinaryField128b::ONE;
+        corruptions.push(tampered);
+        let mut tampered = proof.clone();
+        tampered.sum_check_oracles[4].coeffs[1] += BinaryField128b::ONE;
+        corruptions.push(tampered);
+        let mut tampered = proof.clone();
+        tampered.fri_queried_symbols[2][7].1 += BinaryField128b::ONE;
+        corruptions.push(tampered);
+        let mut tampered = proof.clone();
+        tampered.fri_merkle_paths[5][0][1] = tampered.fri_merkle_paths[5][0][0];
+        corruptions.push(tampered);
+        // Truncated proofs are rejected, not indexed past their end.
+        let mut tampered = proof.clone();
+        tampered.fri_queried_symbols.pop();
+        corruptions.push(tampered);
+        let mut tampered = proof.clone();
+        tampered.fri_merkle_paths.pop();
+        corruptions.push(tampered);
+        let mut tampered = proof.clone();
+        tampered.fri_merkle_paths[3][2].pop();
+        corruptions.push(tampered);
+
+        for (i, tampered) in corruptions.iter().enumerate() {
+            assert_eq!(run_both(tampered), (false, false), "corruption {i}");
+        }
+    }
+
+    #[test]
+    fn digesting_oracles_test() {
+        let (inst, proof) = prove_random(10, &mut Channel::digesting_oracles());
+        let oracle = &proof.sum_check_oracles[0];
+        assert_eq!(oracle.digest(), Univariate::digest_coeffs(&oracle.coeffs));
+
+        let run = |mut channel: Channel| {
+            verify(
+                &inst.commitment,
+                &inst.point,
+                inst.eval,
+                proof.clone(),
+                &inst.ntt,
+                &mut channel,
+            )
+        };
+        run(Channel::digesting_oracles()).unwrap();
+        assert!(run(Channel::new()).is_err());
+
+        // Oracles in evaluation form are absorbed through the same digest of their coefficients.
+        let evals_proof = proof.clone().into_oracle_form(OracleForm::Evals).unwrap();
+        verify_interleaved(
+            &inst.commitment,
+            &inst.point,
+            inst.eval,
+            &evals_proof,
+            &inst.ntt,
+            &mut Channel::digesting_oracles(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn effective_variables_test() {
+        let block: Vec<BinaryField64b> = (0..1 << 8)
+            .map(|_| BinaryField64b::random(thread_rng()))
+            .collect();
+        // Constant in its top 2 of 16 variables.
+        let inst = instance_of(PackedMLE::new(block.repeat(4), true));
+
+        let (truncated, commitment, code, merkle_tree) =
+            commit_effective(&inst.poly, &inst.ntt).unwrap();
+        assert_eq!(commitment.variables, inst.poly.variables - 2);
+        let short_proof = prove(
+            &truncated,
+            &inst.point[..commitment.variables],
+            inst.eval,
+            &code,
+            &commitment,
+            &merkle_tree,
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        verify_effective(
+            &commitment,
+            &inst.point,
+            inst.eval,
+            short_proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+        assert!(
+            verify_effective(
+                &commitment,
+                &inst.point,
+                inst.eval + BinaryField128b::ONE,
+                short_proof.clone(),
+                &inst.ntt,
+                &mut Channel::new()
+            )
+            .is_err()
+        );
+
+        let full_proof = prove_at(&inst, &inst.point, &mut Channel::new());
+        assert_eq!(
+            short_proof.sum_check_oracles.len() + 2,
+            full_proof.sum_check_oracles.len()
+        );
+        assert!(short_proof.to_bytes().len() < full_proof.to_bytes().len());
+    }
+
+    #[test]
+    fn prove_sum_test() {
+        let inst = random_instance(10);
+        let ctx = prover_context(&inst);
+
+        // Over GF(2) the hypercube sum of the bits is their parity.
+        let ones: u32 = ctx.mle.coeffs.iter().map(|c| c.val().count_ones()).sum();
+        let expected = match ones & 1 {
+            1 => BinaryField128b::ONE,
+            _ => BinaryField128b::ZERO,
+        };
+
+        let (sum, proof) = prove_sum(&ctx, &mut Channel::new()).unwrap();
+        assert_eq!(sum, expected);
+        assert_eq!(proof.round_oracles.len(), 16);
+        verify_sum(
+            &ctx.commitment,
+            sum,
+            proof.clone(),
+            &inst.ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        assert!(
+            verify_sum(
+                &ctx.commitment,
+                sum + BinaryField128b::ONE,
+                proof.clone(),
+                &inst.ntt,
+                &mut Channel::new()
+            )
+            .is_err()
+        );
+        let mut tampered = proof;
+        tampered.round_oracles[3].coeffs[0] += BinaryField128b::ONE;
+        assert!(
+            verify_sum(
+                &ctx.commitment,
+                sum,
+                tampered,
+                &inst.ntt,
+                &mut Channel::new()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn prove_product_test() {
+        let [inst_p, inst_q] = [(); 2].map(|_| random_instance(10));
+        let (ctx_p, ctx_q) = (prover_context(&inst_p), prover_context(&inst_q));
+        let point = &inst_p.point;
+
+        // The product of two bit tables is their AND.
+        let eq = compute_eq(point);
+        let v = (0..eq.len())
+            .filter(|&i| {
+                ctx_p.mle.packed_idx(i) == BinaryField1b::ONE
+                    && ctx_q.mle.packed_idx(i) == BinaryField1b::ONE
+            })
+            .map(|i| eq[i])
+            .sum::<BinaryField128b>();
+
+        let proof = prove_product(&ctx_p, &ctx_q, point, v, &mut Channel::new()).unwrap();
+        assert_eq!(proof.round_oracles.len(), 16);
+
+        let check = |v: BinaryField128b, proof: ProductProof| {
+            verify_product(
+                &ctx_p.commitment,
+                &ctx_q.commitment,
+                point,
+                v,
+                proof,
+                &inst_p.ntt,
+                &mut Channel::new(),
+            )
+        };
+        check(v, proof.clone()).unwrap();
+        assert!(check(v + BinaryField128b::ONE, proof.clone()).is_err());
+
+        let mut tampered = proof;
+        tampered.p_eval += BinaryField128b::ONE;
+        assert!(check(v, tampered).is_err());
+    }
+
+    #[test]
+    fn linear_combination_test() {
+        let insts: Vec<TestInstance> = (0..3).map(|_| random_instance(10)).collect();
+        let ctxs: Vec<_> = insts.iter().map(prover_context).collect();
+        let ntt = &insts[0].ntt;
+        let coeffs = random_point(ctxs.len());
+        let point = random_point(ctxs[0].mle.variables);
+        let target = ctxs
+            .iter()
+            .zip(&coeffs)
+            .map(|(ctx, c)| *c * ctx.evaluate(&point))
+            .sum::<BinaryField128b>();
+
+        let proof =
+            prove_linear_combination(&ctxs, &coeffs, &point, target, &mut Channel::new()).unwrap();
+        let commitments: Vec<FriCommitment> =
+            ctxs.iter().map(|ctx| ctx.commitment.clone()).collect();
+        verify_linear_combination(
+            &commitments,
+            &coeffs,
+            &point,
+            target,
+            &proof,
+            ntt,
+            &mut Channel::new(),
+        )
+        .unwrap();
+
+        let wrong_target = target + BinaryField128b::ONE;
+        assert!(
+            verify_linear_combination(
+                &commitments,
+                &coeffs,
+                &point,
+                wrong_target,
+                &proof,
+                ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+
+        let mut forged = proof.clone();
+        forged.committed_symbols[1][0].0 += BinaryField128b::ONE;
+        assert!(
+            verify_linear_combination(
+                &commitments,
+                &coeffs,
+                &point,
+                target,
+                &forged,
+                ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+
+        // Individual partial evaluations shifted without changing their combination are rejected.
+        let mut shifted = proof.clone();
+        shifted.partial_evals[0][0] += BinaryField128b::ONE;
+        shifted.partial_evals[1][0] += coeffs[0] * coeffs[1].invert().unwrap();
+        assert!(
+            verify_linear_combination(
+                &commitments,
+                &coeffs,
+                &point,
+                target,
+                &shifted,
+                ntt,
+                &mut Channel::new(),
+            )
+            .is_err()
+        );
+
+        // Short paths are rejected before any is hashed, in the commitments and the folded oracles.
+        for (round, mut truncated) in [(0, proof.clone()), (2, proof.clone())] {
+            match round {
+                0 => truncated.committed_paths[1][4].pop(),
+                _ => truncated.combined.fri_merkle_paths[round][4].pop(),
+            };
+            let err = verify_linear_combination(
+                &commitments,
+                &coeffs,
+                &point,
+                target,
+                &truncated,
+                ntt,
+                &mut Channel::new(),
+            )
+            .unwrap_err();
+            assert_eq!(err, VerificationError::MerklePath { round, query: 4 });
+        }
+
+        // The prover splits every point at TAU and refuses other commitments.
+        let mut ctxs = ctxs;
+        ctxs[2].commitment.tau = 5;
+        assert!(
+            prove_linear_combination(&ctxs, &coeffs, &point, target, &mut Channel::new()).is_err()
+        );
+    }
 }